@@ -63,12 +63,15 @@ fn main() -> Result<()> {
 
     // Create camera
     let mut camera = OrbitalCamera::new(
-        Vec3::ZERO,                        // Target
-        15.0,                              // Distance
-        render_context.aspect_ratio(),     // Aspect ratio
+        Vec3::ZERO,                    // Target
+        15.0,                          // Distance
+        render_context.aspect_ratio(), // Aspect ratio
     );
 
-    info!("Camera initialized at distance {} from origin", camera.distance);
+    info!(
+        "Camera initialized at distance {} from origin",
+        camera.distance
+    );
 
     // Input state
     let mut input_state = InputState::new();
@@ -76,7 +79,6 @@ fn main() -> Result<()> {
     // Performance tracking
     let mut frame_count = 0u32;
     let mut fps_timer = Instant::now();
-    let mut last_fps = 0.0f32;
 
     // Main event loop
     event_loop.run(move |event, elwt| {
@@ -97,10 +99,8 @@ fn main() -> Result<()> {
                     }
 
                     // Mouse button events
-                    WindowEvent::MouseInput { state, button, .. } => {
-                        if button == MouseButton::Left {
-                            input_state.mouse_pressed = state == ElementState::Pressed;
-                        }
+                    WindowEvent::MouseInput { state, button: MouseButton::Left, .. } => {
+                        input_state.mouse_pressed = state == ElementState::Pressed;
                     }
 
                     // Mouse movement
@@ -154,13 +154,10 @@ fn main() -> Result<()> {
                                 }
                             }
                         } else if event.state == ElementState::Released {
-                            if let PhysicalKey::Code(keycode) = event.physical_key {
-                                match keycode {
-                                    KeyCode::ShiftLeft | KeyCode::ShiftRight => {
-                                        input_state.shift_pressed = false;
-                                    }
-                                    _ => {}
-                                }
+                            if let PhysicalKey::Code(KeyCode::ShiftLeft | KeyCode::ShiftRight) =
+                                event.physical_key
+                            {
+                                input_state.shift_pressed = false;
                             }
                         }
                     }
@@ -217,7 +214,7 @@ fn main() -> Result<()> {
                                 // Track FPS
                                 frame_count += 1;
                                 if fps_timer.elapsed().as_secs() >= 1 {
-                                    last_fps = frame_count as f32 / fps_timer.elapsed().as_secs_f32();
+                                    let last_fps = frame_count as f32 / fps_timer.elapsed().as_secs_f32();
                                     info!(
                                         "FPS: {:.1} | Camera: pos={:.2?}, distance={:.2}, yaw={:.2}, pitch={:.2}",
                                         last_fps,
@@ -5,8 +5,8 @@ use std::sync::Arc;
 use std::time::Instant;
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
-use viz_core::{Dataset, OrbitalCamera, PointCloud, RenderContext};
-use viz_plots::Scatter3D;
+use viz_core::{Dataset, OrbitalCamera, PointCloud, RenderContext, Transform};
+use viz_plots::{Glyph3D, GlyphStyle, Scene};
 use winit::{
     event::{ElementState, Event, MouseButton, MouseScrollDelta, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
@@ -51,7 +51,7 @@ fn generate_spiral_points(num_points: usize) -> PointCloud {
     cloud.with_name("Spiral")
 }
 
-/// Generate a cube of random points
+/// Generate a cube of random points with a swirling velocity field
 fn generate_cube_points(num_points: usize) -> PointCloud {
     use rand::Rng;
     let mut rng = rand::thread_rng();
@@ -64,7 +64,13 @@ fn generate_cube_points(num_points: usize) -> PointCloud {
         positions.push(Vec3::new(x, y, z));
     }
 
-    let mut cloud = PointCloud::new(positions);
+    // Rotation about the y axis with a slight upward drift
+    let velocity = positions
+        .iter()
+        .map(|p| Vec3::new(-p.z, 0.5, p.x))
+        .collect();
+
+    let mut cloud = PointCloud::new(positions).with_vectors("velocity", velocity);
     cloud.generate_height_colors();
     cloud.with_name("Random Cube")
 }
@@ -82,8 +88,9 @@ fn main() -> Result<()> {
     info!("  - Shift + Left Mouse Drag: Pan camera");
     info!("  - Mouse Wheel: Zoom in/out");
     info!("  - R: Reset camera");
-    info!("  - 1: Spiral (1000 points)");
-    info!("  - 2: Cube (10000 points)");
+    info!("  - 1: Show/hide spiral (1000 points)");
+    info!("  - 2: Show/hide cube (10000 points)");
+    info!("  - 3: Show/hide cube velocity glyphs (hidden with the cube)");
     info!("  - ESC: Exit");
 
     // Create event loop and window
@@ -104,23 +111,36 @@ fn main() -> Result<()> {
     );
 
     // Create camera
-    let mut camera = OrbitalCamera::new(
-        Vec3::ZERO,
-        20.0,
-        render_context.aspect_ratio(),
-    );
-
-    // Generate initial point cloud (spiral)
-    let point_cloud = generate_spiral_points(1000);
-    info!("Generated {} points in spiral pattern", point_cloud.len());
-
-    // Create scatter plot
-    let mut scatter = Scatter3D::new(&render_context, &point_cloud)?;
-    info!("Scatter plot initialized with {} points", scatter.point_count());
+    let mut camera = OrbitalCamera::new(Vec3::ZERO, 20.0, render_context.aspect_ratio());
+
+    // Generate the spiral and a cube beside it
+    let spiral = generate_spiral_points(1000);
+    let cube = generate_cube_points(10000);
+    info!("Generated {} + {} points", spiral.len(), cube.len());
+
+    // Arrows for the cube's velocity, one per 1.5 unit cell
+    let cube_transform = Transform::from_translation(Vec3::new(15.0, 0.0, 0.0));
+    let mut glyphs = Glyph3D::new(
+        &render_context,
+        &cube,
+        "velocity",
+        GlyphStyle::new().with_spacing(1.5).with_length(1.2),
+    )?;
+    glyphs.set_transform(cube_transform);
+    let mut show_glyphs = true;
+    info!("Drawing {} velocity glyphs", glyphs.glyph_count());
+
+    // Create a scene with one scatter plot per dataset
+    let mut scene = Scene::new();
+    let spiral = scene.add(&render_context, spiral)?;
+    let cube = scene.add(&render_context, cube)?;
+    if let Some(item) = scene.item_mut(cube) {
+        item.set_transform(cube_transform);
+    }
+    info!("Scene initialized with {} points", scene.visible_count());
 
-    // Frame the camera to see all points
-    let bounds = point_cloud.bounds();
-    camera.frame_bounds(bounds.min, bounds.max, 0.2);
+    // Frame the camera to see all datasets
+    scene.frame(&mut camera, 0.2);
 
     // Input state
     let mut input_state = InputState::new();
@@ -146,10 +166,12 @@ fn main() -> Result<()> {
                         camera.set_aspect(render_context.aspect_ratio());
                     }
 
-                    WindowEvent::MouseInput { state, button, .. } => {
-                        if button == MouseButton::Left {
-                            input_state.mouse_pressed = state == ElementState::Pressed;
-                        }
+                    WindowEvent::MouseInput {
+                        state,
+                        button: MouseButton::Left,
+                        ..
+                    } => {
+                        input_state.mouse_pressed = state == ElementState::Pressed;
                     }
 
                     WindowEvent::CursorMoved { position, .. } => {
@@ -191,19 +213,29 @@ fn main() -> Result<()> {
                                         camera.reset();
                                         camera.distance = 20.0;
                                     }
-                                    KeyCode::Digit1 => {
-                                        info!("Switching to spiral (1000 points)");
-                                        let point_cloud = generate_spiral_points(1000);
-                                        scatter = Scatter3D::new(&render_context, &point_cloud).unwrap();
-                                        let bounds = point_cloud.bounds();
-                                        camera.frame_bounds(bounds.min, bounds.max, 0.2);
+                                    KeyCode::Digit1 | KeyCode::Digit2 => {
+                                        let index = if keycode == KeyCode::Digit1 {
+                                            spiral
+                                        } else {
+                                            cube
+                                        };
+                                        if let Some(item) = scene.item_mut(index) {
+                                            item.set_visible(!item.is_visible());
+                                            info!(
+                                                "{} {}",
+                                                if item.is_visible() {
+                                                    "Showing"
+                                                } else {
+                                                    "Hiding"
+                                                },
+                                                item.name()
+                                            );
+                                        }
+                                        scene.frame(&mut camera, 0.2);
                                     }
-                                    KeyCode::Digit2 => {
-                                        info!("Switching to cube (10000 points)");
-                                        let point_cloud = generate_cube_points(10000);
-                                        scatter = Scatter3D::new(&render_context, &point_cloud).unwrap();
-                                        let bounds = point_cloud.bounds();
-                                        camera.frame_bounds(bounds.min, bounds.max, 0.2);
+                                    KeyCode::Digit3 => {
+                                        show_glyphs = !show_glyphs;
+                                        info!("Glyphs: {}", if show_glyphs { "on" } else { "off" });
                                     }
                                     KeyCode::ShiftLeft | KeyCode::ShiftRight => {
                                         input_state.shift_pressed = true;
@@ -222,7 +254,8 @@ fn main() -> Result<()> {
 
                     WindowEvent::RedrawRequested => {
                         // Update camera uniforms
-                        scatter.update_camera(&render_context, &camera);
+                        scene.update_camera(&render_context, &camera);
+                        glyphs.update_camera(&render_context, &camera);
 
                         // Render
                         match render_context.get_current_texture() {
@@ -231,11 +264,11 @@ fn main() -> Result<()> {
                                     .texture
                                     .create_view(&wgpu::TextureViewDescriptor::default());
 
-                                let mut encoder = render_context
-                                    .device
-                                    .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                                let mut encoder = render_context.device.create_command_encoder(
+                                    &wgpu::CommandEncoderDescriptor {
                                         label: Some("Render Encoder"),
-                                    });
+                                    },
+                                );
 
                                 {
                                     let mut render_pass =
@@ -261,21 +294,31 @@ fn main() -> Result<()> {
                                             occlusion_query_set: None,
                                         });
 
-                                    // Render scatter plot!
-                                    scatter.render(&mut render_pass);
+                                    // Render every visible dataset
+                                    scene.render(&mut render_pass);
+
+                                    // The glyphs belong to the cube and hide with it
+                                    let cube_visible =
+                                        scene.item(cube).is_some_and(|item| item.is_visible());
+                                    if show_glyphs && cube_visible {
+                                        glyphs.render(&mut render_pass);
+                                    }
                                 }
 
-                                render_context.queue.submit(std::iter::once(encoder.finish()));
+                                render_context
+                                    .queue
+                                    .submit(std::iter::once(encoder.finish()));
                                 output.present();
 
                                 // Track FPS
                                 frame_count += 1;
                                 if fps_timer.elapsed().as_secs() >= 1 {
-                                    let fps = frame_count as f32 / fps_timer.elapsed().as_secs_f32();
+                                    let fps =
+                                        frame_count as f32 / fps_timer.elapsed().as_secs_f32();
                                     info!(
                                         "FPS: {:.1} | Points: {} | Camera distance: {:.1}",
                                         fps,
-                                        scatter.point_count(),
+                                        scene.visible_count(),
                                         camera.distance
                                     );
                                     frame_count = 0;
@@ -5,7 +5,7 @@ use std::sync::Arc;
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 use viz_core::{
-    performance_panel, Colormap, ControlPanel, Dataset, Inferno, OrbitalCamera,
+    performance_panel, Colormap, ControlPanel, Dataset, FieldInfo, Inferno, OrbitalCamera,
     PerformanceMetrics, Plasma, PointCloud, RenderContext, ScaleType, Turbo, UiContext, Viridis,
 };
 use viz_plots::Scatter3D;
@@ -54,7 +54,18 @@ fn generate_spiral_points(num_points: usize) -> PointCloud {
 
     let mut cloud = PointCloud::new(positions)
         .with_metadata("height".to_string(), heights)
-        .with_metadata("radius".to_string(), radii);
+        .with_metadata("radius".to_string(), radii)
+        .with_field_info(
+            "height",
+            FieldInfo::new().with_long_name("Height").with_unit("m"),
+        )
+        .with_field_info(
+            "radius",
+            FieldInfo::new()
+                .with_long_name("Radius")
+                .with_unit("m")
+                .with_description("Distance from the spiral axis"),
+        );
 
     // Apply Viridis colormap by default to height field
     cloud
@@ -85,7 +96,18 @@ fn generate_cube_points(num_points: usize) -> PointCloud {
 
     let mut cloud = PointCloud::new(positions)
         .with_metadata("distance".to_string(), distances)
-        .with_metadata("height".to_string(), heights);
+        .with_metadata("height".to_string(), heights)
+        .with_field_info(
+            "distance",
+            FieldInfo::new()
+                .with_long_name("Distance")
+                .with_unit("m")
+                .with_description("Distance from the cube centre"),
+        )
+        .with_field_info(
+            "height",
+            FieldInfo::new().with_long_name("Height").with_unit("m"),
+        );
 
     // Apply Plasma colormap to distance field
     cloud
@@ -139,7 +161,7 @@ fn main() -> Result<()> {
     let mut camera = OrbitalCamera::new(Vec3::ZERO, 20.0, render_context.aspect_ratio());
 
     // Generate datasets
-    let datasets = vec![generate_spiral_points(1000), generate_cube_points(10000)];
+    let datasets = [generate_spiral_points(1000), generate_cube_points(10000)];
     let dataset_names: Vec<String> = datasets.iter().map(|d| d.name().to_string()).collect();
 
     // Create scatter plot with first dataset
@@ -190,10 +212,12 @@ fn main() -> Result<()> {
                             camera.set_aspect(render_context.aspect_ratio());
                         }
 
-                        WindowEvent::MouseInput { state, button, .. } => {
-                            if *button == MouseButton::Left {
-                                input_state.mouse_pressed = *state == ElementState::Pressed;
-                            }
+                        WindowEvent::MouseInput {
+                            state,
+                            button: MouseButton::Left,
+                            ..
+                        } => {
+                            input_state.mouse_pressed = *state == ElementState::Pressed;
                         }
 
                         WindowEvent::CursorMoved { position, .. } => {
@@ -214,14 +238,14 @@ fn main() -> Result<()> {
                             input_state.last_mouse_pos = current_pos;
                         }
 
-                        WindowEvent::MouseWheel { delta, .. } => {
-                            if !ui_context.wants_pointer_input() {
-                                let zoom_delta = match delta {
-                                    MouseScrollDelta::LineDelta(_, y) => *y * 0.1,
-                                    MouseScrollDelta::PixelDelta(pos) => pos.y as f32 * 0.001,
-                                };
-                                camera.zoom(zoom_delta);
-                            }
+                        WindowEvent::MouseWheel { delta, .. }
+                            if !ui_context.wants_pointer_input() =>
+                        {
+                            let zoom_delta = match delta {
+                                MouseScrollDelta::LineDelta(_, y) => *y * 0.1,
+                                MouseScrollDelta::PixelDelta(pos) => pos.y as f32 * 0.001,
+                            };
+                            camera.zoom(zoom_delta);
                         }
 
                         WindowEvent::KeyboardInput { event, .. } => {
@@ -236,12 +260,16 @@ fn main() -> Result<()> {
                                             info!("Resetting camera");
                                             camera.reset();
                                             camera.distance = 20.0;
-                                            let bounds = datasets[control_panel.dataset_index].bounds();
+                                            let bounds =
+                                                datasets[control_panel.dataset_index].bounds();
                                             camera.frame_bounds(bounds.min, bounds.max, 0.2);
                                         }
                                         KeyCode::KeyH => {
                                             show_ui = !show_ui;
-                                            info!("UI {}", if show_ui { "shown" } else { "hidden" });
+                                            info!(
+                                                "UI {}",
+                                                if show_ui { "shown" } else { "hidden" }
+                                            );
                                         }
                                         KeyCode::ShiftLeft | KeyCode::ShiftRight => {
                                             input_state.shift_pressed = true;
@@ -274,9 +302,15 @@ fn main() -> Result<()> {
                                 let old_point_size = control_panel.point_size;
                                 let old_colormap = control_panel.colormap_index;
                                 let old_log_scale = control_panel.use_log_scale;
+                                let old_field = control_panel.metadata_field.clone();
 
-                                let dataset_refs: Vec<&str> = dataset_names.iter().map(|s| s.as_str()).collect();
-                                control_panel.show(&ctx, &dataset_refs);
+                                let dataset_refs: Vec<&str> =
+                                    dataset_names.iter().map(|s| s.as_str()).collect();
+                                control_panel.show(
+                                    &ctx,
+                                    &dataset_refs,
+                                    Some(&datasets[control_panel.dataset_index]),
+                                );
 
                                 // Handle dataset change
                                 if control_panel.dataset_index != old_dataset {
@@ -284,9 +318,10 @@ fn main() -> Result<()> {
                                         "Switching to dataset: {}",
                                         datasets[control_panel.dataset_index].name()
                                     );
-                                    scatter =
-                                        Scatter3D::new(&render_context, &datasets[control_panel.dataset_index])
-                                            .unwrap();
+                                    scatter.upload(
+                                        &render_context,
+                                        &datasets[control_panel.dataset_index],
+                                    );
                                     let bounds = datasets[control_panel.dataset_index].bounds();
                                     camera.frame_bounds(bounds.min, bounds.max, 0.2);
                                 }
@@ -297,8 +332,12 @@ fn main() -> Result<()> {
                                 }
 
                                 // Handle colormap or scale change
-                                if control_panel.colormap_index != old_colormap || control_panel.use_log_scale != old_log_scale {
-                                    let colormap: &dyn Colormap = match control_panel.colormap_index {
+                                if control_panel.colormap_index != old_colormap
+                                    || control_panel.use_log_scale != old_log_scale
+                                    || control_panel.metadata_field != old_field
+                                {
+                                    let colormap: &dyn Colormap = match control_panel.colormap_index
+                                    {
                                         0 => &Viridis,
                                         1 => &Plasma,
                                         2 => &Inferno,
@@ -311,15 +350,29 @@ fn main() -> Result<()> {
                                         ScaleType::Linear
                                     };
 
-                                    // Apply colormap to the current dataset's first metadata field
-                                    let mut dataset_clone = datasets[control_panel.dataset_index].clone();
+                                    // Apply colormap to the selected (or else first) metadata field
+                                    let mut dataset_clone =
+                                        datasets[control_panel.dataset_index].clone();
                                     let metadata_keys = dataset_clone.metadata_keys();
                                     if !metadata_keys.is_empty() {
-                                        let field = metadata_keys[0].clone();
-                                        dataset_clone.apply_colormap(&field, colormap, scale_type).ok();
-                                        scatter = Scatter3D::new(&render_context, &dataset_clone).unwrap();
-                                        info!("Applied {} colormap with {:?} scale to field '{}'",
-                                              colormap.name(), scale_type, field);
+                                        let field = if dataset_clone
+                                            .metadata(&control_panel.metadata_field)
+                                            .is_some()
+                                        {
+                                            control_panel.metadata_field.clone()
+                                        } else {
+                                            metadata_keys[0].clone()
+                                        };
+                                        dataset_clone
+                                            .apply_colormap(&field, colormap, scale_type)
+                                            .ok();
+                                        scatter.upload(&render_context, &dataset_clone);
+                                        info!(
+                                            "Applied {} colormap with {:?} scale to field '{}'",
+                                            colormap.name(),
+                                            scale_type,
+                                            field
+                                        );
                                     }
                                 }
                             }
@@ -342,8 +395,8 @@ fn main() -> Result<()> {
 
                                     // Render 3D scene
                                     {
-                                        let mut render_pass =
-                                            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                                        let mut render_pass = encoder.begin_render_pass(
+                                            &wgpu::RenderPassDescriptor {
                                                 label: Some("3D Render Pass"),
                                                 color_attachments: &[Some(
                                                     wgpu::RenderPassColorAttachment {
@@ -351,7 +404,8 @@ fn main() -> Result<()> {
                                                         resolve_target: None,
                                                         ops: wgpu::Operations {
                                                             load: wgpu::LoadOp::Clear(
-                                                                control_panel.background_wgpu_color(),
+                                                                control_panel
+                                                                    .background_wgpu_color(),
                                                             ),
                                                             store: wgpu::StoreOp::Store,
                                                         },
@@ -360,7 +414,8 @@ fn main() -> Result<()> {
                                                 depth_stencil_attachment: None,
                                                 timestamp_writes: None,
                                                 occlusion_query_set: None,
-                                            });
+                                            },
+                                        );
 
                                         scatter.render(&mut render_pass);
                                     }
@@ -380,7 +435,9 @@ fn main() -> Result<()> {
                                             .unwrap();
                                     }
 
-                                    render_context.queue.submit(std::iter::once(encoder.finish()));
+                                    render_context
+                                        .queue
+                                        .submit(std::iter::once(encoder.finish()));
                                     output.present();
                                 }
                                 Err(e) => {
@@ -1,5 +1,1181 @@
-// Main application - will implement in Phase 4+
-fn main() {
-    println!("Scientific Visualizer - Desktop Application");
-    println!("Run examples with: cargo run --example basic_window");
+use anyhow::Result;
+use glam::{EulerRot, Quat, Vec3};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::runtime::Handle;
+use tokio::sync::oneshot;
+use tracing::{info, warn, Level};
+use tracing_subscriber::FmtSubscriber;
+use viz_core::io::{BackgroundLoader, LoadHandle};
+use viz_core::ui::loading_panel;
+use viz_core::{
+    colorbar_panel, legend_panel, performance_panel, stats_panel, Binning, ColorRange, Colormap,
+    ControlPanel, Dataset, FieldStats, Histogram, Inferno, Legend, NormalOrientation,
+    OrbitalCamera, PerformanceMetrics, Plasma, PointCloud, RenderContext, ScaleType, Tab10,
+    TimeSeries, Timeline, Transform, Turbo, UiContext, Viridis,
+};
+use viz_plots::{Scene, SceneItem, Shading};
+use winit::{
+    event::{ElementState, Event, MouseButton, MouseScrollDelta, WindowEvent},
+    event_loop::{ControlFlow, EventLoop},
+    keyboard::{KeyCode, PhysicalKey},
+    window::WindowBuilder,
+};
+
+/// Input state for camera controls
+struct InputState {
+    mouse_pressed: bool,
+    last_mouse_pos: (f32, f32),
+    shift_pressed: bool,
+}
+
+impl InputState {
+    fn new() -> Self {
+        Self {
+            mouse_pressed: false,
+            last_mouse_pos: (0.0, 0.0),
+            shift_pressed: false,
+        }
+    }
+}
+
+/// Neighbours used when estimating normals for lit shading
+const NORMAL_NEIGHBORS: usize = 16;
+
+/// Time series frames loaded in the background from the current one on
+const PREFETCH_FRAMES: usize = 4;
+
+/// Colormap of a dataset, reapplied to each frame of a time series
+#[derive(Clone)]
+struct Coloring {
+    field: String,
+    colormap: &'static dyn Colormap,
+    scale_type: ScaleType,
+    /// Value range resolved when the coloring was chosen, so a color means
+    /// the same value in every frame
+    min: f32,
+    max: f32,
+}
+
+impl Coloring {
+    /// Color a cloud; returns false if it lacks the field
+    fn apply(&self, cloud: &mut PointCloud) -> bool {
+        let range = ColorRange::Fixed {
+            min: self.min,
+            max: self.max,
+        };
+        cloud
+            .apply_colormap_range(&self.field, self.colormap, self.scale_type, range)
+            .is_ok()
+    }
+}
+
+/// A dataset opened in the viewer; its cloud and plot live in the scene
+struct OpenDataset {
+    /// File the dataset was loaded from
+    path: PathBuf,
+    /// Legend for the default coloring, when it is categorical
+    legend: Option<Legend>,
+    /// Colormap on a numeric field, chosen or by default
+    coloring: Option<Coloring>,
+    /// Frames when a directory was opened; the scene holds the current one
+    series: Option<TimeSeries>,
+    /// Normals estimated for series frames, kept when a frame is evicted
+    frame_normals: HashMap<usize, Vec<Vec3>>,
+    /// Series frames that failed to load, which are not retried
+    failed_frames: HashSet<usize>,
+    /// The series moved to frames still loading, to show once they arrive
+    frame_pending: bool,
+}
+
+impl OpenDataset {
+    fn new(path: PathBuf, series: Option<TimeSeries>) -> Self {
+        Self {
+            path,
+            legend: None,
+            coloring: None,
+            frame_pending: series.is_some(),
+            series,
+            frame_normals: HashMap::new(),
+            failed_frames: HashSet::new(),
+        }
+    }
+}
+
+/// A time series frame loading in the background
+struct FrameLoad {
+    dataset: usize,
+    frame: usize,
+    handle: LoadHandle,
+}
+
+/// Normals being estimated in the background for a dataset, or for one
+/// frame of its time series
+struct NormalJob {
+    dataset: usize,
+    frame: Option<usize>,
+    result: oneshot::Receiver<Vec<Vec3>>,
+}
+
+/// Datasets, pending loads and the scene drawing them
+struct Viewer {
+    runtime: Handle,
+    loader: BackgroundLoader,
+    loads: Vec<LoadHandle>,
+    frame_loads: Vec<FrameLoad>,
+    normal_jobs: Vec<NormalJob>,
+    /// Open datasets, in the order of the scene's items
+    datasets: Vec<OpenDataset>,
+    scene: Scene,
+    /// Dataset the control panel colors and the timeline plays
+    active: Option<usize>,
+    /// Legend for the colors currently shown
+    legend: Option<Legend>,
+    /// Label and statistics of the field the colormap was last applied to
+    stats: Option<(String, FieldStats, Histogram)>,
+    /// Label, value range and scale of the colormapped field
+    colorbar: Option<(String, f32, f32, ScaleType)>,
+    shading: Shading,
+    last_error: Option<String>,
+    /// Datasets opened since the last poll, waiting to join the scene
+    opened: Vec<(OpenDataset, PointCloud)>,
+}
+
+impl Viewer {
+    fn new(runtime: Handle) -> Self {
+        Self {
+            loader: BackgroundLoader::new(runtime.clone()),
+            runtime,
+            loads: Vec::new(),
+            frame_loads: Vec::new(),
+            normal_jobs: Vec::new(),
+            datasets: Vec::new(),
+            scene: Scene::new(),
+            active: None,
+            legend: None,
+            stats: None,
+            colorbar: None,
+            shading: Shading::Flat,
+            last_error: None,
+            opened: Vec::new(),
+        }
+    }
+
+    /// Start loading a file in the background, or open a directory as a
+    /// time series with one frame per file
+    fn open(&mut self, path: PathBuf) {
+        info!("Opening {}", path.display());
+        if path.is_dir() {
+            self.open_series(path);
+        } else {
+            self.loads.push(self.loader.spawn(path));
+        }
+    }
+
+    /// Open a directory of frames; they load in the background like files,
+    /// with the dataset empty until the first one arrives
+    fn open_series(&mut self, path: PathBuf) {
+        let series = match TimeSeries::from_directory(&path) {
+            Ok(series) => series,
+            Err(err) => {
+                warn!("Failed to open {}: {}", path.display(), err);
+                self.last_error = Some(format!("{}: {}", path.display(), err));
+                return;
+            }
+        };
+        info!(
+            "Opened {} frames from {}",
+            series.frame_count(),
+            path.display()
+        );
+
+        let cloud = PointCloud::new(Vec::new()).with_name(series.name());
+        self.opened
+            .push((OpenDataset::new(path, Some(series)), cloud));
+    }
+
+    /// Add finished loads to the scene; returns the index of a dataset to
+    /// activate
+    fn poll_loads(&mut self, context: &RenderContext, point_size: f32) -> Option<usize> {
+        let mut opened = std::mem::take(&mut self.opened);
+
+        let mut pending = Vec::with_capacity(self.loads.len());
+        for mut handle in self.loads.drain(..) {
+            match handle.try_finish() {
+                None => pending.push(handle),
+                Some(Ok(cloud)) => {
+                    let (cloud, legend, coloring) = with_default_colors(cloud);
+                    let mut dataset = OpenDataset::new(handle.path().to_path_buf(), None);
+                    dataset.legend = legend;
+                    dataset.coloring = coloring;
+                    opened.push((dataset, cloud));
+                }
+                Some(Err(err)) if handle.is_cancelled() => {
+                    info!("Cancelled loading {}: {}", handle.path().display(), err);
+                }
+                Some(Err(err)) => {
+                    warn!("Failed to load {}: {}", handle.path().display(), err);
+                    self.last_error = Some(format!("{}: {}", handle.path().display(), err));
+                }
+            }
+        }
+        self.loads = pending;
+
+        let mut activate = None;
+        for (dataset, cloud) in opened {
+            activate = self
+                .insert(context, dataset, cloud, point_size)
+                .or(activate);
+        }
+        activate
+    }
+
+    /// Store time series frames that finished loading and show the series
+    /// waiting for them; returns true if one showed its first frame
+    fn poll_frames(&mut self, context: &RenderContext) -> bool {
+        let mut pending = Vec::with_capacity(self.frame_loads.len());
+        let mut unlit = Vec::new();
+        for mut load in std::mem::take(&mut self.frame_loads) {
+            let dataset = &mut self.datasets[load.dataset];
+            match load.handle.try_finish() {
+                None => pending.push(load),
+                Some(Ok(cloud)) => {
+                    if let Some(series) = &mut dataset.series {
+                        if self.shading == Shading::Lit
+                            && cloud.normals().is_none()
+                            && !dataset.frame_normals.contains_key(&load.frame)
+                        {
+                            unlit.push((load.dataset, load.frame, cloud.positions().to_vec()));
+                        }
+                        series.store_frame(load.frame, cloud);
+                    }
+                }
+                Some(Err(err)) => {
+                    warn!(
+                        "Failed to load frame {}: {}",
+                        load.handle.path().display(),
+                        err
+                    );
+                    self.last_error = Some(format!("{}: {}", load.handle.path().display(), err));
+                    dataset.failed_frames.insert(load.frame);
+                }
+            }
+        }
+        self.frame_loads = pending;
+        for (dataset, frame, positions) in unlit {
+            self.estimate_normals(dataset, Some(frame), positions);
+        }
+
+        let mut first = false;
+        for index in 0..self.datasets.len() {
+            let dataset = &self.datasets[index];
+            if dataset.frame_pending || (self.active == Some(index) && dataset.series.is_some()) {
+                self.prefetch(index);
+            }
+            if self.datasets[index].frame_pending {
+                first |= self.show_frame(context, index);
+            }
+        }
+        first
+    }
+
+    /// Load the frames a time series shows now and next in the background
+    fn prefetch(&mut self, index: usize) {
+        let dataset = &self.datasets[index];
+        let Some(series) = &dataset.series else {
+            return;
+        };
+
+        // Looping playback wraps around to the first frames
+        let count = series.frame_count();
+        let start = series.current_frames().start;
+        for frame in (start..start + PREFETCH_FRAMES.min(count)).map(|frame| frame % count) {
+            let loading = self
+                .frame_loads
+                .iter()
+                .any(|load| load.dataset == index && load.frame == frame);
+            if loading || series.is_loaded(frame) || dataset.failed_frames.contains(&frame) {
+                continue;
+            }
+            if let Some(path) = series.frame_path(frame) {
+                let handle = self.loader.spawn(path);
+                self.frame_loads.push(FrameLoad {
+                    dataset: index,
+                    frame,
+                    handle,
+                });
+            }
+        }
+    }
+
+    /// Add a dataset to the scene; returns its index
+    fn insert(
+        &mut self,
+        context: &RenderContext,
+        dataset: OpenDataset,
+        cloud: PointCloud,
+        point_size: f32,
+    ) -> Option<usize> {
+        info!("Adding dataset: {} ({} points)", cloud.name(), cloud.len());
+        let unlit = self.shading == Shading::Lit
+            && dataset.series.is_none()
+            && cloud.normals().is_none()
+            && !cloud.is_empty();
+        let positions = unlit.then(|| cloud.positions().to_vec());
+
+        // Reopening a file replaces the earlier copy, keeping its name,
+        // visibility and transform
+        let index = if let Some(index) = self.datasets.iter().position(|d| d.path == dataset.path) {
+            self.scene.item_mut(index)?.set_cloud(context, cloud);
+            self.datasets[index] = dataset;
+            self.frame_loads.retain(|load| load.dataset != index);
+            self.normal_jobs.retain(|job| job.dataset != index);
+            index
+        } else {
+            match self.scene.add(context, cloud) {
+                Ok(index) => {
+                    let plot = self.scene.item_mut(index)?.plot_mut();
+                    plot.set_point_size(point_size);
+                    plot.set_shading(context, self.shading);
+                    self.datasets.push(dataset);
+                    index
+                }
+                Err(err) => {
+                    self.last_error = Some(format!("{}: {}", dataset.path.display(), err));
+                    return None;
+                }
+            }
+        };
+
+        if let Some(positions) = positions {
+            self.estimate_normals(index, None, positions);
+        }
+        Some(index)
+    }
+
+    /// Start estimating normals for a dataset (or one of its frames) on the
+    /// blocking pool, unless that is already under way
+    fn estimate_normals(&mut self, dataset: usize, frame: Option<usize>, positions: Vec<Vec3>) {
+        let running = self
+            .normal_jobs
+            .iter()
+            .any(|job| job.dataset == dataset && job.frame == frame);
+        if running {
+            return;
+        }
+
+        let (sender, result) = oneshot::channel();
+        self.runtime.spawn_blocking(move || {
+            info!("Estimating normals for {} points", positions.len());
+            let cloud = PointCloud::new(positions)
+                .with_estimated_normals(NORMAL_NEIGHBORS, NormalOrientation::Propagate);
+            // The viewer may have moved on; nobody is waiting then
+            let _ = sender.send(cloud.normals().map(<[Vec3]>::to_vec).unwrap_or_default());
+        });
+        self.normal_jobs.push(NormalJob {
+            dataset,
+            frame,
+            result,
+        });
+    }
+
+    /// Attach normals that finished estimating; series frames keep theirs
+    /// until shown
+    fn poll_normals(&mut self, context: &RenderContext) {
+        for mut job in std::mem::take(&mut self.normal_jobs) {
+            let normals = match job.result.try_recv() {
+                Ok(normals) => normals,
+                Err(oneshot::error::TryRecvError::Empty) => {
+                    self.normal_jobs.push(job);
+                    continue;
+                }
+                Err(oneshot::error::TryRecvError::Closed) => {
+                    warn!("Normal estimation stopped unexpectedly");
+                    continue;
+                }
+            };
+
+            match job.frame {
+                Some(frame) => {
+                    self.datasets[job.dataset]
+                        .frame_normals
+                        .insert(frame, normals);
+                }
+                None => {
+                    // The cloud may have been recolored meanwhile, so the
+                    // normals go onto whatever is shown now
+                    let Some(item) = self.scene.item_mut(job.dataset) else {
+                        continue;
+                    };
+                    let cloud = item.cloud();
+                    if cloud.normals().is_none() && cloud.len() == normals.len() {
+                        let cloud = cloud.clone().with_normals(normals);
+                        item.set_cloud(context, cloud);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Make a dataset the one the control panel and timeline act on
+    fn activate(&mut self, index: usize) {
+        self.legend = self.datasets[index].legend.clone();
+        self.stats = None;
+        self.colorbar = None;
+        self.active = Some(index);
+    }
+
+    /// Color the active dataset by a field, or else its first numeric one
+    ///
+    /// The range is resolved on the frame shown and kept for the others, so
+    /// the colorbar and statistics stay valid while a time series plays.
+    fn recolor(
+        &mut self,
+        context: &RenderContext,
+        field: &str,
+        colormap: &'static dyn Colormap,
+        scale_type: ScaleType,
+        range: ColorRange,
+    ) {
+        let Some(index) = self.active else {
+            return;
+        };
+        let Some(item) = self.scene.item_mut(index) else {
+            return;
+        };
+
+        // The copy keeps the cloud's cached field statistics
+        let mut cloud = item.cloud().clone();
+        let field = if cloud.metadata(field).is_some() {
+            field.to_string()
+        } else {
+            let mut keys: Vec<String> = cloud.metadata_keys().into_iter().cloned().collect();
+            keys.sort();
+            match keys.into_iter().next() {
+                Some(field) => field,
+                None => return,
+            }
+        };
+
+        let label = cloud.field_label(&field);
+        if let Ok((min, max)) = cloud.apply_colormap_range(&field, colormap, scale_type, range) {
+            let stats = cloud.field_stats(&field).ok().cloned();
+            self.stats = stats.map(|stats| {
+                let histogram = stats.histogram_in(Binning::Auto, min, max);
+                (label.clone(), stats, histogram)
+            });
+            self.colorbar = Some((label, min, max, scale_type));
+            self.datasets[index].coloring = Some(Coloring {
+                field: field.clone(),
+                colormap,
+                scale_type,
+                min,
+                max,
+            });
+        }
+        item.set_cloud(context, cloud);
+        self.datasets[index].legend = None;
+        self.legend = None;
+        info!(
+            "Applied {} colormap with {:?} scale to field '{}'",
+            colormap.name(),
+            scale_type,
+            field
+        );
+    }
+
+    /// Set the point size of every dataset
+    fn set_point_size(&mut self, point_size: f32) {
+        for item in self.scene.items_mut() {
+            item.plot_mut().set_point_size(point_size);
+        }
+    }
+
+    /// Switch shading, estimating normals in the background for datasets
+    /// without any
+    fn set_shading(&mut self, context: &RenderContext, shading: Shading) {
+        self.shading = shading;
+        let mut unlit = Vec::new();
+        for (index, (item, dataset)) in self
+            .scene
+            .items_mut()
+            .iter_mut()
+            .zip(&mut self.datasets)
+            .enumerate()
+        {
+            // Normals live in the vertex buffer, so they are uploaded once
+            // estimated; a time series gets them per frame when its frame is
+            // reshown
+            if shading == Shading::Lit && item.cloud().normals().is_none() {
+                if dataset.series.is_some() {
+                    dataset.frame_pending = true;
+                } else if !item.cloud().is_empty() {
+                    unlit.push((index, item.cloud().positions().to_vec()));
+                }
+            }
+            item.plot_mut().set_shading(context, shading);
+        }
+        for (index, positions) in unlit {
+            self.estimate_normals(index, None, positions);
+        }
+    }
+
+    /// Show the active time series at its new time
+    fn show_active_frame(&mut self, context: &RenderContext) {
+        if let Some(index) = self.active {
+            self.prefetch(index);
+            self.show_frame(context, index);
+        }
+    }
+
+    /// Show a time series at its current time once its frames have loaded,
+    /// colored like the frame before; returns true if it was empty before
+    fn show_frame(&mut self, context: &RenderContext, index: usize) -> bool {
+        let lit = self.shading == Shading::Lit;
+        let dataset = &mut self.datasets[index];
+        let Some(series) = &mut dataset.series else {
+            return false;
+        };
+
+        // Until the frames (and when lit, their normals) arrive the previous
+        // one stays on screen
+        let frames = series.current_frames();
+        dataset.frame_pending = frames.clone().any(|frame| !series.is_loaded(frame));
+        if dataset.frame_pending {
+            return false;
+        }
+        if lit {
+            let unlit: Vec<(usize, Vec<Vec3>)> = frames
+                .filter_map(|frame| {
+                    let positions = add_frame_normals(series, &dataset.frame_normals, frame)?;
+                    Some((frame, positions))
+                })
+                .collect();
+            if !unlit.is_empty() {
+                dataset.frame_pending = true;
+                for (frame, positions) in unlit {
+                    self.estimate_normals(index, Some(frame), positions);
+                }
+                return false;
+            }
+        }
+
+        let mut cloud = match series.current() {
+            Ok(cloud) => Arc::unwrap_or_clone(cloud).with_name(series.name()),
+            Err(err) => {
+                warn!("Failed to load frame of {}: {}", series.name(), err);
+                self.last_error = Some(format!("{}: {}", series.name(), err));
+                return false;
+            }
+        };
+        let colored = dataset
+            .coloring
+            .as_ref()
+            .is_some_and(|coloring| coloring.apply(&mut cloud));
+        if !colored {
+            let (default, legend, coloring) = with_default_colors(cloud);
+            cloud = default;
+            dataset.legend = legend;
+            dataset.coloring = coloring;
+        }
+        if self.active == Some(index) {
+            self.legend = dataset.legend.clone();
+        }
+
+        let Some(item) = self.scene.item_mut(index) else {
+            return false;
+        };
+        let first = item.cloud().is_empty();
+        item.set_cloud(context, cloud);
+        first
+    }
+
+    /// The active dataset's time series, if it has one
+    fn active_series_mut(&mut self) -> Option<&mut TimeSeries> {
+        let index = self.active?;
+        self.datasets[index].series.as_mut()
+    }
+
+    /// The active dataset's point cloud
+    fn active_cloud(&self) -> Option<&PointCloud> {
+        self.active
+            .and_then(|index| self.scene.item(index))
+            .map(SceneItem::cloud)
+    }
+
+    fn dataset_names(&self) -> Vec<&str> {
+        self.scene.items().iter().map(SceneItem::name).collect()
+    }
+}
+
+/// Give a loaded time series frame the normals estimated for it; returns
+/// its positions if they still need estimating
+fn add_frame_normals(
+    series: &mut TimeSeries,
+    normals: &HashMap<usize, Vec<Vec3>>,
+    frame: usize,
+) -> Option<Vec<Vec3>> {
+    let cloud = series.frame(frame).ok()?;
+    if cloud.normals().is_some() {
+        return None;
+    }
+
+    match normals.get(&frame) {
+        Some(cached) if cached.len() == cloud.len() => {
+            let cloud = Arc::unwrap_or_clone(cloud).with_normals(cached.clone());
+            series.store_frame(frame, cloud);
+            None
+        }
+        _ => Some(cloud.positions().to_vec()),
+    }
+}
+
+/// Draw the datasets in the scene with their visibility, name and
+/// placement
+fn scene_panel(ctx: &egui::Context, scene: &mut Scene) {
+    egui::Window::new("🗂 Scene")
+        .default_pos([270.0, 350.0])
+        .default_width(250.0)
+        .resizable(false)
+        .show(ctx, |ui| {
+            for (index, item) in scene.items_mut().iter_mut().enumerate() {
+                ui.push_id(index, |ui| {
+                    ui.horizontal(|ui| {
+                        let mut visible = item.is_visible();
+                        if ui
+                            .checkbox(&mut visible, "")
+                            .on_hover_text("Show")
+                            .changed()
+                        {
+                            item.set_visible(visible);
+                        }
+                        let mut name = item.name().to_string();
+                        if ui.text_edit_singleline(&mut name).changed() {
+                            item.set_name(name);
+                        }
+                    });
+
+                    egui::CollapsingHeader::new("Transform").show(ui, |ui| {
+                        if let Some(transform) = transform_controls(ui, item.transform()) {
+                            item.set_transform(transform);
+                        }
+                    });
+                });
+                ui.separator();
+            }
+        });
+}
+
+/// Edit the offset, rotation and uniform scale of a transform; returns the
+/// new transform if it changed
+fn transform_controls(ui: &mut egui::Ui, mut transform: Transform) -> Option<Transform> {
+    let mut changed = false;
+
+    ui.horizontal(|ui| {
+        ui.label("Offset:");
+        let offset: &mut [f32; 3] = transform.translation.as_mut();
+        for value in offset {
+            changed |= ui.add(egui::DragValue::new(value).speed(0.1)).changed();
+        }
+    });
+
+    // Rebuild the rotation only when edited, so it does not drift through
+    // the Euler angles
+    let (x, y, z) = transform.rotation.to_euler(EulerRot::XYZ);
+    let mut angles = [x, y, z].map(f32::to_degrees);
+    let mut rotated = false;
+    ui.horizontal(|ui| {
+        ui.label("Rotation:");
+        for angle in &mut angles {
+            rotated |= ui
+                .add(
+                    egui::DragValue::new(angle)
+                        .suffix("°")
+                        .clamp_range(-180.0..=180.0),
+                )
+                .changed();
+        }
+    });
+    if rotated {
+        let [x, y, z] = angles.map(f32::to_radians);
+        transform.rotation = Quat::from_euler(EulerRot::XYZ, x, y, z);
+        changed = true;
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("Scale:");
+        let mut scale = transform.scale.x;
+        if ui
+            .add(
+                egui::DragValue::new(&mut scale)
+                    .speed(0.01)
+                    .clamp_range(0.01..=100.0),
+            )
+            .changed()
+        {
+            transform.scale = Vec3::splat(scale);
+            changed = true;
+        }
+    });
+
+    if ui.button("Reset").clicked() {
+        transform = Transform::identity();
+        changed = true;
+    }
+
+    changed.then_some(transform)
+}
+
+/// Colormap for an index of the control panel's colormap selector
+fn colormap_at(index: usize) -> &'static dyn Colormap {
+    match index {
+        1 => &Plasma,
+        2 => &Inferno,
+        3 => &Turbo,
+        _ => &Viridis,
+    }
+}
+
+/// Give clouds without colors a colormap on their first numeric field, a
+/// palette on their first categorical field, or height colors
+fn with_default_colors(mut cloud: PointCloud) -> (PointCloud, Option<Legend>, Option<Coloring>) {
+    if cloud.colors().is_some() {
+        return (cloud, None, None);
+    }
+
+    let mut keys: Vec<String> = cloud.metadata_keys().into_iter().cloned().collect();
+    keys.sort();
+    let mut categorical_keys: Vec<String> = cloud.categorical_keys().into_iter().cloned().collect();
+    categorical_keys.sort();
+
+    let mut legend = None;
+    let mut coloring = None;
+    if let Some(field) = keys.first() {
+        let range = ColorRange::MinMax;
+        let resolved = cloud.apply_colormap_range(field, &Viridis, ScaleType::Linear, range);
+        coloring = resolved.ok().map(|(min, max)| Coloring {
+            field: field.clone(),
+            colormap: &Viridis,
+            scale_type: ScaleType::Linear,
+            min,
+            max,
+        });
+    } else if let Some(field) = categorical_keys.first() {
+        legend = cloud.apply_palette(field, &Tab10).ok();
+    } else {
+        cloud.generate_height_colors();
+    }
+    (cloud, legend, coloring)
+}
+
+fn main() -> Result<()> {
+    // Setup logging
+    let subscriber = FmtSubscriber::builder()
+        .with_max_level(Level::INFO)
+        .finish();
+    tracing::subscriber::set_global_default(subscriber)?;
+
+    info!("Starting Scientific Visualizer");
+    info!("Usage: viz-app [FILE|DIR...] (or drop files onto the window)");
+    info!("  A directory opens as a time series with one frame per file");
+    info!("Controls:");
+    info!("  - Left Mouse Drag: Rotate camera");
+    info!("  - Shift + Left Mouse Drag: Pan camera");
+    info!("  - Mouse Wheel: Zoom in/out");
+    info!("  - R: Reset camera");
+    info!("  - H: Toggle UI");
+    info!("  - ESC: Exit");
+
+    // Loads run on the tokio blocking pool so parsing never stalls the window
+    let runtime = tokio::runtime::Runtime::new()?;
+    let mut viewer = Viewer::new(runtime.handle().clone());
+    for path in std::env::args_os().skip(1) {
+        viewer.open(PathBuf::from(path));
+    }
+
+    // Create event loop and window
+    let event_loop = EventLoop::new()?;
+    let window = Arc::new(
+        WindowBuilder::new()
+            .with_title("Scientific Visualizer")
+            .with_inner_size(winit::dpi::LogicalSize::new(1280, 720))
+            .build(&event_loop)?,
+    );
+
+    // Initialize GPU context
+    let mut render_context = pollster::block_on(RenderContext::new(window.clone()))?;
+
+    info!(
+        "GPU initialized: {} ({:?})",
+        render_context.adapter_info.name, render_context.adapter_info.backend
+    );
+
+    // Initialize UI
+    let mut ui_context = UiContext::new(
+        &render_context.device,
+        render_context.config.format,
+        &window,
+    );
+
+    // Create camera
+    let mut camera = OrbitalCamera::new(Vec3::ZERO, 20.0, render_context.aspect_ratio());
+
+    // UI state
+    let mut control_panel = ControlPanel::default();
+    let mut timeline = Timeline::default();
+    let mut last_frame = Instant::now();
+    let mut performance_metrics = PerformanceMetrics::new(100);
+    let mut show_ui = true;
+    let mut hovering_file = false;
+
+    // Input state
+    let mut input_state = InputState::new();
+
+    // Main event loop
+    event_loop.run(move |event, elwt| {
+        elwt.set_control_flow(ControlFlow::Poll);
+
+        match event {
+            Event::WindowEvent {
+                event: ref window_event,
+                window_id,
+            } if window_id == window.id() => {
+                // File drops are handled regardless of what the UI is doing
+                match window_event {
+                    WindowEvent::DroppedFile(path) => {
+                        hovering_file = false;
+                        viewer.open(path.clone());
+                        return;
+                    }
+                    WindowEvent::HoveredFile(_) => hovering_file = true,
+                    WindowEvent::HoveredFileCancelled => hovering_file = false,
+                    _ => {}
+                }
+
+                // Let UI handle event first
+                let ui_consumed = if show_ui {
+                    ui_context.handle_event(&window, window_event)
+                } else {
+                    false
+                };
+
+                // Only process 3D controls if UI didn't consume the event
+                if !ui_consumed {
+                    match window_event {
+                        WindowEvent::CloseRequested => {
+                            info!("Close requested, exiting");
+                            elwt.exit();
+                        }
+
+                        WindowEvent::Resized(physical_size) => {
+                            render_context.resize(physical_size.width, physical_size.height);
+                            camera.set_aspect(render_context.aspect_ratio());
+                        }
+
+                        WindowEvent::MouseInput { state, button: MouseButton::Left, .. } => {
+                            input_state.mouse_pressed = *state == ElementState::Pressed;
+                        }
+
+                        WindowEvent::CursorMoved { position, .. } => {
+                            let current_pos = (position.x as f32, position.y as f32);
+
+                            if input_state.mouse_pressed && !ui_context.wants_pointer_input() {
+                                let delta_x = current_pos.0 - input_state.last_mouse_pos.0;
+                                let delta_y = current_pos.1 - input_state.last_mouse_pos.1;
+
+                                if input_state.shift_pressed {
+                                    camera.pan(delta_x, -delta_y);
+                                } else {
+                                    let sensitivity = 0.005;
+                                    camera.rotate(delta_x * sensitivity, -delta_y * sensitivity);
+                                }
+                            }
+
+                            input_state.last_mouse_pos = current_pos;
+                        }
+
+                        WindowEvent::MouseWheel { delta, .. } if !ui_context.wants_pointer_input() => {
+                            let zoom_delta = match delta {
+                                MouseScrollDelta::LineDelta(_, y) => *y * 0.1,
+                                MouseScrollDelta::PixelDelta(pos) => pos.y as f32 * 0.001,
+                            };
+                            camera.zoom(zoom_delta);
+                        }
+
+                        WindowEvent::KeyboardInput { event, .. } => {
+                            if event.state == ElementState::Pressed {
+                                if let PhysicalKey::Code(keycode) = event.physical_key {
+                                    match keycode {
+                                        KeyCode::Escape => {
+                                            info!("ESC pressed, exiting");
+                                            elwt.exit();
+                                        }
+                                        KeyCode::KeyR => {
+                                            info!("Resetting camera");
+                                            camera.reset();
+                                            camera.distance = 20.0;
+                                            viewer.scene.frame(&mut camera, 0.2);
+                                        }
+                                        KeyCode::KeyH => {
+                                            show_ui = !show_ui;
+                                            info!("UI {}", if show_ui { "shown" } else { "hidden" });
+                                        }
+                                        KeyCode::ShiftLeft | KeyCode::ShiftRight => {
+                                            input_state.shift_pressed = true;
+                                        }
+                                        _ => {}
+                                    }
+                                }
+                            } else if let PhysicalKey::Code(KeyCode::ShiftLeft | KeyCode::ShiftRight) =
+                                event.physical_key
+                            {
+                                input_state.shift_pressed = false;
+                            }
+                        }
+
+                        WindowEvent::RedrawRequested => {
+                            // Update performance metrics
+                            performance_metrics.record_frame();
+
+                            let now = Instant::now();
+                            let dt = now.duration_since(last_frame).as_secs_f32();
+                            last_frame = now;
+
+                            // Pick up datasets that finished loading, framing the camera on
+                            // everything shown
+                            if let Some(index) =
+                                viewer.poll_loads(&render_context, control_panel.point_size)
+                            {
+                                control_panel.dataset_index = index;
+                                viewer.activate(index);
+                                viewer.scene.frame(&mut camera, 0.2);
+                            }
+                            if viewer.poll_frames(&render_context) {
+                                viewer.scene.frame(&mut camera, 0.2);
+                            }
+                            viewer.poll_normals(&render_context);
+
+                            // Play the active time series
+                            if let Some(series) = viewer.active_series_mut() {
+                                if timeline.advance(series, dt) {
+                                    viewer.show_active_frame(&render_context);
+                                }
+                            }
+
+                            // Begin UI frame
+                            if show_ui {
+                                let ctx = ui_context.begin_frame(&window);
+
+                                // Draw performance panel
+                                performance_panel(&ctx, &performance_metrics);
+
+                                if let Some(legend) = &viewer.legend {
+                                    legend_panel(&ctx, legend);
+                                }
+
+                                if let Some((label, stats, histogram)) = &viewer.stats {
+                                    stats_panel(&ctx, label, stats, histogram);
+                                }
+
+                                if let Some((label, min, max, scale_type)) = &viewer.colorbar {
+                                    let colormap = colormap_at(control_panel.colormap_index);
+                                    colorbar_panel(&ctx, label, colormap, *min, *max, *scale_type);
+                                }
+
+                                if let Some(series) = viewer.active_series_mut() {
+                                    if timeline.show(&ctx, series) {
+                                        viewer.show_active_frame(&render_context);
+                                    }
+                                }
+
+                                // Draw progress for pending loads
+                                for handle in &viewer.loads {
+                                    if loading_panel(&ctx, handle) {
+                                        info!("Cancelling load of {}", handle.path().display());
+                                        handle.cancel();
+                                    }
+                                }
+
+                                if !viewer.scene.is_empty() {
+                                    scene_panel(&ctx, &mut viewer.scene);
+                                }
+
+                                if viewer.datasets.is_empty() && viewer.loads.is_empty() {
+                                    egui::Window::new("📂 Open a dataset")
+                                        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                                        .collapsible(false)
+                                        .resizable(false)
+                                        .show(&ctx, |ui| {
+                                            ui.label("Drop a CSV, Parquet, PLY, LAS, NumPy or cache file onto the window.");
+                                            ui.label("Drop a folder of such files to play them as a time series.");
+                                        });
+                                }
+
+                                if hovering_file {
+                                    egui::Area::new("drop_hint")
+                                        .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -20.0])
+                                        .show(&ctx, |ui| {
+                                            ui.heading("Release to open");
+                                        });
+                                }
+
+                                let mut dismiss_error = false;
+                                if let Some(error) = &viewer.last_error {
+                                    egui::Window::new("⚠ Load failed")
+                                        .anchor(egui::Align2::RIGHT_TOP, [-10.0, 10.0])
+                                        .collapsible(false)
+                                        .resizable(false)
+                                        .show(&ctx, |ui| {
+                                            ui.label(error);
+                                            dismiss_error = ui.button("Dismiss").clicked();
+                                        });
+                                }
+                                if dismiss_error {
+                                    viewer.last_error = None;
+                                }
+
+                                // Draw control panel
+                                let old_dataset = control_panel.dataset_index;
+                                let old_point_size = control_panel.point_size;
+                                let old_colormap = control_panel.colormap_index;
+                                let old_log_scale = control_panel.use_log_scale;
+                                let old_robust_range = control_panel.robust_range;
+                                let old_lit_shading = control_panel.lit_shading;
+                                let old_field = control_panel.metadata_field.clone();
+
+                                let dataset_names = viewer.dataset_names();
+                                control_panel.show(&ctx, &dataset_names, viewer.active_cloud());
+
+                                // Handle dataset change
+                                if control_panel.dataset_index != old_dataset
+                                    && control_panel.dataset_index < viewer.datasets.len()
+                                {
+                                    viewer.activate(control_panel.dataset_index);
+                                }
+
+                                // Handle point size change
+                                if control_panel.point_size != old_point_size {
+                                    viewer.set_point_size(control_panel.point_size);
+                                }
+
+                                // Handle shading change
+                                if control_panel.lit_shading != old_lit_shading {
+                                    let shading = if control_panel.lit_shading {
+                                        Shading::Lit
+                                    } else {
+                                        Shading::Flat
+                                    };
+                                    viewer.set_shading(&render_context, shading);
+                                }
+
+                                // Handle colormap, scale or field change
+                                if control_panel.colormap_index != old_colormap
+                                    || control_panel.use_log_scale != old_log_scale
+                                    || control_panel.robust_range != old_robust_range
+                                    || control_panel.metadata_field != old_field
+                                {
+                                    let colormap = colormap_at(control_panel.colormap_index);
+                                    let scale_type = if control_panel.use_log_scale {
+                                        ScaleType::Log
+                                    } else {
+                                        ScaleType::Linear
+                                    };
+
+                                    let range = if control_panel.robust_range {
+                                        ColorRange::ROBUST
+                                    } else {
+                                        ColorRange::MinMax
+                                    };
+
+                                    viewer.recolor(
+                                        &render_context,
+                                        &control_panel.metadata_field,
+                                        colormap,
+                                        scale_type,
+                                        range,
+                                    );
+                                }
+                            }
+
+                            // Update camera uniforms
+                            viewer.scene.update_camera(&render_context, &camera);
+
+                            // Render
+                            match render_context.get_current_texture() {
+                                Ok(output) => {
+                                    let view = output
+                                        .texture
+                                        .create_view(&wgpu::TextureViewDescriptor::default());
+
+                                    let mut encoder = render_context.device.create_command_encoder(
+                                        &wgpu::CommandEncoderDescriptor {
+                                            label: Some("Render Encoder"),
+                                        },
+                                    );
+
+                                    // Render 3D scene
+                                    {
+                                        let mut render_pass =
+                                            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                                                label: Some("3D Render Pass"),
+                                                color_attachments: &[Some(
+                                                    wgpu::RenderPassColorAttachment {
+                                                        view: &view,
+                                                        resolve_target: None,
+                                                        ops: wgpu::Operations {
+                                                            load: wgpu::LoadOp::Clear(
+                                                                control_panel.background_wgpu_color(),
+                                                            ),
+                                                            store: wgpu::StoreOp::Store,
+                                                        },
+                                                    },
+                                                )],
+                                                depth_stencil_attachment: None,
+                                                timestamp_writes: None,
+                                                occlusion_query_set: None,
+                                            });
+
+                                        viewer.scene.render(&mut render_pass);
+                                    }
+
+                                    // Render UI
+                                    if show_ui {
+                                        let full_output = ui_context.end_frame(&window);
+                                        ui_context
+                                            .render(
+                                                &render_context.device,
+                                                &render_context.queue,
+                                                &mut encoder,
+                                                &window,
+                                                &view,
+                                                full_output,
+                                            )
+                                            .unwrap();
+                                    }
+
+                                    render_context.queue.submit(std::iter::once(encoder.finish()));
+                                    output.present();
+                                }
+                                Err(e) => {
+                                    eprintln!("Failed to get surface texture: {}", e);
+                                }
+                            }
+                        }
+
+                        _ => {}
+                    }
+                }
+            }
+
+            Event::AboutToWait => {
+                window.request_redraw();
+            }
+
+            _ => {}
+        }
+    })?;
+
+    Ok(())
 }
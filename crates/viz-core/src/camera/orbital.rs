@@ -1,4 +1,4 @@
-use glam::{Mat4, Vec3};
+use glam::{DVec3, Mat4, Vec3};
 use std::f32::consts::{FRAC_PI_2, PI};
 
 /// Orbital camera for 3D visualization with smooth controls
@@ -15,8 +15,18 @@ use std::f32::consts::{FRAC_PI_2, PI};
 /// camera.rotate(0.1, 0.05); // Rotate by delta angles
 /// let view_proj = camera.view_projection_matrix();
 /// ```
+///
+/// For data far from the world origin (e.g. UTM coordinates), set
+/// [`origin`](Self::origin) to the data's origin so that `target` stays
+/// small, and render with [`CameraUniforms::camera_relative`].
+///
+/// [`CameraUniforms::camera_relative`]: crate::renderer::CameraUniforms::camera_relative
 #[derive(Debug, Clone)]
 pub struct OrbitalCamera {
+    /// World-space point that `target` and the camera position are
+    /// relative to
+    pub origin: DVec3,
+
     /// Target point the camera looks at
     pub target: Vec3,
 
@@ -51,6 +61,7 @@ impl OrbitalCamera {
     /// * `aspect` - Aspect ratio (width / height)
     pub fn new(target: Vec3, distance: f32, aspect: f32) -> Self {
         Self {
+            origin: DVec3::ZERO,
             target,
             distance,
             yaw: 0.0,
@@ -62,12 +73,22 @@ impl OrbitalCamera {
         }
     }
 
-    /// Compute the camera's position in world space
+    /// Compute the camera's position (relative to [`origin`](Self::origin))
     pub fn position(&self) -> Vec3 {
+        self.target + self.eye_offset()
+    }
+
+    /// Compute the camera's position in world space in double precision
+    pub fn world_position(&self) -> DVec3 {
+        self.origin + self.target.as_dvec3() + self.eye_offset().as_dvec3()
+    }
+
+    /// Offset from the target to the camera
+    fn eye_offset(&self) -> Vec3 {
         let x = self.distance * self.pitch.cos() * self.yaw.sin();
         let y = self.distance * self.pitch.sin();
         let z = self.distance * self.pitch.cos() * self.yaw.cos();
-        self.target + Vec3::new(x, y, z)
+        Vec3::new(x, y, z)
     }
 
     /// Get the forward direction (from camera to target)
@@ -100,6 +121,16 @@ impl OrbitalCamera {
         self.projection_matrix() * self.view_matrix()
     }
 
+    /// Compute the view-projection matrix for coordinates relative to the
+    /// camera position
+    ///
+    /// The view has no translation, so points close to the camera keep
+    /// full f32 precision however far the camera is from the origin.
+    pub fn relative_view_projection_matrix(&self) -> Mat4 {
+        let view = Mat4::look_at_rh(Vec3::ZERO, -self.eye_offset(), Vec3::Y);
+        self.projection_matrix() * view
+    }
+
     /// Rotate the camera by delta angles
     ///
     /// # Arguments
@@ -112,8 +143,7 @@ impl OrbitalCamera {
         self.yaw = self.yaw.rem_euclid(2.0 * PI);
 
         // Clamp pitch to prevent gimbal lock
-        self.pitch = (self.pitch + delta_pitch)
-            .clamp(-FRAC_PI_2 + 0.01, FRAC_PI_2 - 0.01);
+        self.pitch = (self.pitch + delta_pitch).clamp(-FRAC_PI_2 + 0.01, FRAC_PI_2 - 0.01);
     }
 
     /// Zoom the camera (change distance to target)
@@ -278,6 +308,26 @@ mod tests {
         assert!(camera.distance > 0.0);
     }
 
+    #[test]
+    fn test_camera_relative_matrices() {
+        let mut camera = OrbitalCamera::new(Vec3::new(1.0, 2.0, 3.0), 10.0, 1.77);
+        camera.rotate(0.7, 0.3);
+        camera.origin = DVec3::new(500_000.0, 4_000_000.0, 0.0);
+
+        let world = camera.world_position();
+        let expected = camera.origin + camera.position().as_dvec3();
+        assert!((world - expected).length() < 1e-5);
+
+        // Shifting a point by the camera position gives the same clip
+        // coordinates as the absolute matrices
+        let point = Vec3::new(2.0, 1.0, -1.0);
+        let absolute = camera.view_projection_matrix().project_point3(point);
+        let relative = camera
+            .relative_view_projection_matrix()
+            .project_point3(point - camera.position());
+        assert!(approx_eq(absolute, relative, 1e-5));
+    }
+
     #[test]
     fn test_reset() {
         let mut camera = OrbitalCamera::new(Vec3::new(5.0, 5.0, 5.0), 20.0, 1.77);
@@ -0,0 +1,183 @@
+use super::{distance_squared, Clusters, Features};
+use crate::data::{DataError, PointCloud};
+use crate::spatial::KdTree;
+use rayon::prelude::*;
+use std::collections::VecDeque;
+
+/// Density-based clustering (DBSCAN)
+///
+/// A point is a core point if at least `min_points` points (itself
+/// included) lie within `eps` of it. Core points within `eps` of each other
+/// share a cluster, border points join the cluster of a nearby core point,
+/// and everything else is noise. The number of clusters is not fixed in
+/// advance.
+///
+/// Neighbours are found with a [`KdTree`] over the positions, so clustering
+/// scales to millions of points. Metadata fields added with
+/// [`Dbscan::with_field`] extend the distance, so points must be close both
+/// in space and in those fields.
+///
+/// # Examples
+/// ```
+/// use glam::Vec3;
+/// use viz_core::cluster::{Clusters, Dbscan};
+/// use viz_core::data::PointCloud;
+///
+/// let cloud = PointCloud::new(vec![
+///     Vec3::ZERO,
+///     Vec3::new(0.1, 0.0, 0.0),
+///     Vec3::new(5.0, 0.0, 0.0),
+///     Vec3::new(5.1, 0.0, 0.0),
+///     Vec3::new(20.0, 0.0, 0.0),
+/// ]);
+/// let clusters = Dbscan::new(0.5, 2).cluster(&cloud).unwrap();
+///
+/// assert_eq!(clusters.count(), 2);
+/// assert_eq!(clusters.labels(), [0, 0, 1, 1, Clusters::NOISE]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dbscan {
+    eps: f32,
+    min_points: usize,
+    fields: Vec<(String, f32)>,
+}
+
+impl Dbscan {
+    /// Create a clustering with neighbourhood radius `eps` and `min_points`
+    /// neighbours per core point
+    ///
+    /// # Panics
+    /// Panics if `eps` is not positive
+    pub fn new(eps: f32, min_points: usize) -> Self {
+        assert!(eps > 0.0, "DBSCAN radius must be positive");
+        Self {
+            eps,
+            min_points,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Also compare points by a metadata field, scaled by `weight`
+    ///
+    /// A weight of `w` makes a difference of `1 / w` in the field as far
+    /// apart as one unit of distance.
+    pub fn with_field(mut self, field: impl Into<String>, weight: f32) -> Self {
+        self.fields.push((field.into(), weight));
+        self
+    }
+
+    /// Cluster the points of a cloud
+    ///
+    /// Points with a non-finite position or field value are noise. Clusters
+    /// are numbered in the order of their first point.
+    ///
+    /// # Errors
+    /// [`DataError::MissingField`] if a field does not exist
+    pub fn cluster(&self, cloud: &PointCloud) -> Result<Clusters, DataError> {
+        let features = Features::new(cloud, &self.fields)?;
+        let tree = KdTree::new(cloud.positions());
+        let eps_squared = self.eps * self.eps;
+
+        // Extra fields only add to the distance, so the spatial neighbours
+        // within eps are a superset of the feature neighbours
+        let neighbors = |index: usize| -> Vec<usize> {
+            let point = features.point(index);
+            tree.within_radius(cloud.positions()[index], self.eps)
+                .into_iter()
+                .map(|neighbor| neighbor.index)
+                .filter(|&other| {
+                    features.valid[other]
+                        && distance_squared(point, features.point(other)) <= eps_squared
+                })
+                .collect()
+        };
+
+        let core: Vec<bool> = (0..features.len())
+            .into_par_iter()
+            .map(|index| features.valid[index] && neighbors(index).len() >= self.min_points)
+            .collect();
+
+        let mut labels = vec![Clusters::NOISE; features.len()];
+        let mut count = 0;
+        let mut queue = VecDeque::new();
+        for seed in 0..features.len() {
+            if !core[seed] || labels[seed] != Clusters::NOISE {
+                continue;
+            }
+
+            let label = count as u32;
+            count += 1;
+            labels[seed] = label;
+            queue.push_back(seed);
+            while let Some(index) = queue.pop_front() {
+                for other in neighbors(index) {
+                    if labels[other] != Clusters::NOISE {
+                        continue;
+                    }
+                    labels[other] = label;
+                    // Border points join the cluster but do not extend it
+                    if core[other] {
+                        queue.push_back(other);
+                    }
+                }
+            }
+        }
+
+        Ok(Clusters { labels, count })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec3;
+
+    /// `count` points on a small grid around `center`
+    fn blob(center: Vec3, count: usize) -> Vec<Vec3> {
+        (0..count)
+            .map(|i| center + Vec3::new((i % 4) as f32, (i / 4 % 4) as f32, (i / 16) as f32) * 0.1)
+            .collect()
+    }
+
+    #[test]
+    fn test_blobs_and_noise() {
+        let mut positions = blob(Vec3::ZERO, 32);
+        positions.extend(blob(Vec3::splat(10.0), 48));
+        positions.push(Vec3::new(-5.0, 0.0, 0.0));
+        positions.push(Vec3::NAN);
+        let clusters = Dbscan::new(0.15, 4)
+            .cluster(&PointCloud::new(positions))
+            .unwrap();
+
+        assert_eq!(clusters.count(), 2);
+        assert_eq!(clusters.sizes(), [32, 48]);
+        assert_eq!(clusters.noise_count(), 2);
+        assert!(clusters.to_metadata()[81].is_nan());
+        assert_eq!(clusters.to_categorical().labels(), ["0", "1"]);
+    }
+
+    #[test]
+    fn test_field_separates_colocated_points() {
+        let positions = blob(Vec3::ZERO, 32);
+        let intensity: Vec<f32> = (0..32).map(|i| if i < 16 { 0.0 } else { 100.0 }).collect();
+        let cloud = PointCloud::new(positions).with_metadata("intensity".to_string(), intensity);
+
+        let spatial = Dbscan::new(0.15, 4).cluster(&cloud).unwrap();
+        assert_eq!(spatial.count(), 1);
+
+        let clusters = Dbscan::new(0.15, 4)
+            .with_field("intensity", 1.0)
+            .cluster(&cloud)
+            .unwrap();
+        assert_eq!(clusters.sizes(), [16, 16]);
+        assert_eq!(clusters.labels()[0], 0);
+        assert_eq!(clusters.labels()[31], 1);
+
+        assert!(matches!(
+            Dbscan::new(0.15, 4)
+                .with_field("missing", 1.0)
+                .cluster(&cloud),
+            Err(DataError::MissingField(_))
+        ));
+    }
+}
@@ -0,0 +1,220 @@
+use super::{distance_squared, Clusters, Features};
+use crate::data::{DataError, PointCloud};
+use crate::math::SplitMix64;
+use rayon::prelude::*;
+
+/// Default limit on the number of Lloyd iterations
+const DEFAULT_MAX_ITERATIONS: usize = 100;
+
+/// Partition into a fixed number of clusters (k-means)
+///
+/// Starts from k-means++ centers chosen with a seeded generator and then
+/// alternates assigning every point to its nearest center and moving each
+/// center to the mean of its points, until no point changes cluster or the
+/// iteration limit is reached. Metadata fields added with
+/// [`KMeans::with_field`] are appended to the positions as extra weighted
+/// coordinates.
+///
+/// # Examples
+/// ```
+/// use glam::Vec3;
+/// use viz_core::cluster::KMeans;
+/// use viz_core::data::PointCloud;
+///
+/// let cloud = PointCloud::new(vec![
+///     Vec3::ZERO,
+///     Vec3::new(0.1, 0.0, 0.0),
+///     Vec3::new(5.0, 0.0, 0.0),
+///     Vec3::new(5.1, 0.0, 0.0),
+/// ]);
+/// let clusters = KMeans::new(2).cluster(&cloud).unwrap();
+/// let labels = clusters.labels();
+///
+/// assert_eq!(labels[0], labels[1]);
+/// assert_eq!(labels[2], labels[3]);
+/// assert_ne!(labels[0], labels[2]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct KMeans {
+    k: usize,
+    max_iterations: usize,
+    seed: u64,
+    fields: Vec<(String, f32)>,
+}
+
+impl KMeans {
+    /// Create a clustering into `k` clusters
+    ///
+    /// # Panics
+    /// Panics if `k` is zero
+    pub fn new(k: usize) -> Self {
+        assert!(k > 0, "k-means needs at least one cluster");
+        Self {
+            k,
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            seed: 0,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Set the maximum number of assignment/update iterations
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Set the seed for choosing the initial centers
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Also cluster by a metadata field, scaled by `weight`
+    pub fn with_field(mut self, field: impl Into<String>, weight: f32) -> Self {
+        self.fields.push((field.into(), weight));
+        self
+    }
+
+    /// Cluster the points of a cloud
+    ///
+    /// Points with a non-finite position or field value are left
+    /// unassigned ([`Clusters::NOISE`]). With fewer valid points than `k`,
+    /// there are fewer clusters.
+    ///
+    /// # Errors
+    /// [`DataError::MissingField`] if a field does not exist
+    pub fn cluster(&self, cloud: &PointCloud) -> Result<Clusters, DataError> {
+        let features = Features::new(cloud, &self.fields)?;
+        let valid: Vec<usize> = (0..features.len()).filter(|&i| features.valid[i]).collect();
+
+        let mut centers = self.initial_centers(&features, &valid);
+        let count = centers.len() / features.dims.max(1);
+        let mut labels = vec![Clusters::NOISE; features.len()];
+
+        for _ in 0..self.max_iterations {
+            let assigned: Vec<u32> = valid
+                .par_iter()
+                .map(|&index| nearest(features.point(index), &centers, features.dims).0)
+                .collect();
+
+            let mut changed = false;
+            for (&index, &label) in valid.iter().zip(&assigned) {
+                changed |= labels[index] != label;
+                labels[index] = label;
+            }
+            if !changed {
+                break;
+            }
+
+            // Means in f64; an empty cluster keeps its center
+            let mut sums = vec![0.0f64; centers.len()];
+            let mut sizes = vec![0usize; count];
+            for (&index, &label) in valid.iter().zip(&assigned) {
+                let offset = label as usize * features.dims;
+                for (sum, &value) in sums[offset..].iter_mut().zip(features.point(index)) {
+                    *sum += value as f64;
+                }
+                sizes[label as usize] += 1;
+            }
+            for (cluster, &size) in sizes.iter().enumerate().filter(|(_, &size)| size > 0) {
+                let offset = cluster * features.dims;
+                for d in offset..offset + features.dims {
+                    centers[d] = (sums[d] / size as f64) as f32;
+                }
+            }
+        }
+
+        Ok(Clusters { labels, count })
+    }
+
+    /// k-means++ seeding: each next center is a point picked with
+    /// probability proportional to its squared distance to the nearest
+    /// center so far
+    fn initial_centers(&self, features: &Features, valid: &[usize]) -> Vec<f32> {
+        let mut rng = SplitMix64::new(self.seed);
+        let mut centers = Vec::with_capacity(self.k * features.dims);
+        let Some(&first) = valid.get(rng.below(valid.len().max(1))) else {
+            return centers;
+        };
+        centers.extend_from_slice(features.point(first));
+
+        let mut distances: Vec<f64> = valid
+            .iter()
+            .map(|&index| distance_squared(features.point(index), features.point(first)) as f64)
+            .collect();
+        while centers.len() < self.k * features.dims {
+            let total: f64 = distances.iter().sum();
+            if total <= 0.0 {
+                // Every point coincides with a center
+                break;
+            }
+
+            let mut target = rng.next_f64() * total;
+            let chosen = distances
+                .iter()
+                .position(|&d| {
+                    target -= d;
+                    target < 0.0
+                })
+                .unwrap_or(valid.len() - 1);
+            let center = features.point(valid[chosen]);
+            centers.extend_from_slice(center);
+
+            for (distance, &index) in distances.iter_mut().zip(valid) {
+                *distance = distance.min(distance_squared(features.point(index), center) as f64);
+            }
+        }
+        centers
+    }
+}
+
+/// Index of and squared distance to the nearest center
+fn nearest(point: &[f32], centers: &[f32], dims: usize) -> (u32, f32) {
+    centers
+        .chunks_exact(dims)
+        .enumerate()
+        .map(|(i, center)| (i as u32, distance_squared(point, center)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap_or((Clusters::NOISE, f32::INFINITY))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec3;
+
+    #[test]
+    fn test_recovers_blobs() {
+        let centers = [
+            Vec3::ZERO,
+            Vec3::new(10.0, 0.0, 0.0),
+            Vec3::new(0.0, 10.0, 5.0),
+        ];
+        let mut rng = SplitMix64::new(7);
+        let mut positions: Vec<Vec3> = (0..300)
+            .map(|i| {
+                let jitter = Vec3::new(rng.next_f64() as f32, rng.next_f64() as f32, 0.0);
+                centers[i % 3] + jitter
+            })
+            .collect();
+        positions.push(Vec3::NAN);
+
+        let clusters = KMeans::new(3)
+            .with_seed(1)
+            .cluster(&PointCloud::new(positions))
+            .unwrap();
+        assert_eq!(clusters.count(), 3);
+        assert_eq!(clusters.sizes(), [100, 100, 100]);
+        assert_eq!(clusters.labels()[300], Clusters::NOISE);
+
+        // Points generated from the same center share a cluster
+        let labels = clusters.labels();
+        for i in 3..300 {
+            assert_eq!(labels[i], labels[i % 3]);
+        }
+
+        // Fewer distinct points than clusters
+        let two = PointCloud::new(vec![Vec3::ZERO, Vec3::ZERO, Vec3::ONE]);
+        assert_eq!(KMeans::new(5).cluster(&two).unwrap().count(), 2);
+    }
+}
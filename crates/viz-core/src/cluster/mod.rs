@@ -0,0 +1,140 @@
+//! Clustering of point clouds into label fields
+//!
+//! [`Dbscan`] and [`KMeans`] group points by position, optionally together
+//! with weighted metadata fields, and return [`Clusters`], which convert to
+//! a metadata or categorical field for coloring.
+
+mod dbscan;
+mod kmeans;
+
+pub use dbscan::Dbscan;
+pub use kmeans::KMeans;
+
+use crate::data::{CategoricalField, DataError, PointCloud};
+
+/// Cluster label of every point
+///
+/// # Examples
+/// ```
+/// use glam::Vec3;
+/// use viz_core::cluster::Dbscan;
+/// use viz_core::color::Tab10;
+/// use viz_core::data::PointCloud;
+///
+/// let cloud = PointCloud::new(vec![Vec3::ZERO, Vec3::X * 0.1, Vec3::X * 5.0]);
+/// let clusters = Dbscan::new(0.5, 2).cluster(&cloud).unwrap();
+///
+/// // Cluster, then color by cluster
+/// let mut cloud = cloud.with_categorical("cluster", clusters.to_categorical());
+/// let legend = cloud.apply_palette("cluster", &Tab10).unwrap();
+/// assert_eq!(legend.entries.len(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clusters {
+    /// Cluster of each point, or [`Clusters::NOISE`]
+    labels: Vec<u32>,
+    /// Number of clusters
+    count: usize,
+}
+
+impl Clusters {
+    /// Label of a point in no cluster (same as [`CategoricalField::MISSING`])
+    pub const NOISE: u32 = CategoricalField::MISSING;
+
+    /// Cluster of each point, numbered from 0, or [`Clusters::NOISE`]
+    pub fn labels(&self) -> &[u32] {
+        &self.labels
+    }
+
+    /// Number of clusters
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Number of points in no cluster
+    pub fn noise_count(&self) -> usize {
+        self.labels.iter().filter(|&&l| l == Self::NOISE).count()
+    }
+
+    /// Number of points in each cluster
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes = vec![0; self.count];
+        for &label in &self.labels {
+            if label != Self::NOISE {
+                sizes[label as usize] += 1;
+            }
+        }
+        sizes
+    }
+
+    /// Labels as a metadata field, with NaN for noise
+    pub fn to_metadata(&self) -> Vec<f32> {
+        self.labels
+            .iter()
+            .map(|&label| match label {
+                Self::NOISE => f32::NAN,
+                label => label as f32,
+            })
+            .collect()
+    }
+
+    /// Labels as a categorical field named `0`, `1`, ..., with noise missing
+    pub fn to_categorical(&self) -> CategoricalField {
+        let labels = (0..self.count).map(|i| i.to_string()).collect();
+        CategoricalField::new(self.labels.clone(), labels)
+            .expect("cluster labels are below the cluster count")
+    }
+}
+
+/// Per-point feature vectors: the position followed by weighted fields
+struct Features {
+    /// Values per point, `dims` at a time
+    values: Vec<f32>,
+    dims: usize,
+    /// Points whose features are all finite
+    valid: Vec<bool>,
+}
+
+impl Features {
+    fn new(cloud: &PointCloud, fields: &[(String, f32)]) -> Result<Self, DataError> {
+        let columns = fields
+            .iter()
+            .map(|(name, weight)| {
+                cloud
+                    .metadata(name)
+                    .map(|values| (values, *weight))
+                    .ok_or_else(|| DataError::MissingField(name.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let dims = 3 + columns.len();
+        let positions = cloud.positions();
+        let mut values = Vec::with_capacity(positions.len() * dims);
+        for (i, position) in positions.iter().enumerate() {
+            values.extend(position.to_array());
+            values.extend(columns.iter().map(|(column, weight)| column[i] * weight));
+        }
+        let valid = values
+            .chunks_exact(dims)
+            .map(|point| point.iter().all(|v| v.is_finite()))
+            .collect();
+
+        Ok(Self {
+            values,
+            dims,
+            valid,
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.valid.len()
+    }
+
+    fn point(&self, index: usize) -> &[f32] {
+        &self.values[index * self.dims..(index + 1) * self.dims]
+    }
+}
+
+fn distance_squared(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum()
+}
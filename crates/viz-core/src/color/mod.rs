@@ -1,5 +1,7 @@
 mod colormap;
+mod palette;
 mod scale;
 
-pub use colormap::{Colormap, Viridis, Plasma, Inferno, Turbo};
-pub use scale::{ColorScale, ScaleType};
+pub use colormap::{Colormap, Inferno, Plasma, Turbo, Viridis};
+pub use palette::{Legend, LegendEntry, Palette, Tab10};
+pub use scale::{ColorRange, ColorScale, ScaleType};
@@ -0,0 +1,90 @@
+use glam::Vec4;
+
+/// Trait for qualitative palettes that give each category a distinct color
+pub trait Palette: Send + Sync {
+    /// Color for the category with the given index
+    ///
+    /// Indices past the end of the palette wrap around.
+    fn color(&self, index: usize) -> Vec4;
+
+    /// Number of distinct colors before the palette repeats
+    fn color_count(&self) -> usize;
+
+    /// Get the name of this palette
+    fn name(&self) -> &str;
+}
+
+// Tab10 palette - matplotlib's default categorical colors
+const TAB10_COLORS: [[f32; 3]; 10] = [
+    [0.121569, 0.466667, 0.705882],
+    [1.000000, 0.498039, 0.054902],
+    [0.172549, 0.627451, 0.172549],
+    [0.839216, 0.152941, 0.156863],
+    [0.580392, 0.403922, 0.741176],
+    [0.549020, 0.337255, 0.294118],
+    [0.890196, 0.466667, 0.760784],
+    [0.498039, 0.498039, 0.498039],
+    [0.737255, 0.741176, 0.133333],
+    [0.090196, 0.745098, 0.811765],
+];
+
+/// Tab10 palette - ten well-separated colors for categorical data
+pub struct Tab10;
+
+impl Palette for Tab10 {
+    fn color(&self, index: usize) -> Vec4 {
+        let rgb = TAB10_COLORS[index % TAB10_COLORS.len()];
+        Vec4::new(rgb[0], rgb[1], rgb[2], 1.0)
+    }
+
+    fn color_count(&self) -> usize {
+        TAB10_COLORS.len()
+    }
+
+    fn name(&self) -> &str {
+        "Tab10"
+    }
+}
+
+/// One row of a [`Legend`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegendEntry {
+    pub label: String,
+    pub color: Vec4,
+}
+
+/// Mapping from category labels to the colors they were drawn with
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Legend {
+    /// Name of the field that was colored
+    pub title: String,
+    pub entries: Vec<LegendEntry>,
+}
+
+impl Legend {
+    /// Color drawn for a label, if it is in the legend
+    pub fn color_of(&self, label: &str) -> Option<Vec4> {
+        self.entries
+            .iter()
+            .find(|entry| entry.label == label)
+            .map(|entry| entry.color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tab10_wraps_and_is_distinct() {
+        assert_eq!(Tab10.color_count(), 10);
+        assert_eq!(Tab10.color(0), Tab10.color(10));
+        assert_eq!(Tab10.color(3).w, 1.0);
+
+        for i in 0..Tab10.color_count() {
+            for j in (i + 1)..Tab10.color_count() {
+                assert_ne!(Tab10.color(i), Tab10.color(j));
+            }
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::data::{DataError, FieldStats};
+
 /// Type of scaling to apply when mapping values to colormap domain [0, 1]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ScaleType {
@@ -7,6 +9,66 @@ pub enum ScaleType {
     Log,
 }
 
+/// Data range mapped onto the ends of a colormap
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorRange {
+    /// Smallest to largest finite value
+    MinMax,
+    /// Between two percentiles in [0, 100], robust to outliers
+    Percentile { low: f32, high: f32 },
+    /// Fixed bounds
+    Fixed { min: f32, max: f32 },
+}
+
+impl ColorRange {
+    /// 2nd to 98th percentile
+    pub const ROBUST: Self = Self::Percentile {
+        low: 2.0,
+        high: 98.0,
+    };
+
+    /// Resolve to the `(min, max)` of `values` the range covers
+    ///
+    /// Non-finite values are ignored. Percentiles are read from `stats`
+    /// when given, e.g. a cloud's cached [field statistics], and computed
+    /// from `values` otherwise.
+    ///
+    /// # Errors
+    /// [`DataError::InvalidPercentile`] for percentiles outside [0, 100] or
+    /// with `low > high`
+    ///
+    /// [field statistics]: crate::data::PointCloud::field_stats
+    pub fn resolve(
+        self,
+        values: &[f32],
+        stats: Option<&FieldStats>,
+    ) -> Result<(f32, f32), DataError> {
+        match self {
+            ColorRange::MinMax => {
+                let finite = values.iter().copied().filter(|v| v.is_finite());
+                let min = finite.clone().fold(f32::INFINITY, f32::min);
+                let max = finite.fold(f32::NEG_INFINITY, f32::max);
+                Ok((min, max))
+            }
+            ColorRange::Percentile { low, high } => {
+                if !(0.0 <= low && low <= high && high <= 100.0) {
+                    return Err(DataError::InvalidPercentile { low, high });
+                }
+                let computed;
+                let stats = match stats {
+                    Some(stats) => stats,
+                    None => {
+                        computed = FieldStats::from_values(values);
+                        &computed
+                    }
+                };
+                Ok((stats.percentile(low), stats.percentile(high)))
+            }
+            ColorRange::Fixed { min, max } => Ok((min, max)),
+        }
+    }
+}
+
 /// Utilities for mapping data values to colormap domain [0, 1]
 pub struct ColorScale;
 
@@ -118,14 +180,14 @@ mod tests {
     fn test_log_scale_expansion() {
         // Log scale expands lower values (gives them more color range)
         // and compresses higher values
-        let linear_1 = ColorScale::map_linear(1.0, 0.0, 10.0);  // 0.1
+        let linear_1 = ColorScale::map_linear(1.0, 0.0, 10.0); // 0.1
         let log_1 = ColorScale::map_log(1.0, 0.0, 10.0);
 
         // Lower values get expanded - mapped to higher t values than linear
         assert!(log_1 > linear_1);
 
         // Verify the expansion is significant
-        assert!(log_1 > 0.2);  // Should be around 0.29
+        assert!(log_1 > 0.2); // Should be around 0.29
     }
 
     #[test]
@@ -134,6 +196,35 @@ mod tests {
         assert_eq!(ColorScale::map_log(5.0, 5.0, 5.0), 0.5);
     }
 
+    #[test]
+    fn test_resolve_range() {
+        let values: Vec<f32> = (0..=100).map(|i| i as f32).chain([f32::NAN]).collect();
+
+        assert_eq!(ColorRange::MinMax.resolve(&values, None), Ok((0.0, 100.0)));
+        assert_eq!(
+            ColorRange::Fixed {
+                min: -1.0,
+                max: 1.0
+            }
+            .resolve(&values, None),
+            Ok((-1.0, 1.0))
+        );
+
+        let robust = ColorRange::ROBUST.resolve(&values, None).unwrap();
+        assert!((robust.0 - 2.0).abs() < 1e-4 && (robust.1 - 98.0).abs() < 1e-4);
+        let stats = FieldStats::from_values(&values[..11]);
+        let cached = ColorRange::ROBUST.resolve(&values, Some(&stats)).unwrap();
+        assert_eq!(cached.1, stats.percentile(98.0));
+
+        for (low, high) in [(-1.0, 50.0), (10.0, 101.0), (60.0, 40.0), (f32::NAN, 50.0)] {
+            let range = ColorRange::Percentile { low, high };
+            assert!(matches!(
+                range.resolve(&values, None),
+                Err(DataError::InvalidPercentile { .. })
+            ));
+        }
+    }
+
     #[test]
     fn test_scale_type_dispatch() {
         let value = 5.0;
@@ -0,0 +1,179 @@
+use super::{Dataset, PointCloud};
+use crate::math::Bounds3D;
+use arrow::array::Float32Array;
+use glam::Vec3;
+
+/// Dataset backed by Arrow Float32 arrays
+///
+/// Columns are stored in the record-batch chunks they were decoded in, so
+/// reading a column borrows the Arrow buffers instead of copying them. Use
+/// [`ArrowDataset::to_point_cloud`] when a contiguous [`PointCloud`] is needed
+/// for rendering.
+///
+/// Created by [`ParquetLoader::load_arrow`](crate::io::ParquetLoader::load_arrow).
+#[derive(Debug, Clone)]
+pub struct ArrowDataset {
+    /// Column names; the first three are the x, y and z positions
+    columns: Vec<String>,
+
+    /// Arrays per batch, in the same order as `columns`
+    chunks: Vec<Vec<Float32Array>>,
+
+    /// Total number of rows across all batches
+    num_rows: usize,
+
+    /// Bounding box of the positions
+    bounds: Bounds3D,
+
+    /// Dataset name
+    name: String,
+}
+
+impl ArrowDataset {
+    /// Build a dataset from per-batch columns
+    ///
+    /// The first three columns must be non-null x, y and z positions.
+    pub(crate) fn from_columns(columns: Vec<String>, chunks: Vec<Vec<Float32Array>>) -> Self {
+        let num_rows = chunks.iter().map(|batch| batch[0].len()).sum();
+
+        let mut dataset = Self {
+            columns,
+            chunks,
+            num_rows,
+            bounds: Bounds3D::zero(),
+            name: String::from("Arrow Dataset"),
+        };
+
+        // Non-finite positions are ignored, as in `Bounds3D::from_points`
+        let mut positions = dataset.positions().filter(|p| p.is_finite());
+        let bounds = positions.next().map(|first| {
+            let mut bounds = Bounds3D::new(first, first);
+            positions.for_each(|p| bounds.expand_to_include(p));
+            bounds
+        });
+        dataset.bounds = bounds.unwrap_or_default();
+
+        dataset
+    }
+
+    /// Set the dataset name
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Names of the metadata columns (everything except x/y/z)
+    pub fn metadata_keys(&self) -> &[String] {
+        &self.columns[3..]
+    }
+
+    /// Borrow a column as Arrow arrays, one per record batch
+    pub fn column_arrays(&self, name: &str) -> Option<Vec<&Float32Array>> {
+        let index = self.columns.iter().position(|c| c == name)?;
+        Some(self.chunks.iter().map(|batch| &batch[index]).collect())
+    }
+
+    /// Borrow a column as raw value slices, one per record batch
+    ///
+    /// Slots that are null in Arrow hold unspecified values; use
+    /// [`ArrowDataset::column_arrays`] to inspect validity.
+    pub fn column_chunks(&self, name: &str) -> Option<Vec<&[f32]>> {
+        self.column_arrays(name)
+            .map(|arrays| arrays.into_iter().map(|a| &a.values()[..]).collect())
+    }
+
+    /// Iterate over point positions
+    pub fn positions(&self) -> impl Iterator<Item = Vec3> + '_ {
+        self.chunks.iter().flat_map(|batch| {
+            let (xs, ys, zs) = (batch[0].values(), batch[1].values(), batch[2].values());
+            (0..xs.len()).map(move |i| Vec3::new(xs[i], ys[i], zs[i]))
+        })
+    }
+
+    /// Copy the dataset into a point cloud
+    ///
+    /// Null metadata values become NaN.
+    pub fn to_point_cloud(&self) -> PointCloud {
+        let mut cloud = PointCloud::new(self.positions().collect()).with_name(self.name.clone());
+
+        for (index, name) in self.columns.iter().enumerate().skip(3) {
+            let values = self
+                .chunks
+                .iter()
+                .flat_map(|batch| batch[index].iter().map(|v| v.unwrap_or(f32::NAN)))
+                .collect();
+            cloud = cloud.with_metadata(name.clone(), values);
+        }
+
+        cloud
+    }
+}
+
+impl Dataset for ArrowDataset {
+    fn bounds(&self) -> Bounds3D {
+        self.bounds
+    }
+
+    fn len(&self) -> usize {
+        self.num_rows
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns() -> Vec<String> {
+        ["x", "y", "z", "mass"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_chunks_and_bounds() {
+        let chunks = vec![
+            vec![
+                Float32Array::from(vec![0.0, 1.0]),
+                Float32Array::from(vec![-1.0, 2.0]),
+                Float32Array::from(vec![0.0, 0.0]),
+                Float32Array::from(vec![Some(5.0), None]),
+            ],
+            vec![
+                Float32Array::from(vec![4.0]),
+                Float32Array::from(vec![0.0]),
+                Float32Array::from(vec![3.0]),
+                Float32Array::from(vec![7.0]),
+            ],
+        ];
+
+        let dataset = ArrowDataset::from_columns(columns(), chunks);
+
+        assert_eq!(dataset.len(), 3);
+        assert_eq!(dataset.bounds().min, Vec3::new(0.0, -1.0, 0.0));
+        assert_eq!(dataset.bounds().max, Vec3::new(4.0, 2.0, 3.0));
+        assert_eq!(dataset.metadata_keys(), &["mass".to_string()]);
+        assert_eq!(
+            dataset.column_chunks("x").unwrap(),
+            vec![&[0.0, 1.0][..], &[4.0][..]]
+        );
+        assert!(dataset.column_chunks("missing").is_none());
+
+        let cloud = dataset.to_point_cloud();
+        let mass = cloud.metadata("mass").unwrap();
+        assert_eq!(mass[0], 5.0);
+        assert!(mass[1].is_nan());
+        assert_eq!(mass[2], 7.0);
+    }
+
+    #[test]
+    fn test_empty() {
+        let dataset = ArrowDataset::from_columns(columns(), Vec::new());
+        assert!(dataset.is_empty());
+        assert_eq!(dataset.bounds(), Bounds3D::zero());
+    }
+}
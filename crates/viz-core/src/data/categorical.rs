@@ -0,0 +1,181 @@
+use super::DataError;
+use std::collections::HashMap;
+
+/// Per-point category codes with a label dictionary
+///
+/// Each point stores a `u32` code indexing into [`labels`](Self::labels), or
+/// [`CategoricalField::MISSING`] when it has no value.
+///
+/// # Examples
+/// ```
+/// use viz_core::data::CategoricalField;
+///
+/// let species = CategoricalField::from_labels([Some("oak"), Some("pine"), None, Some("oak")]);
+///
+/// assert_eq!(species.labels(), &["oak", "pine"]);
+/// assert_eq!(species.codes(), &[0, 1, CategoricalField::MISSING, 0]);
+/// assert_eq!(species.label(1), Some("pine"));
+/// assert_eq!(species.label(2), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CategoricalField {
+    /// Category code per point
+    codes: Vec<u32>,
+
+    /// Label for each code
+    labels: Vec<String>,
+}
+
+impl CategoricalField {
+    /// Code of a point without a category
+    pub const MISSING: u32 = u32::MAX;
+
+    /// Create a field from codes and their labels
+    ///
+    /// # Errors
+    /// [`DataError::UnknownCategory`] if a code (other than
+    /// [`MISSING`](Self::MISSING)) has no label
+    pub fn new(codes: Vec<u32>, labels: Vec<String>) -> Result<Self, DataError> {
+        if let Some(index) = codes
+            .iter()
+            .position(|&code| code != Self::MISSING && code as usize >= labels.len())
+        {
+            return Err(DataError::UnknownCategory {
+                index,
+                code: codes[index],
+            });
+        }
+
+        Ok(Self { codes, labels })
+    }
+
+    /// Build a field from per-point labels, numbering categories in order of
+    /// first appearance
+    pub fn from_labels<I, S>(values: I) -> Self
+    where
+        I: IntoIterator<Item = Option<S>>,
+        S: AsRef<str>,
+    {
+        let mut builder = CategoricalBuilder::new();
+        for value in values {
+            builder.push(value.as_ref().map(AsRef::as_ref));
+        }
+        builder.finish()
+    }
+
+    /// Category code per point
+    pub fn codes(&self) -> &[u32] {
+        &self.codes
+    }
+
+    /// Label for each code
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Label of the point at `index`, or None if it has no category
+    pub fn label(&self, index: usize) -> Option<&str> {
+        self.labels
+            .get(self.codes[index] as usize)
+            .map(String::as_str)
+    }
+
+    /// Number of points
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    /// Check if the field has no points
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    pub(crate) fn codes_mut(&mut self) -> &mut Vec<u32> {
+        &mut self.codes
+    }
+
+    /// Codes of `other` in this field's dictionary, adding any labels it
+    /// lacks
+    pub(crate) fn translate_codes(&mut self, other: &CategoricalField) -> Vec<u32> {
+        let mapping: Vec<u32> = other
+            .labels
+            .iter()
+            .map(|label| match self.labels.iter().position(|l| l == label) {
+                Some(code) => code as u32,
+                None => {
+                    self.labels.push(label.clone());
+                    self.labels.len() as u32 - 1
+                }
+            })
+            .collect();
+
+        other
+            .codes
+            .iter()
+            .map(|&code| match code {
+                Self::MISSING => Self::MISSING,
+                code => mapping[code as usize],
+            })
+            .collect()
+    }
+
+    /// Field with the codes at `indices` and the same labels
+    pub(crate) fn gather(&self, indices: &[usize]) -> Self {
+        Self {
+            codes: indices.iter().map(|&i| self.codes[i]).collect(),
+            labels: self.labels.clone(),
+        }
+    }
+}
+
+/// Incremental builder used by the loaders
+#[derive(Debug, Default)]
+pub(crate) struct CategoricalBuilder {
+    index: HashMap<String, u32>,
+    field: CategoricalField,
+}
+
+impl CategoricalBuilder {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append one point's label
+    pub(crate) fn push(&mut self, label: Option<&str>) {
+        let code = match label {
+            None => CategoricalField::MISSING,
+            Some(label) => match self.index.get(label) {
+                Some(&code) => code,
+                None => {
+                    let code = self.field.labels.len() as u32;
+                    self.field.labels.push(label.to_string());
+                    self.index.insert(label.to_string(), code);
+                    code
+                }
+            },
+        };
+        self.field.codes.push(code);
+    }
+
+    pub(crate) fn finish(self) -> CategoricalField {
+        self.field
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_rejects_unknown_code() {
+        let labels = vec!["ground".to_string(), "water".to_string()];
+
+        let field =
+            CategoricalField::new(vec![1, CategoricalField::MISSING, 0], labels.clone()).unwrap();
+        assert_eq!(field.label(0), Some("water"));
+        assert_eq!(field.label(1), None);
+
+        let err = CategoricalField::new(vec![0, 2], labels).unwrap_err();
+        assert_eq!(err, DataError::UnknownCategory { index: 1, code: 2 });
+    }
+}
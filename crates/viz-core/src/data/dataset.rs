@@ -1,5 +1,4 @@
 use crate::math::Bounds3D;
-use wgpu::{Buffer, Device};
 
 /// Generic trait for all visualization datasets
 pub trait Dataset: Send + Sync {
@@ -0,0 +1,413 @@
+use super::{CategoricalField, Dataset, PointCloud, Selection};
+use crate::math::SplitMix64;
+use glam::{DVec3, DVec4, Vec3};
+use std::collections::HashMap;
+
+/// Downsampling operators
+///
+/// Each returns a new cloud with the same name, origin and color settings.
+/// Points with a non-finite position are never kept.
+impl PointCloud {
+    /// Merge all points in each cube of side `voxel_size` into one
+    ///
+    /// The merged point has the mean position, color, size and metadata of
+    /// the points in its voxel, with NaN metadata values left out of the
+    /// mean (a voxel of only NaNs stays NaN). Vector fields take the mean of
+    /// their finite vectors the same way. Normals are averaged and
+    /// renormalized. Categorical fields take the most common label in the
+    /// voxel. Voxels are ordered by their first point.
+    ///
+    /// # Panics
+    /// Panics if `voxel_size` is not positive
+    ///
+    /// # Examples
+    /// ```
+    /// use glam::Vec3;
+    /// use viz_core::data::{Dataset, PointCloud};
+    ///
+    /// let cloud = PointCloud::new(vec![Vec3::ZERO, Vec3::splat(0.2), Vec3::splat(5.0)])
+    ///     .with_metadata("t".to_string(), vec![1.0, 3.0, 7.0]);
+    ///
+    /// let merged = cloud.voxel_downsample(1.0);
+    /// assert_eq!(merged.len(), 2);
+    /// assert_eq!(merged.positions()[0], Vec3::splat(0.1));
+    /// assert_eq!(merged.metadata("t").unwrap(), &[2.0, 7.0]);
+    /// ```
+    pub fn voxel_downsample(&self, voxel_size: f32) -> PointCloud {
+        assert!(voxel_size > 0.0, "Voxel size must be positive");
+
+        // Voxel of every point (None for non-finite positions)
+        let mut voxels: HashMap<[i64; 3], usize> = HashMap::new();
+        let voxel_of: Vec<Option<usize>> = self
+            .positions()
+            .iter()
+            .map(|position| {
+                if !position.is_finite() {
+                    return None;
+                }
+                let key = (*position / voxel_size).floor().as_i64vec3().to_array();
+                let next = voxels.len();
+                Some(*voxels.entry(key).or_insert(next))
+            })
+            .collect();
+        let voxel_count = voxels.len();
+
+        let mut counts = vec![0u32; voxel_count];
+        let mut position_sums = vec![DVec3::ZERO; voxel_count];
+        for (position, voxel) in self.positions().iter().zip(&voxel_of) {
+            if let Some(voxel) = *voxel {
+                counts[voxel] += 1;
+                position_sums[voxel] += position.as_dvec3();
+            }
+        }
+        let positions: Vec<Vec3> = position_sums
+            .iter()
+            .zip(&counts)
+            .map(|(sum, &count)| (*sum / count as f64).as_vec3())
+            .collect();
+
+        let mut cloud = PointCloud::new(positions);
+
+        if let Some(colors) = self.colors() {
+            let mut sums = vec![DVec4::ZERO; voxel_count];
+            for (color, voxel) in colors.iter().zip(&voxel_of) {
+                if let Some(voxel) = *voxel {
+                    sums[voxel] += color.as_dvec4();
+                }
+            }
+            let colors = sums
+                .iter()
+                .zip(&counts)
+                .map(|(sum, &count)| (*sum / count as f64).as_vec4())
+                .collect();
+            cloud = cloud.with_colors(colors);
+        }
+
+        if let Some(sizes) = self.sizes() {
+            cloud = cloud.with_sizes(mean_per_voxel(sizes, &voxel_of, voxel_count));
+        }
+
+        if let Some(normals) = self.normals() {
+            let mut sums = vec![Vec3::ZERO; voxel_count];
+            for (normal, voxel) in normals.iter().zip(&voxel_of) {
+                if let Some(voxel) = *voxel {
+                    sums[voxel] += *normal;
+                }
+            }
+            let normals = sums.iter().map(|sum| sum.normalize_or_zero()).collect();
+            cloud = cloud.with_normals(normals);
+        }
+
+        for key in self.metadata_keys() {
+            let values = self.metadata(key).unwrap_or_default();
+            cloud =
+                cloud.with_metadata(key.clone(), mean_per_voxel(values, &voxel_of, voxel_count));
+        }
+
+        for key in self.vector_keys() {
+            let vectors = self.vectors(key).unwrap_or_default();
+            cloud = cloud.with_vectors(
+                key.clone(),
+                mean_vector_per_voxel(vectors, &voxel_of, voxel_count),
+            );
+        }
+
+        for key in self.categorical_keys() {
+            let Some(field) = self.categorical(key) else {
+                continue;
+            };
+            let codes = mode_per_voxel(field.codes(), &voxel_of, voxel_count);
+            let labels = field.labels().to_vec();
+            if let Ok(field) = CategoricalField::new(codes, labels) {
+                cloud = cloud.with_categorical(key.clone(), field);
+            }
+        }
+
+        self.with_settings(cloud)
+    }
+
+    /// Keep `count` points chosen uniformly at random
+    ///
+    /// The same seed always picks the same points, and the kept points stay
+    /// in their original order. Asking for more points than are finite keeps
+    /// all of them.
+    pub fn random_downsample(&self, count: usize, seed: u64) -> PointCloud {
+        let finite: Vec<usize> = self.finite_indices();
+        let mut rng = SplitMix64::new(seed);
+
+        // Selection sampling (Knuth's Algorithm S) keeps the indices sorted
+        let mut needed = count.min(finite.len());
+        let mut kept = Vec::with_capacity(needed);
+        for (i, &index) in finite.iter().enumerate() {
+            if needed == 0 {
+                break;
+            }
+            let remaining = finite.len() - i;
+            if rng.next_f64() * (remaining as f64) < needed as f64 {
+                kept.push(index);
+                needed -= 1;
+            }
+        }
+
+        self.subset(&Selection::from_indices(kept))
+    }
+
+    /// Keep a subset in which no two points are closer than `radius`
+    ///
+    /// Points are visited in a random order given by `seed` and kept if no
+    /// kept point lies within `radius`, which gives an even, blue-noise-like
+    /// coverage. Kept points stay in their original order.
+    ///
+    /// # Panics
+    /// Panics if `radius` is not positive
+    pub fn poisson_disk_downsample(&self, radius: f32, seed: u64) -> PointCloud {
+        assert!(radius > 0.0, "Poisson-disk radius must be positive");
+
+        let positions = self.positions();
+        let mut order = self.finite_indices();
+        SplitMix64::new(seed).shuffle(&mut order);
+
+        // Grid of kept points with cells one radius wide, so every neighbour
+        // within the radius is in the surrounding 3×3×3 cells
+        let cell_of = |p: Vec3| (p / radius).floor().as_i64vec3().to_array();
+        let mut grid: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+        let radius_squared = radius * radius;
+        let mut kept = Vec::new();
+
+        for index in order {
+            let position = positions[index];
+            let [x, y, z] = cell_of(position);
+
+            let blocked = (-1..=1).any(|dx| {
+                (-1..=1).any(|dy| {
+                    (-1..=1).any(|dz| {
+                        grid.get(&[x + dx, y + dy, z + dz]).is_some_and(|cell| {
+                            cell.iter().any(|&other| {
+                                positions[other].distance_squared(position) < radius_squared
+                            })
+                        })
+                    })
+                })
+            });
+
+            if !blocked {
+                grid.entry([x, y, z]).or_default().push(index);
+                kept.push(index);
+            }
+        }
+
+        self.subset(&Selection::from_indices(kept))
+    }
+
+    /// Indices of points with a finite position
+    fn finite_indices(&self) -> Vec<usize> {
+        self.positions()
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.is_finite())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Copy the name, origin, field attributes and color settings onto a
+    /// derived cloud
+    fn with_settings(&self, cloud: PointCloud) -> PointCloud {
+        let mut cloud = cloud
+            .with_name(self.name().to_string())
+            .with_origin(self.origin())
+            .with_bad_value_color(self.bad_value_color())
+            .with_nan_policy(self.nan_policy());
+        let keys = self
+            .metadata_keys()
+            .into_iter()
+            .chain(self.categorical_keys());
+        for key in keys.chain(self.vector_keys()) {
+            if let Some(info) = self.field_info(key) {
+                cloud.set_field_info(key.clone(), info.clone());
+            }
+        }
+        cloud
+    }
+}
+
+/// Mean of the finite values in each voxel (NaN if there are none)
+fn mean_per_voxel(values: &[f32], voxel_of: &[Option<usize>], voxel_count: usize) -> Vec<f32> {
+    let mut sums = vec![0.0f64; voxel_count];
+    let mut counts = vec![0u32; voxel_count];
+    for (&value, voxel) in values.iter().zip(voxel_of) {
+        if let Some(voxel) = *voxel {
+            if value.is_finite() {
+                sums[voxel] += value as f64;
+                counts[voxel] += 1;
+            }
+        }
+    }
+
+    sums.iter()
+        .zip(&counts)
+        .map(|(&sum, &count)| match count {
+            0 => f32::NAN,
+            count => (sum / count as f64) as f32,
+        })
+        .collect()
+}
+
+/// Mean of the finite vectors in each voxel (NaN if there are none)
+fn mean_vector_per_voxel(
+    vectors: &[Vec3],
+    voxel_of: &[Option<usize>],
+    voxel_count: usize,
+) -> Vec<Vec3> {
+    let mut sums = vec![DVec3::ZERO; voxel_count];
+    let mut counts = vec![0u32; voxel_count];
+    for (vector, voxel) in vectors.iter().zip(voxel_of) {
+        if let Some(voxel) = *voxel {
+            if vector.is_finite() {
+                sums[voxel] += vector.as_dvec3();
+                counts[voxel] += 1;
+            }
+        }
+    }
+
+    sums.iter()
+        .zip(&counts)
+        .map(|(&sum, &count)| match count {
+            0 => Vec3::NAN,
+            count => (sum / count as f64).as_vec3(),
+        })
+        .collect()
+}
+
+/// Most common code in each voxel, ignoring missing values (ties go to the
+/// lower code)
+fn mode_per_voxel(codes: &[u32], voxel_of: &[Option<usize>], voxel_count: usize) -> Vec<u32> {
+    let mut pairs: Vec<(usize, u32)> = codes
+        .iter()
+        .zip(voxel_of)
+        .filter(|(&code, _)| code != CategoricalField::MISSING)
+        .filter_map(|(&code, voxel)| voxel.map(|voxel| (voxel, code)))
+        .collect();
+    pairs.sort_unstable();
+
+    let mut modes = vec![CategoricalField::MISSING; voxel_count];
+    let mut best_count = vec![0usize; voxel_count];
+    for run in pairs.chunk_by(|a, b| a == b) {
+        let (voxel, code) = run[0];
+        if run.len() > best_count[voxel] {
+            best_count[voxel] = run.len();
+            modes[voxel] = code;
+        }
+    }
+    modes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec4;
+
+    fn grid_cloud(side: usize) -> PointCloud {
+        let positions: Vec<Vec3> = (0..side * side * side)
+            .map(|i| {
+                Vec3::new(
+                    (i % side) as f32,
+                    ((i / side) % side) as f32,
+                    (i / side / side) as f32,
+                ) * 0.1
+            })
+            .collect();
+        let n = positions.len();
+        PointCloud::new(positions)
+            .with_metadata("id".to_string(), (0..n).map(|i| i as f32).collect())
+            .with_name("grid")
+    }
+
+    #[test]
+    fn test_voxel_aggregates_every_field() {
+        let cloud = PointCloud::new(vec![
+            Vec3::new(0.1, 0.1, 0.1),
+            Vec3::new(0.3, 0.5, 0.1),
+            Vec3::NAN,
+            Vec3::new(0.9, 0.9, 0.9),
+            Vec3::new(2.5, 0.0, 0.0),
+        ])
+        .with_colors(vec![Vec4::X, Vec4::Y, Vec4::Z, Vec4::X, Vec4::W])
+        .with_sizes(vec![1.0, 3.0, 9.0, 5.0, 2.0])
+        .with_normals(vec![Vec3::Z, Vec3::X, Vec3::Y, Vec3::Z, Vec3::Y])
+        .with_metadata("t".to_string(), vec![10.0, f32::NAN, 99.0, 20.0, f32::NAN])
+        .with_categorical(
+            "class",
+            CategoricalField::from_labels([Some("a"), Some("b"), Some("b"), Some("b"), None]),
+        )
+        .with_vectors("v", vec![Vec3::X, Vec3::NAN, Vec3::Y, Vec3::Y, Vec3::NAN])
+        .with_origin(DVec3::new(1000.0, 0.0, 0.0))
+        .with_name("scan");
+
+        let merged = cloud.voxel_downsample(1.0);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged.name(), "scan");
+        assert_eq!(merged.origin(), cloud.origin());
+
+        assert!(merged.positions()[0].abs_diff_eq(Vec3::new(1.3, 1.5, 1.1) / 3.0, 1e-6));
+        assert_eq!(merged.positions()[1], Vec3::new(2.5, 0.0, 0.0));
+        assert!(merged.colors().unwrap()[0].abs_diff_eq(Vec4::new(2.0, 1.0, 0.0, 0.0) / 3.0, 1e-6));
+        assert_eq!(merged.sizes().unwrap(), &[3.0, 2.0]);
+        let normal = merged.normals().unwrap()[0];
+        assert!(normal.abs_diff_eq(Vec3::new(1.0, 0.0, 2.0) / 5f32.sqrt(), 1e-6));
+
+        let t = merged.metadata("t").unwrap();
+        assert_eq!(t[0], 15.0);
+        assert!(t[1].is_nan());
+
+        let v = merged.vectors("v").unwrap();
+        assert_eq!(v[0], Vec3::new(0.5, 0.5, 0.0));
+        assert!(v[1].is_nan());
+
+        let class = merged.categorical("class").unwrap();
+        assert_eq!(class.label(0), Some("b"));
+        assert_eq!(class.label(1), None);
+    }
+
+    #[test]
+    fn test_random_is_seeded_and_keeps_metadata() {
+        let cloud = grid_cloud(10);
+
+        let a = cloud.random_downsample(100, 42);
+        let b = cloud.random_downsample(100, 42);
+        let c = cloud.random_downsample(100, 7);
+        assert_eq!(a.len(), 100);
+        assert_eq!(a.positions(), b.positions());
+        assert_ne!(a.positions(), c.positions());
+
+        // Metadata follows the kept points, which stay in order
+        let ids = a.metadata("id").unwrap();
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+        for (position, &id) in a.positions().iter().zip(ids) {
+            assert_eq!(*position, cloud.positions()[id as usize]);
+        }
+
+        assert_eq!(cloud.random_downsample(5000, 1).len(), cloud.len());
+    }
+
+    #[test]
+    fn test_poisson_disk_spacing() {
+        let cloud = grid_cloud(12);
+        let radius = 0.25;
+
+        let sampled = cloud.poisson_disk_downsample(radius, 3);
+        assert!(sampled.len() > 1 && sampled.len() < cloud.len());
+        assert_eq!(sampled.name(), "grid");
+
+        let positions = sampled.positions();
+        for (i, a) in positions.iter().enumerate() {
+            for b in &positions[i + 1..] {
+                assert!(a.distance(*b) >= radius);
+            }
+        }
+
+        // Every input point is covered by some kept point
+        for point in cloud.positions() {
+            assert!(positions.iter().any(|p| p.distance(*point) < radius));
+        }
+    }
+}
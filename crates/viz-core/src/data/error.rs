@@ -0,0 +1,33 @@
+use thiserror::Error;
+
+/// Errors from building or transforming a dataset
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum DataError {
+    #[error("{field} has {found} values, expected {expected} (one per point)")]
+    LengthMismatch {
+        field: String,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("Metadata field '{0}' not found")]
+    MissingField(String),
+
+    #[error("{field} has a non-finite value at index {index}")]
+    NonFinite { field: String, index: usize },
+
+    #[error("Category code {code} at index {index} has no label")]
+    UnknownCategory { index: usize, code: u32 },
+
+    #[error("Invalid expression at column {column}: {message}")]
+    InvalidExpression { column: usize, message: String },
+
+    #[error("Field '{field}' is present in only one of the point clouds")]
+    FieldMismatch { field: String },
+
+    #[error("No row has a finite value in every column")]
+    NoCompleteRows,
+
+    #[error("Percentile range {low} to {high} is not within [0, 100] in ascending order")]
+    InvalidPercentile { low: f32, high: f32 },
+}
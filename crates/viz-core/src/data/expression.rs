@@ -0,0 +1,843 @@
+use super::{DataError, PointCloud};
+use std::fmt;
+use std::str::FromStr;
+
+/// Arithmetic expression over point positions and metadata fields
+///
+/// Evaluated once per point to derive a new metadata field. The language has
+/// - numbers (`2`, `0.5`, `1e-3`) and the constants `pi` and `e`
+/// - `x`, `y` and `z` for the point's world position (the cloud's origin plus
+///   its local position) and any metadata field by name; names that are not
+///   plain identifiers go in backticks (`` `gps time` ``)
+/// - `+ - * / %` and `^` for powers, with the usual precedence
+/// - comparisons `< <= > >= == !=` and logic `&& || !`, which give 1 or 0
+/// - conditionals `cond ? a : b` or `if(cond, a, b)`
+/// - the functions `abs sqrt cbrt exp ln log10 log2 sin cos tan asin acos
+///   atan floor ceil round sign isnan` of one argument, `atan2 pow min max
+///   hypot` of two and `clamp(value, min, max)`
+///
+/// NaN follows IEEE rules: it propagates through arithmetic, compares false,
+/// counts as false in `&&`, `||` and `!`, and a NaN condition gives NaN.
+/// Evaluation runs in double precision, so georeferenced coordinates keep
+/// their detail until the result is stored as f32.
+///
+/// # Examples
+/// ```
+/// use glam::Vec3;
+/// use viz_core::data::{Expression, PointCloud};
+///
+/// let cloud = PointCloud::new(vec![Vec3::new(3.0, 1.0, 4.0), Vec3::new(0.0, 2.0, 0.0)])
+///     .with_metadata("intensity".to_string(), vec![100.0, 0.0]);
+///
+/// let radius = Expression::parse("sqrt(x*x + z*z)").unwrap();
+/// assert_eq!(radius.evaluate(&cloud).unwrap(), [5.0, 0.0]);
+///
+/// let level = Expression::parse("intensity > 0 ? log10(intensity) : -1").unwrap();
+/// assert_eq!(level.evaluate(&cloud).unwrap(), [2.0, -1.0]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    source: String,
+    root: Node,
+    /// Variable names, indexed by [`Node::Variable`]
+    variables: Vec<String>,
+}
+
+impl Expression {
+    /// Parse an expression
+    ///
+    /// # Errors
+    /// [`DataError::InvalidExpression`] with the column of the problem
+    pub fn parse(source: &str) -> Result<Self, DataError> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            next: 0,
+            depth: 0,
+            variables: Vec::new(),
+        };
+        let root = parser.conditional()?;
+        let token = parser.peek();
+        if token.kind != TokenKind::End {
+            return Err(token.error(format!("unexpected {}", token.kind)));
+        }
+
+        Ok(Self {
+            source: source.to_string(),
+            root,
+            variables: parser.variables,
+        })
+    }
+
+    /// The expression text as parsed
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Names of the positions and metadata fields the expression reads
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+
+    /// Evaluate the expression for every point
+    ///
+    /// # Errors
+    /// [`DataError::MissingField`] if a variable is neither `x`, `y`, `z`
+    /// nor a metadata field of the cloud
+    pub fn evaluate(&self, cloud: &PointCloud) -> Result<Vec<f32>, DataError> {
+        let columns = self
+            .variables
+            .iter()
+            .map(|name| match name.as_str() {
+                "x" => Ok(Column::Axis(0)),
+                "y" => Ok(Column::Axis(1)),
+                "z" => Ok(Column::Axis(2)),
+                _ => cloud
+                    .metadata(name)
+                    .map(Column::Values)
+                    .ok_or_else(|| DataError::MissingField(name.clone())),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let positions = cloud.positions();
+        let origin = cloud.origin();
+        Ok((0..positions.len())
+            .map(|i| {
+                let variable = |index: usize| match columns[index] {
+                    Column::Axis(axis) => origin[axis] + positions[i][axis] as f64,
+                    Column::Values(values) => values[i] as f64,
+                };
+                self.root.evaluate(&variable) as f32
+            })
+            .collect())
+    }
+}
+
+impl FromStr for Expression {
+    type Err = DataError;
+
+    fn from_str(source: &str) -> Result<Self, DataError> {
+        Self::parse(source)
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Where a variable's values come from
+enum Column<'a> {
+    Axis(usize),
+    Values(&'a [f32]),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Number(f64),
+    Variable(usize),
+    Negate(Box<Node>),
+    Not(Box<Node>),
+    Binary(BinaryOp, Box<Node>, Box<Node>),
+    Call(Function, Vec<Node>),
+    Conditional(Box<Node>, Box<Node>, Box<Node>),
+}
+
+impl Node {
+    /// Number of nodes on the longest path down from this one
+    fn depth(&self) -> usize {
+        1 + match self {
+            Self::Number(_) | Self::Variable(_) => 0,
+            Self::Negate(inner) | Self::Not(inner) => inner.depth(),
+            Self::Binary(_, a, b) => a.depth().max(b.depth()),
+            Self::Call(_, args) => args.iter().map(Node::depth).max().unwrap_or(0),
+            Self::Conditional(condition, then, otherwise) => {
+                condition.depth().max(then.depth()).max(otherwise.depth())
+            }
+        }
+    }
+
+    fn evaluate(&self, variable: &impl Fn(usize) -> f64) -> f64 {
+        match self {
+            Self::Number(value) => *value,
+            Self::Variable(index) => variable(*index),
+            Self::Negate(inner) => -inner.evaluate(variable),
+            Self::Not(inner) => flag(!truthy(inner.evaluate(variable))),
+            Self::Binary(op, a, b) => op.apply(a.evaluate(variable), b.evaluate(variable)),
+            Self::Call(function, args) => {
+                let mut values = [0.0; 3];
+                for (value, arg) in values.iter_mut().zip(args) {
+                    *value = arg.evaluate(variable);
+                }
+                function.apply(&values)
+            }
+            Self::Conditional(condition, then, otherwise) => {
+                let condition = condition.evaluate(variable);
+                if condition.is_nan() {
+                    f64::NAN
+                } else if condition != 0.0 {
+                    then.evaluate(variable)
+                } else {
+                    otherwise.evaluate(variable)
+                }
+            }
+        }
+    }
+}
+
+fn truthy(value: f64) -> bool {
+    value != 0.0 && !value.is_nan()
+}
+
+fn flag(value: bool) -> f64 {
+    if value {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    And,
+    Or,
+}
+
+impl BinaryOp {
+    fn apply(self, a: f64, b: f64) -> f64 {
+        match self {
+            Self::Add => a + b,
+            Self::Sub => a - b,
+            Self::Mul => a * b,
+            Self::Div => a / b,
+            Self::Rem => a % b,
+            Self::Pow => a.powf(b),
+            Self::Lt => flag(a < b),
+            Self::Le => flag(a <= b),
+            Self::Gt => flag(a > b),
+            Self::Ge => flag(a >= b),
+            Self::Eq => flag(a == b),
+            Self::Ne => flag(a != b),
+            Self::And => flag(truthy(a) && truthy(b)),
+            Self::Or => flag(truthy(a) || truthy(b)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Function {
+    Abs,
+    Sqrt,
+    Cbrt,
+    Exp,
+    Ln,
+    Log10,
+    Log2,
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Floor,
+    Ceil,
+    Round,
+    Sign,
+    IsNan,
+    Atan2,
+    Pow,
+    Min,
+    Max,
+    Hypot,
+    Clamp,
+}
+
+impl Function {
+    /// Look up a function and its number of arguments
+    fn from_name(name: &str) -> Option<(Self, usize)> {
+        Some(match name {
+            "abs" => (Self::Abs, 1),
+            "sqrt" => (Self::Sqrt, 1),
+            "cbrt" => (Self::Cbrt, 1),
+            "exp" => (Self::Exp, 1),
+            "ln" => (Self::Ln, 1),
+            "log10" => (Self::Log10, 1),
+            "log2" => (Self::Log2, 1),
+            "sin" => (Self::Sin, 1),
+            "cos" => (Self::Cos, 1),
+            "tan" => (Self::Tan, 1),
+            "asin" => (Self::Asin, 1),
+            "acos" => (Self::Acos, 1),
+            "atan" => (Self::Atan, 1),
+            "floor" => (Self::Floor, 1),
+            "ceil" => (Self::Ceil, 1),
+            "round" => (Self::Round, 1),
+            "sign" => (Self::Sign, 1),
+            "isnan" => (Self::IsNan, 1),
+            "atan2" => (Self::Atan2, 2),
+            "pow" => (Self::Pow, 2),
+            "min" => (Self::Min, 2),
+            "max" => (Self::Max, 2),
+            "hypot" => (Self::Hypot, 2),
+            "clamp" => (Self::Clamp, 3),
+            _ => return None,
+        })
+    }
+
+    fn apply(self, args: &[f64]) -> f64 {
+        let a = args[0];
+        match self {
+            Self::Abs => a.abs(),
+            Self::Sqrt => a.sqrt(),
+            Self::Cbrt => a.cbrt(),
+            Self::Exp => a.exp(),
+            Self::Ln => a.ln(),
+            Self::Log10 => a.log10(),
+            Self::Log2 => a.log2(),
+            Self::Sin => a.sin(),
+            Self::Cos => a.cos(),
+            Self::Tan => a.tan(),
+            Self::Asin => a.asin(),
+            Self::Acos => a.acos(),
+            Self::Atan => a.atan(),
+            Self::Floor => a.floor(),
+            Self::Ceil => a.ceil(),
+            Self::Round => a.round(),
+            Self::Sign => {
+                if a == 0.0 || a.is_nan() {
+                    a
+                } else {
+                    a.signum()
+                }
+            }
+            Self::IsNan => flag(a.is_nan()),
+            Self::Atan2 => a.atan2(args[1]),
+            Self::Pow => a.powf(args[1]),
+            Self::Min => a.min(args[1]),
+            Self::Max => a.max(args[1]),
+            Self::Hypot => a.hypot(args[1]),
+            // Not f64::clamp, which panics on a NaN or inverted range
+            Self::Clamp => a.max(args[1]).min(args[2]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Number(f64),
+    Identifier(String),
+    /// Backtick-quoted name, always a variable
+    Quoted(String),
+    Symbol(&'static str),
+    End,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(value) => write!(f, "number {}", value),
+            Self::Identifier(name) => write!(f, "'{}'", name),
+            Self::Quoted(name) => write!(f, "`{}`", name),
+            Self::Symbol(symbol) => write!(f, "'{}'", symbol),
+            Self::End => f.write_str("end of expression"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    /// 1-based column of the first character
+    column: usize,
+}
+
+impl Token {
+    fn error(&self, message: impl Into<String>) -> DataError {
+        invalid(self.column, message)
+    }
+}
+
+fn invalid(column: usize, message: impl Into<String>) -> DataError {
+    DataError::InvalidExpression {
+        column,
+        message: message.into(),
+    }
+}
+
+/// Symbols, longest first so `<=` is not read as `<`
+const SYMBOLS: [&str; 21] = [
+    "<=", ">=", "==", "!=", "&&", "||", "+", "-", "*", "/", "%", "^", "<", ">", "!", "(", ")", ",",
+    "?", ":", "=",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, DataError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i + 1;
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit()
+            || (c == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit))
+        {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // Exponent, only if digits follow so `2e` stays an error below
+            if i < chars.len() && matches!(chars[i], 'e' | 'E') {
+                let mut end = i + 1;
+                if end < chars.len() && matches!(chars[end], '+' | '-') {
+                    end += 1;
+                }
+                if chars.get(end).is_some_and(char::is_ascii_digit) {
+                    i = end;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let text: String = chars[start..i].iter().collect();
+            let value = text
+                .parse()
+                .map_err(|_| invalid(column, format!("invalid number '{}'", text)))?;
+            if chars
+                .get(i)
+                .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+            {
+                return Err(invalid(
+                    i + 1,
+                    format!("unexpected '{}' after number", chars[i]),
+                ));
+            }
+            tokens.push(Token {
+                kind: TokenKind::Number(value),
+                column,
+            });
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token {
+                kind: TokenKind::Identifier(chars[start..i].iter().collect()),
+                column,
+            });
+        } else if c == '`' {
+            let start = i + 1;
+            let end = chars[start..]
+                .iter()
+                .position(|&c| c == '`')
+                .map(|length| start + length)
+                .ok_or_else(|| invalid(column, "unclosed '`'"))?;
+            tokens.push(Token {
+                kind: TokenKind::Quoted(chars[start..end].iter().collect()),
+                column,
+            });
+            i = end + 1;
+        } else {
+            let symbol = SYMBOLS
+                .iter()
+                .find(|symbol| {
+                    symbol
+                        .chars()
+                        .enumerate()
+                        .all(|(j, s)| chars.get(i + j) == Some(&s))
+                })
+                .ok_or_else(|| invalid(column, format!("unexpected character '{}'", c)))?;
+            if *symbol == "=" {
+                return Err(invalid(column, "unexpected '=', use '==' to compare"));
+            }
+            tokens.push(Token {
+                kind: TokenKind::Symbol(symbol),
+                column,
+            });
+            i += symbol.len();
+        }
+    }
+
+    tokens.push(Token {
+        kind: TokenKind::End,
+        column: chars.len() + 1,
+    });
+    Ok(tokens)
+}
+
+/// Deepest nesting of parentheses, prefix operators and operator chains;
+/// parsing and evaluation recurse this deep, so it keeps the stack bounded
+const MAX_DEPTH: usize = 128;
+
+/// Recursive-descent parser, one method per precedence level
+struct Parser {
+    tokens: Vec<Token>,
+    next: usize,
+    /// Current nesting of sub-expressions
+    depth: usize,
+    variables: Vec<String>,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.next]
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.next].clone();
+        if token.kind != TokenKind::End {
+            self.next += 1;
+        }
+        token
+    }
+
+    /// Consume the symbol if it comes next
+    fn eat(&mut self, symbol: &'static str) -> bool {
+        if self.peek().kind == TokenKind::Symbol(symbol) {
+            self.next += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, symbol: &'static str) -> Result<(), DataError> {
+        if self.eat(symbol) {
+            return Ok(());
+        }
+        let token = self.peek();
+        Err(token.error(format!("expected '{}', found {}", symbol, token.kind)))
+    }
+
+    /// Parse a sub-expression one level deeper, failing past [`MAX_DEPTH`]
+    /// at the token that opened it
+    fn nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<Node, DataError>,
+    ) -> Result<Node, DataError> {
+        if self.depth == MAX_DEPTH {
+            let opener = &self.tokens[self.next - 1];
+            return Err(opener.error("expression is nested too deeply"));
+        }
+        self.depth += 1;
+        let node = parse(self);
+        self.depth -= 1;
+        node
+    }
+
+    /// Join two operands, failing if the chain grows past [`MAX_DEPTH`]
+    fn binary(&self, op: BinaryOp, a: Node, b: Node, column: usize) -> Result<Node, DataError> {
+        if self.depth + 1 + a.depth().max(b.depth()) > MAX_DEPTH {
+            return Err(invalid(column, "expression is nested too deeply"));
+        }
+        Ok(Node::Binary(op, Box::new(a), Box::new(b)))
+    }
+
+    /// `or ? conditional : conditional`
+    fn conditional(&mut self) -> Result<Node, DataError> {
+        let condition = self.or()?;
+        if !self.eat("?") {
+            return Ok(condition);
+        }
+        let then = self.nested(Self::conditional)?;
+        self.expect(":")?;
+        let otherwise = self.nested(Self::conditional)?;
+        Ok(Node::Conditional(
+            Box::new(condition),
+            Box::new(then),
+            Box::new(otherwise),
+        ))
+    }
+
+    fn or(&mut self) -> Result<Node, DataError> {
+        let mut node = self.and()?;
+        while let TokenKind::Symbol("||") = self.peek().kind {
+            let column = self.advance().column;
+            let rhs = self.and()?;
+            node = self.binary(BinaryOp::Or, node, rhs, column)?;
+        }
+        Ok(node)
+    }
+
+    fn and(&mut self) -> Result<Node, DataError> {
+        let mut node = self.comparison()?;
+        while let TokenKind::Symbol("&&") = self.peek().kind {
+            let column = self.advance().column;
+            let rhs = self.comparison()?;
+            node = self.binary(BinaryOp::And, node, rhs, column)?;
+        }
+        Ok(node)
+    }
+
+    /// Comparisons do not chain: `a < b < c` is an error
+    fn comparison(&mut self) -> Result<Node, DataError> {
+        let node = self.additive()?;
+        let op = match self.peek().kind {
+            TokenKind::Symbol("<") => BinaryOp::Lt,
+            TokenKind::Symbol("<=") => BinaryOp::Le,
+            TokenKind::Symbol(">") => BinaryOp::Gt,
+            TokenKind::Symbol(">=") => BinaryOp::Ge,
+            TokenKind::Symbol("==") => BinaryOp::Eq,
+            TokenKind::Symbol("!=") => BinaryOp::Ne,
+            _ => return Ok(node),
+        };
+        let column = self.advance().column;
+        let rhs = self.additive()?;
+        let node = self.binary(op, node, rhs, column)?;
+
+        let token = self.peek();
+        if matches!(
+            token.kind,
+            TokenKind::Symbol("<" | "<=" | ">" | ">=" | "==" | "!=")
+        ) {
+            return Err(token.error("comparisons cannot be chained, use '&&'"));
+        }
+        Ok(node)
+    }
+
+    fn additive(&mut self) -> Result<Node, DataError> {
+        let mut node = self.multiplicative()?;
+        loop {
+            let op = match self.peek().kind {
+                TokenKind::Symbol("+") => BinaryOp::Add,
+                TokenKind::Symbol("-") => BinaryOp::Sub,
+                _ => return Ok(node),
+            };
+            let column = self.advance().column;
+            let rhs = self.multiplicative()?;
+            node = self.binary(op, node, rhs, column)?;
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Node, DataError> {
+        let mut node = self.unary()?;
+        loop {
+            let op = match self.peek().kind {
+                TokenKind::Symbol("*") => BinaryOp::Mul,
+                TokenKind::Symbol("/") => BinaryOp::Div,
+                TokenKind::Symbol("%") => BinaryOp::Rem,
+                _ => return Ok(node),
+            };
+            let column = self.advance().column;
+            let rhs = self.unary()?;
+            node = self.binary(op, node, rhs, column)?;
+        }
+    }
+
+    /// Prefix `-` and `!` bind looser than `^`, so `-x^2` is `-(x^2)`
+    fn unary(&mut self) -> Result<Node, DataError> {
+        if self.eat("-") {
+            return Ok(Node::Negate(Box::new(self.nested(Self::unary)?)));
+        }
+        if self.eat("+") {
+            return self.nested(Self::unary);
+        }
+        if self.eat("!") {
+            return Ok(Node::Not(Box::new(self.nested(Self::unary)?)));
+        }
+        self.power()
+    }
+
+    /// Right-associative: `2^3^2` is `2^(3^2)`
+    fn power(&mut self) -> Result<Node, DataError> {
+        let base = self.primary()?;
+        if self.eat("^") {
+            return Ok(Node::Binary(
+                BinaryOp::Pow,
+                Box::new(base),
+                Box::new(self.nested(Self::unary)?),
+            ));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Node, DataError> {
+        let Token { kind, column } = self.advance();
+        match kind {
+            TokenKind::Number(value) => Ok(Node::Number(value)),
+            TokenKind::Quoted(name) => Ok(self.variable(name)),
+            TokenKind::Identifier(name) if self.peek().kind == TokenKind::Symbol("(") => {
+                self.call(&name, column)
+            }
+            TokenKind::Identifier(name) => Ok(match name.as_str() {
+                "pi" => Node::Number(std::f64::consts::PI),
+                "e" => Node::Number(std::f64::consts::E),
+                _ => self.variable(name),
+            }),
+            TokenKind::Symbol("(") => {
+                let node = self.nested(Self::conditional)?;
+                self.expect(")")?;
+                Ok(node)
+            }
+            kind => Err(invalid(column, format!("expected a value, found {}", kind))),
+        }
+    }
+
+    fn call(&mut self, name: &str, column: usize) -> Result<Node, DataError> {
+        self.expect("(")?;
+        let mut args = Vec::new();
+        if !self.eat(")") {
+            loop {
+                args.push(self.nested(Self::conditional)?);
+                if self.eat(")") {
+                    break;
+                }
+                self.expect(",")?;
+            }
+        }
+
+        if name == "if" {
+            if args.len() != 3 {
+                return Err(invalid(
+                    column,
+                    format!("if() takes 3 arguments, found {}", args.len()),
+                ));
+            }
+            let mut args = args.into_iter().map(Box::new);
+            let (condition, then, otherwise) = (
+                args.next().unwrap(),
+                args.next().unwrap(),
+                args.next().unwrap(),
+            );
+            return Ok(Node::Conditional(condition, then, otherwise));
+        }
+
+        let (function, arity) = Function::from_name(name)
+            .ok_or_else(|| invalid(column, format!("unknown function '{}'", name)))?;
+        if args.len() != arity {
+            let plural = if arity == 1 { "" } else { "s" };
+            return Err(invalid(
+                column,
+                format!(
+                    "{}() takes {} argument{}, found {}",
+                    name,
+                    arity,
+                    plural,
+                    args.len()
+                ),
+            ));
+        }
+        Ok(Node::Call(function, args))
+    }
+
+    fn variable(&mut self, name: String) -> Node {
+        let index = match self.variables.iter().position(|v| *v == name) {
+            Some(index) => index,
+            None => {
+                self.variables.push(name);
+                self.variables.len() - 1
+            }
+        };
+        Node::Variable(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec3;
+
+    fn evaluate(source: &str) -> f32 {
+        let cloud = PointCloud::new(vec![Vec3::new(3.0, -2.0, 4.0)])
+            .with_metadata("t".to_string(), vec![10.0])
+            .with_metadata("gps time".to_string(), vec![f32::NAN]);
+        Expression::parse(source).unwrap().evaluate(&cloud).unwrap()[0]
+    }
+
+    fn column(source: &str) -> usize {
+        match Expression::parse(source).unwrap_err() {
+            DataError::InvalidExpression { column, .. } => column,
+            err => panic!("unexpected error {err}"),
+        }
+    }
+
+    #[test]
+    fn test_precedence_and_functions() {
+        assert_eq!(evaluate("1 + 2 * 3 - 4 / 2"), 5.0);
+        assert_eq!(evaluate("-2^2"), -4.0);
+        assert_eq!(evaluate("2^3^2"), 512.0);
+        assert_eq!(evaluate("(1 + 2) * 3 % 5"), 4.0);
+        assert_eq!(evaluate("hypot(x, z) + abs(y)"), 7.0);
+        assert_eq!(evaluate("clamp(t, 0, 5) + min(x, y) + max(x, y)"), 6.0);
+        assert_eq!(evaluate("log10(t * 10) + 1.5e1"), 17.0);
+        assert_eq!(evaluate("round(pi * 100)"), 314.0);
+    }
+
+    #[test]
+    fn test_comparisons_conditionals_and_nan() {
+        assert_eq!(evaluate("x > 2 && y < 0"), 1.0);
+        assert_eq!(evaluate("!(x == 3) || t != 10"), 0.0);
+        assert_eq!(evaluate("x >= 3 ? t : -t"), 10.0);
+        assert_eq!(evaluate("if(x < 3, 1, y < 0 ? 2 : 3)"), 2.0);
+        assert!(evaluate("`gps time` + 1").is_nan());
+        assert_eq!(evaluate("`gps time` > 0 || isnan(`gps time`)"), 1.0);
+        assert!(evaluate("`gps time` ? 1 : 2").is_nan());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(column("x + * 2"), 5);
+        assert_eq!(column("sqrt(x"), 7);
+        assert_eq!(column("x # 2"), 3);
+        assert_eq!(column("x = 2"), 3);
+        assert_eq!(column("1 < x < 2"), 7);
+        assert_eq!(column("2 * foo(x)"), 5);
+        assert_eq!(column("max(x)"), 1);
+        assert_eq!(column("x ? 1"), 6);
+        assert_eq!(column("3x"), 2);
+        assert_eq!(column("`open"), 1);
+
+        // Deep nesting is an error, not a stack overflow
+        assert_eq!(column(&"(".repeat(100_000)), MAX_DEPTH + 1);
+        assert_eq!(column(&"-".repeat(100_000)), MAX_DEPTH + 1);
+        assert_eq!(column(&"2^".repeat(100_000)), 2 * MAX_DEPTH + 2);
+        assert!(column(&vec!["x"; 100_000].join("+")) <= 2 * MAX_DEPTH);
+        assert_eq!(evaluate(&vec!["1"; MAX_DEPTH].join("+")), MAX_DEPTH as f32);
+
+        let err = Expression::parse("log(x)").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid expression at column 1: unknown function 'log'"
+        );
+    }
+
+    #[test]
+    fn test_positions_are_world_coordinates() {
+        let cloud = PointCloud::new(vec![Vec3::new(0.25, -0.5, 1.0)])
+            .with_origin(glam::DVec3::new(500_000.0, 4_000_000.0, 100.0));
+
+        let evaluate = |source: &str| {
+            let expression = Expression::parse(source).unwrap();
+            expression.evaluate(&cloud).unwrap()[0]
+        };
+        assert_eq!(evaluate("x - 500000"), 0.25);
+        assert_eq!(evaluate("y - 4000000"), -0.5);
+        assert_eq!(evaluate("z > 100"), 1.0);
+        assert_eq!(evaluate("z"), 101.0);
+    }
+
+    #[test]
+    fn test_missing_variable() {
+        let cloud = PointCloud::new(vec![Vec3::ZERO]);
+        let expression = Expression::parse("vx * vx + x").unwrap();
+        assert_eq!(expression.variables(), ["vx", "x"]);
+        assert_eq!(
+            expression.evaluate(&cloud).unwrap_err(),
+            DataError::MissingField("vx".to_string())
+        );
+    }
+}
@@ -0,0 +1,144 @@
+/// Descriptive attributes of a field: unit, long name, description and
+/// valid range
+///
+/// Attach them with [`PointCloud::with_field_info`](super::PointCloud::with_field_info);
+/// loaders fill them in where the file format records them.
+///
+/// # Examples
+/// ```
+/// use viz_core::data::FieldInfo;
+///
+/// let info = FieldInfo::new()
+///     .with_long_name("Temperature")
+///     .with_unit("K")
+///     .with_valid_range(0.0, 10_000.0);
+///
+/// assert_eq!(info.label("temp"), "Temperature [K]");
+/// assert_eq!(FieldInfo::new().label("temp"), "temp");
+/// assert!(!info.is_valid(-1.0));
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FieldInfo {
+    unit: Option<String>,
+    long_name: Option<String>,
+    description: Option<String>,
+    valid_range: Option<(f32, f32)>,
+}
+
+impl FieldInfo {
+    /// Create attributes with nothing set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the unit, e.g. `K` or `m/s`
+    pub fn with_unit(mut self, unit: impl Into<String>) -> Self {
+        self.unit = Some(unit.into());
+        self
+    }
+
+    /// Set a human-readable name to show instead of the field key
+    pub fn with_long_name(mut self, long_name: impl Into<String>) -> Self {
+        self.long_name = Some(long_name.into());
+        self
+    }
+
+    /// Set a longer description, shown as a tooltip
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the range of physically valid values
+    ///
+    /// # Panics
+    /// Panics if `min > max`
+    pub fn with_valid_range(mut self, min: f32, max: f32) -> Self {
+        assert!(
+            min <= max,
+            "Valid range minimum must not exceed the maximum"
+        );
+        self.valid_range = Some((min, max));
+        self
+    }
+
+    /// Get the unit
+    pub fn unit(&self) -> Option<&str> {
+        self.unit.as_deref()
+    }
+
+    /// Get the long name
+    pub fn long_name(&self) -> Option<&str> {
+        self.long_name.as_deref()
+    }
+
+    /// Get the description
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Get the valid range as `(min, max)`
+    pub fn valid_range(&self) -> Option<(f32, f32)> {
+        self.valid_range
+    }
+
+    /// Check if no attribute is set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Check if a value lies in the valid range (always true without one)
+    pub fn is_valid(&self, value: f32) -> bool {
+        self.valid_range
+            .is_none_or(|(min, max)| (min..=max).contains(&value))
+    }
+
+    /// Display label such as `Temperature [K]`, falling back to `key` for
+    /// the name
+    pub fn label(&self, key: &str) -> String {
+        let name = self.long_name.as_deref().unwrap_or(key);
+        match &self.unit {
+            Some(unit) if !unit.is_empty() => format!("{} [{}]", name, unit),
+            _ => name.to_string(),
+        }
+    }
+
+    /// Multi-line summary of the attributes for a tooltip
+    pub fn tooltip(&self, key: &str) -> String {
+        let mut lines = vec![self.label(key)];
+        if self.long_name.is_some() {
+            lines.push(format!("Field: {}", key));
+        }
+        if let Some(description) = &self.description {
+            lines.push(description.clone());
+        }
+        if let Some((min, max)) = self.valid_range {
+            lines.push(format!("Valid range: {} to {}", min, max));
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label_and_tooltip() {
+        let info = FieldInfo::new()
+            .with_unit("m/s")
+            .with_description("Speed along the flow");
+        assert_eq!(info.label("speed"), "speed [m/s]");
+        assert_eq!(info.tooltip("speed"), "speed [m/s]\nSpeed along the flow");
+
+        let named = info
+            .with_long_name("Flow speed")
+            .with_valid_range(0.0, 50.0);
+        assert_eq!(
+            named.tooltip("speed"),
+            "Flow speed [m/s]\nField: speed\nSpeed along the flow\nValid range: 0 to 50"
+        );
+        assert!(named.is_valid(50.0) && !named.is_valid(f32::NAN));
+        assert!(FieldInfo::new().is_empty() && !named.is_empty());
+    }
+}
@@ -0,0 +1,251 @@
+use super::{DataError, PointCloud};
+use std::ops::Not;
+
+/// Comparison used by [`Filter::Compare`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl CompareOp {
+    fn apply(self, a: f32, b: f32) -> bool {
+        match self {
+            Self::Lt => a < b,
+            Self::Le => a <= b,
+            Self::Gt => a > b,
+            Self::Ge => a >= b,
+            Self::Eq => a == b,
+            Self::Ne => a != b,
+        }
+    }
+}
+
+/// Predicate over the metadata of a [`PointCloud`]
+///
+/// Comparisons follow IEEE rules, so a NaN value matches only
+/// [`CompareOp::Ne`]; wrap a filter in `!` to invert it.
+///
+/// # Examples
+/// ```
+/// use glam::Vec3;
+/// use viz_core::data::{Filter, PointCloud};
+///
+/// let cloud = PointCloud::new(vec![Vec3::ZERO, Vec3::X, Vec3::Y])
+///     .with_metadata("temperature".to_string(), vec![290.0, 310.0, 320.0])
+///     .with_metadata("class".to_string(), vec![2.0, 2.0, 6.0]);
+///
+/// // temperature > 300 and class == 2
+/// let filter = Filter::gt("temperature", 300.0).and(Filter::eq("class", 2.0));
+///
+/// assert_eq!(cloud.select(&filter).unwrap().indices(), &[1]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    /// Compare a metadata field against a constant
+    Compare {
+        field: String,
+        op: CompareOp,
+        value: f32,
+    },
+    /// Metadata field within an inclusive range
+    Between {
+        field: String,
+        min: f32,
+        max: f32,
+    },
+    /// Categorical field equal to a label
+    Category {
+        field: String,
+        label: String,
+    },
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+}
+
+impl Filter {
+    /// `field < value`
+    pub fn lt(field: impl Into<String>, value: f32) -> Self {
+        Self::compare(field, CompareOp::Lt, value)
+    }
+
+    /// `field <= value`
+    pub fn le(field: impl Into<String>, value: f32) -> Self {
+        Self::compare(field, CompareOp::Le, value)
+    }
+
+    /// `field > value`
+    pub fn gt(field: impl Into<String>, value: f32) -> Self {
+        Self::compare(field, CompareOp::Gt, value)
+    }
+
+    /// `field >= value`
+    pub fn ge(field: impl Into<String>, value: f32) -> Self {
+        Self::compare(field, CompareOp::Ge, value)
+    }
+
+    /// `field == value`
+    pub fn eq(field: impl Into<String>, value: f32) -> Self {
+        Self::compare(field, CompareOp::Eq, value)
+    }
+
+    /// `field != value`
+    pub fn ne(field: impl Into<String>, value: f32) -> Self {
+        Self::compare(field, CompareOp::Ne, value)
+    }
+
+    /// `min <= field <= max`
+    pub fn between(field: impl Into<String>, min: f32, max: f32) -> Self {
+        Self::Between {
+            field: field.into(),
+            min,
+            max,
+        }
+    }
+
+    /// Categorical `field` has the given label
+    pub fn category(field: impl Into<String>, label: impl Into<String>) -> Self {
+        Self::Category {
+            field: field.into(),
+            label: label.into(),
+        }
+    }
+
+    /// Both filters match
+    pub fn and(self, other: Filter) -> Self {
+        Self::And(Box::new(self), Box::new(other))
+    }
+
+    /// Either filter matches
+    pub fn or(self, other: Filter) -> Self {
+        Self::Or(Box::new(self), Box::new(other))
+    }
+
+    fn compare(field: impl Into<String>, op: CompareOp, value: f32) -> Self {
+        Self::Compare {
+            field: field.into(),
+            op,
+            value,
+        }
+    }
+
+    /// Evaluate the filter for every point
+    ///
+    /// # Errors
+    /// [`DataError::MissingField`] if a referenced field does not exist
+    pub fn mask(&self, cloud: &PointCloud) -> Result<Vec<bool>, DataError> {
+        let field_values = |field: &str| {
+            cloud
+                .metadata(field)
+                .ok_or_else(|| DataError::MissingField(field.to_string()))
+        };
+
+        Ok(match self {
+            Self::Compare { field, op, value } => field_values(field)?
+                .iter()
+                .map(|&v| op.apply(v, *value))
+                .collect(),
+            Self::Between { field, min, max } => field_values(field)?
+                .iter()
+                .map(|&v| v >= *min && v <= *max)
+                .collect(),
+            Self::Category { field, label } => {
+                let categories = cloud
+                    .categorical(field)
+                    .ok_or_else(|| DataError::MissingField(field.clone()))?;
+                match categories.labels().iter().position(|l| l == label) {
+                    Some(code) => categories
+                        .codes()
+                        .iter()
+                        .map(|&c| c as usize == code)
+                        .collect(),
+                    None => vec![false; categories.len()],
+                }
+            }
+            Self::And(a, b) => {
+                let mut mask = a.mask(cloud)?;
+                mask.iter_mut()
+                    .zip(b.mask(cloud)?)
+                    .for_each(|(m, other)| *m &= other);
+                mask
+            }
+            Self::Or(a, b) => {
+                let mut mask = a.mask(cloud)?;
+                mask.iter_mut()
+                    .zip(b.mask(cloud)?)
+                    .for_each(|(m, other)| *m |= other);
+                mask
+            }
+            Self::Not(inner) => inner.mask(cloud)?.into_iter().map(|m| !m).collect(),
+        })
+    }
+}
+
+impl Not for Filter {
+    type Output = Filter;
+
+    fn not(self) -> Filter {
+        Filter::Not(Box::new(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CategoricalField;
+    use glam::Vec3;
+
+    fn sample_cloud() -> PointCloud {
+        PointCloud::new(vec![Vec3::ZERO; 4])
+            .with_metadata("t".to_string(), vec![1.0, f32::NAN, 3.0, 4.0])
+            .with_categorical(
+                "species",
+                CategoricalField::from_labels([Some("oak"), Some("pine"), None, Some("oak")]),
+            )
+    }
+
+    #[test]
+    fn test_combinators_and_nan() {
+        let cloud = sample_cloud();
+
+        let mask = |filter: Filter| filter.mask(&cloud).unwrap();
+        assert_eq!(mask(Filter::ge("t", 3.0)), [false, false, true, true]);
+        assert_eq!(mask(!Filter::ge("t", 3.0)), [true, true, false, false]);
+        assert_eq!(
+            mask(Filter::between("t", 1.0, 3.0)),
+            [true, false, true, false]
+        );
+        assert_eq!(
+            mask(Filter::category("species", "oak")),
+            [true, false, false, true]
+        );
+        assert_eq!(mask(Filter::category("species", "elm")), [false; 4]);
+        assert_eq!(
+            mask(Filter::category("species", "oak").and(Filter::gt("t", 2.0))),
+            [false, false, false, true]
+        );
+        assert_eq!(
+            mask(Filter::lt("t", 2.0).or(Filter::category("species", "pine"))),
+            [true, true, false, false]
+        );
+    }
+
+    #[test]
+    fn test_missing_field() {
+        let cloud = sample_cloud();
+
+        let err = Filter::gt("t", 0.0)
+            .and(Filter::eq("class", 2.0))
+            .mask(&cloud)
+            .unwrap_err();
+        assert_eq!(err, DataError::MissingField("class".to_string()));
+
+        // Categorical fields are not numeric metadata
+        assert!(Filter::eq("species", 0.0).mask(&cloud).is_err());
+    }
+}
@@ -1,5 +1,31 @@
+mod arrow_dataset;
+mod categorical;
 mod dataset;
+mod downsample;
+mod error;
+mod expression;
+mod field_info;
+mod filter;
+mod nan_policy;
+mod normals;
+mod pca;
 mod point_cloud;
+mod selection;
+mod stats;
+mod time_series;
 
+pub use arrow_dataset::ArrowDataset;
+pub(crate) use categorical::CategoricalBuilder;
+pub use categorical::CategoricalField;
 pub use dataset::Dataset;
+pub use error::DataError;
+pub use expression::Expression;
+pub use field_info::FieldInfo;
+pub use filter::{CompareOp, Filter};
+pub use nan_policy::NanPolicy;
+pub use normals::NormalOrientation;
+pub use pca::Pca;
 pub use point_cloud::PointCloud;
+pub use selection::Selection;
+pub use stats::{Binning, FieldStats, Histogram};
+pub use time_series::TimeSeries;
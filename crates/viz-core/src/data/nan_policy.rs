@@ -0,0 +1,17 @@
+/// How a [`PointCloud`](super::PointCloud) treats NaN and infinite values
+///
+/// Applied with [`PointCloud::with_nan_policy`](super::PointCloud::with_nan_policy)
+/// to the positions and metadata present at that point.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum NanPolicy {
+    /// Keep the values; points with non-finite positions are not drawn and
+    /// non-finite metadata is shown in the bad-value color
+    #[default]
+    Keep,
+
+    /// Remove every point with a non-finite position or metadata value
+    Drop,
+
+    /// Replace non-finite position components and metadata values
+    Substitute(f32),
+}
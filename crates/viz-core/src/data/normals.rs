@@ -0,0 +1,251 @@
+use super::PointCloud;
+use crate::spatial::KdTree;
+use glam::{DVec3, Vec3};
+use nalgebra::{Matrix3, SymmetricEigen, Vector3};
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Unused slot in the neighbour buffer of a point with fewer neighbours
+const NO_NEIGHBOR: u32 = u32::MAX;
+
+/// How [`PointCloud::with_estimated_normals`] orients the normals it estimates
+///
+/// A fitted plane has no inside or outside, so each raw normal may point
+/// either way.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NormalOrientation {
+    /// Face a viewpoint, e.g. the scanner position (in the same frame as
+    /// the positions)
+    Viewpoint(Vec3),
+    /// Agree with neighbouring normals, propagated along a minimum spanning
+    /// tree of the neighbour graph from the highest point, which faces +Z
+    Propagate,
+}
+
+impl PointCloud {
+    /// Estimate a surface normal for every point and store the normals
+    ///
+    /// Fits a plane to each point and its nearest neighbours (the normal is
+    /// the direction of least variance of the `neighbors` closest points,
+    /// the point itself included) and then orients the normals. Points
+    /// with a non-finite position or too few neighbours get a zero normal.
+    ///
+    /// # Panics
+    /// Panics if `neighbors` is less than 3
+    ///
+    /// # Examples
+    /// ```
+    /// use glam::Vec3;
+    /// use viz_core::data::{NormalOrientation, PointCloud};
+    ///
+    /// // A small patch of the z = 0 plane
+    /// let positions = (0..25)
+    ///     .map(|i| Vec3::new((i % 5) as f32, (i / 5) as f32, 0.0))
+    ///     .collect();
+    /// let cloud = PointCloud::new(positions)
+    ///     .with_estimated_normals(8, NormalOrientation::Viewpoint(Vec3::new(0.0, 0.0, -10.0)));
+    ///
+    /// assert!(cloud.normals().unwrap().iter().all(|&n| n.abs_diff_eq(-Vec3::Z, 1e-5)));
+    /// ```
+    pub fn with_estimated_normals(self, neighbors: usize, orientation: NormalOrientation) -> Self {
+        assert!(
+            neighbors >= 3,
+            "Normal estimation needs at least 3 neighbors"
+        );
+
+        let positions = self.positions();
+        let tree = KdTree::new(positions);
+        let estimate = |position: Vec3, normal: &mut Vec3, slots: &mut [u32]| {
+            if !position.is_finite() {
+                return;
+            }
+            let nearest: Vec<usize> = tree
+                .k_nearest(position, neighbors)
+                .into_iter()
+                .map(|neighbor| neighbor.index)
+                .collect();
+            *normal = fit_normal(positions, &nearest);
+            for (slot, &index) in slots.iter_mut().zip(&nearest) {
+                *slot = index as u32;
+            }
+        };
+
+        let mut normals = vec![Vec3::ZERO; positions.len()];
+        match orientation {
+            NormalOrientation::Viewpoint(viewpoint) => {
+                normals
+                    .par_iter_mut()
+                    .zip(positions.par_iter())
+                    .for_each(|(normal, &position)| estimate(position, normal, &mut []));
+
+                for (normal, &position) in normals.iter_mut().zip(positions) {
+                    if normal.dot(viewpoint - position) < 0.0 {
+                        *normal = -*normal;
+                    }
+                }
+            }
+            NormalOrientation::Propagate => {
+                // Only propagation needs the neighbours, `neighbors` per
+                // point in one flat buffer
+                let mut nearest = vec![NO_NEIGHBOR; positions.len() * neighbors];
+                normals
+                    .par_iter_mut()
+                    .zip(nearest.par_chunks_mut(neighbors))
+                    .zip(positions.par_iter())
+                    .for_each(|((normal, slots), &position)| estimate(position, normal, slots));
+
+                propagate(positions, &mut normals, &nearest, neighbors);
+            }
+        }
+
+        self.with_normals(normals)
+    }
+}
+
+/// Normal of the plane through the given points (zero if there are fewer
+/// than 3)
+fn fit_normal(positions: &[Vec3], indices: &[usize]) -> Vec3 {
+    if indices.len() < 3 {
+        return Vec3::ZERO;
+    }
+
+    let points: Vec<DVec3> = indices.iter().map(|&i| positions[i].as_dvec3()).collect();
+    let centroid = points.iter().sum::<DVec3>() / points.len() as f64;
+    let mut covariance = Matrix3::<f64>::zeros();
+    for point in &points {
+        let d = Vector3::from((*point - centroid).to_array());
+        covariance += d * d.transpose();
+    }
+
+    // The eigenvalues come unordered; the normal has the smallest
+    let eigen = SymmetricEigen::new(covariance);
+    let normal = eigen.eigenvectors.column(eigen.eigenvalues.imin());
+    Vec3::new(normal.x as f32, normal.y as f32, normal.z as f32).normalize_or_zero()
+}
+
+/// Orient normals consistently by walking a minimum spanning tree of the
+/// neighbour graph (Hoppe et al. 1992)
+///
+/// Edges between near-parallel normals are cheapest, so the orientation is
+/// passed across smooth regions before sharp edges. Each connected component
+/// starts from its highest point, oriented toward +Z. `nearest` holds
+/// `neighbors` indices per point.
+fn propagate(positions: &[Vec3], normals: &mut [Vec3], nearest: &[u32], neighbors: usize) {
+    // Reverse edges, so the graph is symmetric without copying the forward
+    // ones: the points that have `i` as a neighbour are
+    // `incoming[offsets[i]..offsets[i + 1]]`
+    let mut offsets = vec![0usize; normals.len() + 1];
+    for (i, slots) in nearest.chunks(neighbors).enumerate() {
+        for &j in slots {
+            if j != NO_NEIGHBOR && j as usize != i {
+                offsets[j as usize + 1] += 1;
+            }
+        }
+    }
+    for i in 1..offsets.len() {
+        offsets[i] += offsets[i - 1];
+    }
+    let mut fill = offsets.clone();
+    let mut incoming = vec![0u32; offsets[normals.len()]];
+    for (i, slots) in nearest.chunks(neighbors).enumerate() {
+        for &j in slots {
+            if j != NO_NEIGHBOR && j as usize != i {
+                incoming[fill[j as usize]] = i as u32;
+                fill[j as usize] += 1;
+            }
+        }
+    }
+    let edges = |i: usize| {
+        let reverse = &incoming[offsets[i]..offsets[i + 1]];
+        nearest[i * neighbors..(i + 1) * neighbors]
+            .iter()
+            .chain(reverse)
+            .filter(|&&j| j != NO_NEIGHBOR)
+            .map(|&j| j as usize)
+    };
+
+    // Points without a normal are never visited
+    let mut visited: Vec<bool> = normals.iter().map(|n| *n == Vec3::ZERO).collect();
+    let mut seeds: Vec<usize> = (0..normals.len()).filter(|&i| !visited[i]).collect();
+    seeds.sort_by(|&a, &b| positions[b].z.total_cmp(&positions[a].z));
+
+    // Min-heap of (weight bits, from, to); non-negative f32 bits sort like
+    // the values
+    let mut heap = BinaryHeap::new();
+    for seed in seeds {
+        if visited[seed] {
+            continue;
+        }
+        visited[seed] = true;
+        if normals[seed].z < 0.0 {
+            normals[seed] = -normals[seed];
+        }
+
+        let mut from = seed;
+        loop {
+            for to in edges(from) {
+                if !visited[to] {
+                    let weight = (1.0 - normals[from].dot(normals[to]).abs()).max(0.0);
+                    heap.push(Reverse((weight.to_bits(), from, to)));
+                }
+            }
+
+            let Some(Reverse((_, parent, next))) =
+                std::iter::from_fn(|| heap.pop()).find(|Reverse((_, _, to))| !visited[*to])
+            else {
+                break;
+            };
+            visited[next] = true;
+            if normals[next].dot(normals[parent]) < 0.0 {
+                normals[next] = -normals[next];
+            }
+            from = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Evenly spread points on a sphere (Fibonacci lattice)
+    fn sphere(count: usize, radius: f32) -> Vec<Vec3> {
+        let golden = std::f32::consts::PI * (3.0 - 5f32.sqrt());
+        (0..count)
+            .map(|i| {
+                let y = 1.0 - 2.0 * (i as f32 + 0.5) / count as f32;
+                let r = (1.0 - y * y).sqrt();
+                let phi = golden * i as f32;
+                Vec3::new(r * phi.cos(), y, r * phi.sin()) * radius
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_propagation_orients_sphere_outward() {
+        let mut positions = sphere(600, 2.0);
+        positions.push(Vec3::NAN);
+        let cloud =
+            PointCloud::new(positions).with_estimated_normals(10, NormalOrientation::Propagate);
+
+        let normals = cloud.normals().unwrap();
+        assert_eq!(normals[600], Vec3::ZERO);
+        for (normal, position) in normals.iter().zip(cloud.positions()).take(600) {
+            assert!((normal.length() - 1.0).abs() < 1e-4);
+            // Outward and close to radial
+            assert!(normal.dot(position.normalize()) > 0.95);
+        }
+    }
+
+    #[test]
+    fn test_viewpoint_orientation() {
+        let cloud = PointCloud::new(sphere(300, 1.0))
+            .with_estimated_normals(8, NormalOrientation::Viewpoint(Vec3::ZERO));
+
+        // Every normal faces the centre
+        for (normal, position) in cloud.normals().unwrap().iter().zip(cloud.positions()) {
+            assert!(normal.dot(-*position) > 0.0);
+        }
+    }
+}
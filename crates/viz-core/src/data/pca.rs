@@ -0,0 +1,327 @@
+use super::{DataError, PointCloud};
+use glam::Vec3;
+use nalgebra::{DMatrix, SymmetricEigen};
+use rayon::prelude::*;
+
+/// Principal component analysis of standardized table columns
+///
+/// Each column is centred on its mean and divided by its standard deviation
+/// so that columns in different units weigh the same, then the first three
+/// principal components become x, y and z. Rows with a non-finite value in
+/// any column are left out of the fit.
+///
+/// # Examples
+/// ```
+/// use viz_core::data::PointCloud;
+///
+/// let a: Vec<f32> = (0..100).map(|i| i as f32).collect();
+/// let b: Vec<f32> = a.iter().map(|v| 2.0 * v + 1.0).collect();
+/// let c: Vec<f32> = (0..100).map(|i| (i % 7) as f32).collect();
+///
+/// let (cloud, pca) = PointCloud::from_pca(vec![
+///     ("a".to_string(), a),
+///     ("b".to_string(), b),
+///     ("c".to_string(), c),
+/// ])
+/// .unwrap();
+///
+/// // a and b are perfectly correlated, so PC1 holds two of the three units
+/// // of variance
+/// let ratio = pca.explained_variance_ratio();
+/// assert!((ratio[0] - 2.0 / 3.0).abs() < 0.05);
+/// assert_eq!(pca.axis_label(0), format!("PC1 ({:.1}%)", ratio[0] * 100.0));
+/// assert!(cloud.metadata("c").is_some());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pca {
+    columns: Vec<String>,
+    mean: Vec<f64>,
+    std_dev: Vec<f64>,
+    /// Loadings of up to three components, one weight per column
+    components: Vec<Vec<f32>>,
+    /// Variance along every component, largest first
+    variance: Vec<f32>,
+}
+
+impl Pca {
+    /// Fit the principal components of named columns
+    ///
+    /// Constant columns are centred but not scaled.
+    ///
+    /// # Errors
+    /// [`DataError::LengthMismatch`] if the columns differ in length, or
+    /// [`DataError::NoCompleteRows`] if every row has a non-finite value
+    pub fn fit(columns: &[(&str, &[f32])]) -> Result<Self, DataError> {
+        let rows = columns.first().map_or(0, |(_, values)| values.len());
+        if let Some((name, values)) = columns.iter().find(|(_, values)| values.len() != rows) {
+            return Err(DataError::LengthMismatch {
+                field: name.to_string(),
+                expected: rows,
+                found: values.len(),
+            });
+        }
+
+        let dims = columns.len();
+        let complete: Vec<usize> = (0..rows)
+            .filter(|&row| columns.iter().all(|(_, values)| values[row].is_finite()))
+            .collect();
+        if complete.is_empty() {
+            return Err(DataError::NoCompleteRows);
+        }
+        let n = complete.len() as f64;
+
+        let mut mean = Vec::with_capacity(dims);
+        let mut std_dev = Vec::with_capacity(dims);
+        for (_, values) in columns {
+            let m = complete.iter().map(|&row| values[row] as f64).sum::<f64>() / n;
+            let variance = complete
+                .iter()
+                .map(|&row| (values[row] as f64 - m).powi(2))
+                .sum::<f64>()
+                / (n - 1.0);
+            mean.push(m);
+            std_dev.push(if variance > 0.0 { variance.sqrt() } else { 1.0 });
+        }
+
+        // Covariance of the standardized columns, summed over rows in parallel
+        let covariance = complete
+            .par_iter()
+            .fold(
+                || (vec![0.0; dims * dims], vec![0.0; dims]),
+                |(mut sum, mut z), &row| {
+                    for (j, (_, values)) in columns.iter().enumerate() {
+                        z[j] = (values[row] as f64 - mean[j]) / std_dev[j];
+                    }
+                    for a in 0..dims {
+                        for b in a..dims {
+                            sum[a * dims + b] += z[a] * z[b];
+                        }
+                    }
+                    (sum, z)
+                },
+            )
+            .map(|(sum, _)| sum)
+            .reduce(
+                || vec![0.0; dims * dims],
+                |mut total, sum| {
+                    total.iter_mut().zip(sum).for_each(|(t, s)| *t += s);
+                    total
+                },
+            );
+        let covariance = DMatrix::from_fn(dims, dims, |a, b| {
+            covariance[a.min(b) * dims + a.max(b)] / (n - 1.0).max(1.0)
+        });
+
+        let eigen = SymmetricEigen::new(covariance);
+        let mut order: Vec<usize> = (0..dims).collect();
+        order.sort_by(|&a, &b| eigen.eigenvalues[b].total_cmp(&eigen.eigenvalues[a]));
+
+        let components = order
+            .iter()
+            .take(3)
+            .map(|&k| {
+                let vector = eigen.eigenvectors.column(k);
+                // Eigenvectors have no sign; make the largest loading positive
+                let largest = vector
+                    .iter()
+                    .copied()
+                    .max_by(|a, b| a.abs().total_cmp(&b.abs()));
+                let sign = largest.map_or(1.0, f64::signum);
+                vector.iter().map(|&w| (w * sign) as f32).collect()
+            })
+            .collect();
+        let variance = order
+            .iter()
+            .map(|&k| eigen.eigenvalues[k].max(0.0) as f32)
+            .collect();
+
+        Ok(Self {
+            columns: columns.iter().map(|(name, _)| name.to_string()).collect(),
+            mean,
+            std_dev,
+            components,
+            variance,
+        })
+    }
+
+    /// Names of the fitted columns
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Loadings of the first (up to three) components, one weight per column
+    pub fn components(&self) -> &[Vec<f32>] {
+        &self.components
+    }
+
+    /// Variance along every component, largest first
+    pub fn explained_variance(&self) -> &[f32] {
+        &self.variance
+    }
+
+    /// Fraction of the total variance along every component, largest first
+    ///
+    /// All zero if every column is constant.
+    pub fn explained_variance_ratio(&self) -> Vec<f32> {
+        let total: f32 = self.variance.iter().sum();
+        if total <= 0.0 {
+            return vec![0.0; self.variance.len()];
+        }
+        self.variance.iter().map(|v| v / total).collect()
+    }
+
+    /// Axis title such as `PC1 (42.0%)` for component `axis` (0-based)
+    pub fn axis_label(&self, axis: usize) -> String {
+        match self.explained_variance_ratio().get(axis) {
+            Some(ratio) => format!("PC{} ({:.1}%)", axis + 1, ratio * 100.0),
+            None => format!("PC{}", axis + 1),
+        }
+    }
+
+    /// Project rows onto the first three components
+    ///
+    /// `columns` must be in the order given to [`Pca::fit`]. Rows with a
+    /// non-finite value project to NaN; with fewer than three columns the
+    /// missing axes are zero.
+    ///
+    /// # Panics
+    /// Panics if the number of columns differs from the fit
+    pub fn project(&self, columns: &[&[f32]]) -> Vec<Vec3> {
+        assert_eq!(
+            columns.len(),
+            self.columns.len(),
+            "PCA projection needs the fitted columns"
+        );
+
+        let rows = columns.first().map_or(0, |values| values.len());
+        (0..rows)
+            .into_par_iter()
+            .map(|row| {
+                let mut position = [0.0f32; 3];
+                for (axis, component) in self.components.iter().enumerate() {
+                    position[axis] = columns
+                        .iter()
+                        .zip(component)
+                        .enumerate()
+                        .map(|(j, (values, &weight))| {
+                            (values[row] as f64 - self.mean[j]) / self.std_dev[j] * weight as f64
+                        })
+                        .sum::<f64>() as f32;
+                }
+                Vec3::from_array(position)
+            })
+            .collect()
+    }
+}
+
+impl PointCloud {
+    /// Build a point cloud from table columns placed by their first three
+    /// principal components
+    ///
+    /// The columns are kept as metadata fields. The returned [`Pca`]
+    /// reports the explained variance for labelling the axes.
+    ///
+    /// # Errors
+    /// Same as [`Pca::fit`]
+    pub fn from_pca(columns: Vec<(String, Vec<f32>)>) -> Result<(Self, Pca), DataError> {
+        let named: Vec<(&str, &[f32])> = columns
+            .iter()
+            .map(|(name, values)| (name.as_str(), values.as_slice()))
+            .collect();
+        let pca = Pca::fit(&named)?;
+
+        let values: Vec<&[f32]> = named.iter().map(|&(_, values)| values).collect();
+        let mut cloud = PointCloud::new(pca.project(&values));
+        for (name, values) in columns {
+            cloud = cloud.with_metadata(name, values);
+        }
+        Ok((cloud, pca))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovers_dominant_direction() {
+        // Points along a line in 4D plus small independent noise
+        let rows = 200;
+        let t: Vec<f32> = (0..rows).map(|i| i as f32 / rows as f32 - 0.5).collect();
+        let noise = |seed: usize| -> Vec<f32> {
+            (0..rows)
+                .map(|i| (((i * 7919 + seed * 104_729) % 1000) as f32 / 1000.0 - 0.5) * 0.01)
+                .collect()
+        };
+        let columns = vec![
+            ("a".to_string(), t.iter().map(|v| 10.0 * v).collect()),
+            (
+                "b".to_string(),
+                t.iter().zip(noise(1)).map(|(v, n)| v + n).collect(),
+            ),
+            (
+                "c".to_string(),
+                t.iter().zip(noise(2)).map(|(v, n)| -v + n).collect(),
+            ),
+            ("d".to_string(), noise(3)),
+        ];
+        let (cloud, pca) = PointCloud::from_pca(columns).unwrap();
+
+        let ratio = pca.explained_variance_ratio();
+        assert_eq!(ratio.len(), 4);
+        assert!(ratio[0] > 0.7);
+        assert!(ratio.windows(2).all(|w| w[0] >= w[1]));
+        assert!((ratio.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+
+        // PC1 follows t, with the largest loading positive
+        let positions = cloud.positions();
+        assert!(positions[rows - 1].x > positions[0].x);
+        assert!(pca.components()[0][0] > 0.0 && pca.components()[0][2] < 0.0);
+
+        // Positions are centred and the inputs are kept
+        let mean = positions.iter().sum::<Vec3>() / rows as f32;
+        assert!(mean.abs().max_element() < 1e-4);
+        assert_eq!(cloud.metadata("a").unwrap()[0], -5.0);
+    }
+
+    #[test]
+    fn test_incomplete_rows_and_errors() {
+        let a = [1.0, 2.0, f32::NAN, 4.0];
+        let b = [2.0, 1.0, 0.0, 3.0];
+        let pca = Pca::fit(&[("a", &a[..]), ("b", &b[..])]).unwrap();
+
+        let positions = pca.project(&[&a[..], &b[..]]);
+        assert!(positions[2].is_nan());
+        assert!(positions[0].is_finite());
+        assert_eq!(positions[0].z, 0.0);
+        assert_eq!(pca.components().len(), 2);
+        assert_eq!(pca.axis_label(2), "PC3");
+
+        // Constant columns have no variance to share out
+        let constant = [5.0; 4];
+        let pca = Pca::fit(&[("a", &constant[..]), ("b", &constant[..])]).unwrap();
+        assert_eq!(pca.explained_variance_ratio(), vec![0.0, 0.0]);
+        assert_eq!(pca.axis_label(0), "PC1 (0.0%)");
+
+        assert_eq!(
+            Pca::fit(&[("a", &a[..]), ("short", &b[..3])]),
+            Err(DataError::LengthMismatch {
+                field: "short".to_string(),
+                expected: 4,
+                found: 3,
+            })
+        );
+
+        // No row is complete, so there is nothing to fit
+        let c = [f32::NAN, 1.0, 2.0, f32::INFINITY];
+        let d = [0.0, f32::NAN, f32::NAN, 1.0];
+        assert_eq!(
+            Pca::fit(&[("c", &c[..]), ("d", &d[..])]),
+            Err(DataError::NoCompleteRows)
+        );
+        assert_eq!(
+            PointCloud::from_pca(vec![("empty".to_string(), Vec::new())]).unwrap_err(),
+            DataError::NoCompleteRows
+        );
+    }
+}
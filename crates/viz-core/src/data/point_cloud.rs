@@ -1,8 +1,12 @@
-use super::Dataset;
-use crate::color::{Colormap, ColorScale, ScaleType};
+use super::{
+    CategoricalField, DataError, Dataset, Expression, FieldInfo, FieldStats, Filter, NanPolicy,
+    Selection,
+};
+use crate::color::{ColorRange, ColorScale, Colormap, Legend, LegendEntry, Palette, ScaleType};
 use crate::math::Bounds3D;
-use glam::{Vec3, Vec4};
+use glam::{DVec3, Vec3, Vec4};
 use std::collections::HashMap;
+use std::ops::Range;
 
 /// 3D point cloud dataset with optional colors and metadata
 ///
@@ -37,33 +41,94 @@ pub struct PointCloud {
     /// Optional per-point sizes
     sizes: Option<Vec<f32>>,
 
+    /// Optional unit surface normals (zero where unknown)
+    normals: Option<Vec<Vec3>>,
+
     /// Additional metadata fields (for color mapping, filtering, etc.)
     metadata: HashMap<String, Vec<f32>>,
 
+    /// Categorical fields (codes plus a label dictionary)
+    categories: HashMap<String, CategoricalField>,
+
+    /// Named per-point vectors, e.g. velocity or force
+    vectors: HashMap<String, Vec<Vec3>>,
+
+    /// Units, long names and other attributes per field
+    field_info: HashMap<String, FieldInfo>,
+
+    /// World-space origin that positions are relative to
+    origin: DVec3,
+
+    /// Policy applied to non-finite positions and metadata
+    nan_policy: NanPolicy,
+
+    /// Color used for non-finite values when coloring and rendering
+    bad_value_color: Vec4,
+
     /// Cached bounding box
     bounds: Option<Bounds3D>,
 
+    /// Cached statistics per metadata field
+    stats: HashMap<String, FieldStats>,
+
+    /// Most points kept by [`PointCloud::extend`] before the oldest are
+    /// overwritten
+    max_points: Option<usize>,
+
+    /// Slot of the oldest point once a limited cloud is full
+    oldest: usize,
+
+    /// Slots written by [`PointCloud::extend`] since the last
+    /// [`PointCloud::take_changed`]
+    changed: Option<Range<usize>>,
+
     /// Dataset name
     name: String,
 }
 
 impl PointCloud {
+    /// Default color for non-finite values (mid grey)
+    pub const DEFAULT_BAD_VALUE_COLOR: Vec4 = Vec4::new(0.5, 0.5, 0.5, 1.0);
+
     /// Create a new point cloud from positions
     pub fn new(positions: Vec<Vec3>) -> Self {
         Self {
             positions,
             colors: None,
             sizes: None,
+            normals: None,
             metadata: HashMap::new(),
+            categories: HashMap::new(),
+            vectors: HashMap::new(),
+            field_info: HashMap::new(),
+            origin: DVec3::ZERO,
+            nan_policy: NanPolicy::Keep,
+            bad_value_color: Self::DEFAULT_BAD_VALUE_COLOR,
             bounds: None,
+            stats: HashMap::new(),
+            max_points: None,
+            oldest: 0,
+            changed: None,
             name: String::from("Point Cloud"),
         }
     }
 
+    /// Create a point cloud, rejecting NaN or infinite positions
+    pub fn try_new(positions: Vec<Vec3>) -> Result<Self, DataError> {
+        if let Some(index) = positions.iter().position(|p| !p.is_finite()) {
+            return Err(DataError::NonFinite {
+                field: "positions".to_string(),
+                index,
+            });
+        }
+        Ok(Self::new(positions))
+    }
+
     /// Set colors for all points
     ///
     /// # Panics
-    /// Panics if colors.len() != positions.len()
+    /// Panics if colors.len() != positions.len(); see
+    /// [`PointCloud::try_with_colors`] for a fallible version
     pub fn with_colors(mut self, colors: Vec<Vec4>) -> Self {
         assert_eq!(
             colors.len(),
@@ -74,10 +139,25 @@ impl PointCloud {
         self
     }
 
+    /// Set colors for all points, rejecting a length mismatch or
+    /// non-finite components
+    pub fn try_with_colors(mut self, colors: Vec<Vec4>) -> Result<Self, DataError> {
+        self.check_len("colors", colors.len())?;
+        if let Some(index) = colors.iter().position(|c| !c.is_finite()) {
+            return Err(DataError::NonFinite {
+                field: "colors".to_string(),
+                index,
+            });
+        }
+        self.colors = Some(colors);
+        Ok(self)
+    }
+
     /// Set sizes for all points
     ///
     /// # Panics
-    /// Panics if sizes.len() != positions.len()
+    /// Panics if sizes.len() != positions.len(); see
+    /// [`PointCloud::try_with_sizes`] for a fallible version
     pub fn with_sizes(mut self, sizes: Vec<f32>) -> Self {
         assert_eq!(
             sizes.len(),
@@ -88,27 +168,332 @@ impl PointCloud {
         self
     }
 
+    /// Set sizes for all points, rejecting a length mismatch or non-finite
+    /// sizes
+    pub fn try_with_sizes(mut self, sizes: Vec<f32>) -> Result<Self, DataError> {
+        self.check_len("sizes", sizes.len())?;
+        if let Some(index) = sizes.iter().position(|s| !s.is_finite()) {
+            return Err(DataError::NonFinite {
+                field: "sizes".to_string(),
+                index,
+            });
+        }
+        self.sizes = Some(sizes);
+        Ok(self)
+    }
+
+    /// Set surface normals for all points
+    ///
+    /// A zero vector marks a point without a normal.
+    ///
+    /// # Panics
+    /// Panics if normals.len() != positions.len(); see
+    /// [`PointCloud::try_with_normals`] for a fallible version
+    pub fn with_normals(mut self, normals: Vec<Vec3>) -> Self {
+        assert_eq!(
+            normals.len(),
+            self.positions.len(),
+            "Normals length must match positions length"
+        );
+        self.normals = Some(normals);
+        self
+    }
+
+    /// Set surface normals for all points, rejecting a length mismatch or
+    /// non-finite components
+    pub fn try_with_normals(mut self, normals: Vec<Vec3>) -> Result<Self, DataError> {
+        self.check_len("normals", normals.len())?;
+        if let Some(index) = normals.iter().position(|n| !n.is_finite()) {
+            return Err(DataError::NonFinite {
+                field: "normals".to_string(),
+                index,
+            });
+        }
+        self.normals = Some(normals);
+        Ok(self)
+    }
+
     /// Add metadata field
     ///
     /// # Panics
-    /// Panics if values.len() != positions.len()
+    /// Panics if values.len() != positions.len(); see
+    /// [`PointCloud::try_with_metadata`] for a fallible version
     pub fn with_metadata(mut self, key: String, values: Vec<f32>) -> Self {
         assert_eq!(
             values.len(),
             self.positions.len(),
             "Metadata length must match positions length"
         );
+        self.stats.remove(&key);
+        self.metadata.insert(key, values);
+        self
+    }
+
+    /// Add metadata field, rejecting a length mismatch
+    ///
+    /// NaN is allowed and marks a missing value.
+    pub fn try_with_metadata(
+        mut self,
+        key: impl Into<String>,
+        values: Vec<f32>,
+    ) -> Result<Self, DataError> {
+        let key = key.into();
+        self.check_len(&key, values.len())?;
+        self.stats.remove(&key);
         self.metadata.insert(key, values);
+        Ok(self)
+    }
+
+    /// Add a categorical field
+    ///
+    /// # Panics
+    /// Panics if the field length != positions.len(); see
+    /// [`PointCloud::try_with_categorical`] for a fallible version
+    pub fn with_categorical(mut self, key: impl Into<String>, field: CategoricalField) -> Self {
+        assert_eq!(
+            field.len(),
+            self.positions.len(),
+            "Categorical field length must match positions length"
+        );
+        self.categories.insert(key.into(), field);
+        self
+    }
+
+    /// Add a categorical field, rejecting a length mismatch
+    pub fn try_with_categorical(
+        mut self,
+        key: impl Into<String>,
+        field: CategoricalField,
+    ) -> Result<Self, DataError> {
+        let key = key.into();
+        self.check_len(&key, field.len())?;
+        self.categories.insert(key, field);
+        Ok(self)
+    }
+
+    /// Add a named vector field, e.g. velocity, with one vector per point
+    ///
+    /// # Panics
+    /// Panics if vectors.len() != positions.len(); see
+    /// [`PointCloud::try_with_vectors`] for a fallible version
+    pub fn with_vectors(mut self, key: impl Into<String>, vectors: Vec<Vec3>) -> Self {
+        assert_eq!(
+            vectors.len(),
+            self.positions.len(),
+            "Vector field length must match positions length"
+        );
+        self.vectors.insert(key.into(), vectors);
         self
     }
 
+    /// Add a named vector field, rejecting a length mismatch
+    ///
+    /// Non-finite vectors are allowed and mark a missing value.
+    pub fn try_with_vectors(
+        mut self,
+        key: impl Into<String>,
+        vectors: Vec<Vec3>,
+    ) -> Result<Self, DataError> {
+        let key = key.into();
+        self.check_len(&key, vectors.len())?;
+        self.vectors.insert(key, vectors);
+        Ok(self)
+    }
+
+    /// Add a metadata field computed from an [`Expression`]
+    ///
+    /// # Errors
+    /// [`DataError::InvalidExpression`] if the expression does not parse and
+    /// [`DataError::MissingField`] if it reads an unknown field
+    ///
+    /// # Examples
+    /// ```
+    /// use glam::Vec3;
+    /// use viz_core::data::PointCloud;
+    ///
+    /// let cloud = PointCloud::new(vec![Vec3::ZERO, Vec3::ONE])
+    ///     .with_metadata("vx".to_string(), vec![3.0, 0.0])
+    ///     .with_metadata("vy".to_string(), vec![4.0, 1.0])
+    ///     .try_with_derived("speed", "sqrt(vx^2 + vy^2)")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cloud.metadata("speed").unwrap(), &[5.0, 1.0]);
+    /// ```
+    pub fn try_with_derived(
+        self,
+        key: impl Into<String>,
+        expression: &str,
+    ) -> Result<Self, DataError> {
+        let values = Expression::parse(expression)?.evaluate(&self)?;
+        self.try_with_metadata(key, values)
+    }
+
+    /// Attach a unit, long name, description or valid range to a field
+    ///
+    /// Attributes are kept by key, for metadata or categorical fields, and
+    /// replace any set before.
+    pub fn with_field_info(mut self, key: impl Into<String>, info: FieldInfo) -> Self {
+        self.set_field_info(key, info);
+        self
+    }
+
+    /// Attach attributes to a field, or remove them if `info` is empty
+    pub fn set_field_info(&mut self, key: impl Into<String>, info: FieldInfo) {
+        let key = key.into();
+        if info.is_empty() {
+            self.field_info.remove(&key);
+        } else {
+            self.field_info.insert(key, info);
+        }
+    }
+
+    /// Get the attributes of a field, if any were set
+    pub fn field_info(&self, key: &str) -> Option<&FieldInfo> {
+        self.field_info.get(key)
+    }
+
+    /// Display label of a field, e.g. `Temperature [K]`, or the key itself
+    /// without attributes
+    pub fn field_label(&self, key: &str) -> String {
+        match self.field_info.get(key) {
+            Some(info) => info.label(key),
+            None => key.to_string(),
+        }
+    }
+
     /// Set the dataset name
     pub fn with_name(mut self, name: impl Into<String>) -> Self {
         self.name = name.into();
         self
     }
 
-    /// Get point positions
+    /// Set the world-space origin of the positions
+    ///
+    /// Large coordinates (e.g. UTM or ECEF) lose precision in f32, so loaders
+    /// store positions relative to a nearby origin kept here in f64. The world
+    /// position of a point is `origin + position`.
+    pub fn with_origin(mut self, origin: DVec3) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Get the world-space origin of the positions
+    pub fn origin(&self) -> DVec3 {
+        self.origin
+    }
+
+    /// Create a point cloud from double-precision world positions
+    ///
+    /// The origin is placed at the centre of the finite positions and the
+    /// points are stored as f32 offsets from it, which keeps sub-millimetre
+    /// precision for data spanning kilometres at any distance from zero.
+    ///
+    /// # Examples
+    /// ```
+    /// use glam::{DVec3, Vec3};
+    /// use viz_core::data::PointCloud;
+    ///
+    /// let cloud = PointCloud::from_world_positions(&[
+    ///     DVec3::new(500_000.0, 4_000_000.0, 10.0),
+    ///     DVec3::new(500_000.5, 4_000_000.25, 12.0),
+    /// ]);
+    ///
+    /// assert_eq!(cloud.origin(), DVec3::new(500_000.25, 4_000_000.125, 11.0));
+    /// assert_eq!(cloud.positions()[1], Vec3::new(0.25, 0.125, 1.0));
+    /// assert_eq!(cloud.world_position(1), DVec3::new(500_000.5, 4_000_000.25, 12.0));
+    /// ```
+    pub fn from_world_positions(positions: &[DVec3]) -> Self {
+        let mut finite = positions.iter().filter(|p| p.is_finite());
+        let origin = match finite.next() {
+            Some(&first) => {
+                let (min, max) =
+                    finite.fold((first, first), |(min, max), &p| (min.min(p), max.max(p)));
+                (min + max) * 0.5
+            }
+            None => DVec3::ZERO,
+        };
+
+        let positions = positions.iter().map(|&p| (p - origin).as_vec3()).collect();
+        Self::new(positions).with_origin(origin)
+    }
+
+    /// Apply a NaN policy to the current positions and metadata
+    ///
+    /// [`NanPolicy::Drop`] removes affected points from every field and
+    /// [`NanPolicy::Substitute`] overwrites the bad values; fields added
+    /// afterwards are not rewritten. [`NanPolicy::Keep`] leaves the data as
+    /// is. Bounds and colormap ranges ignore non-finite values in every case.
+    ///
+    /// # Examples
+    /// ```
+    /// use glam::Vec3;
+    /// use viz_core::data::{Dataset, NanPolicy, PointCloud};
+    ///
+    /// let cloud = PointCloud::new(vec![Vec3::ZERO, Vec3::NAN, Vec3::ONE])
+    ///     .with_metadata("t".to_string(), vec![1.0, 2.0, f32::NAN])
+    ///     .with_nan_policy(NanPolicy::Drop);
+    ///
+    /// assert_eq!(cloud.len(), 1);
+    /// ```
+    pub fn with_nan_policy(mut self, policy: NanPolicy) -> Self {
+        match policy {
+            NanPolicy::Keep => {}
+            NanPolicy::Drop => {
+                let keep: Vec<bool> = (0..self.positions.len())
+                    .map(|i| {
+                        self.positions[i].is_finite()
+                            && self.metadata.values().all(|values| values[i].is_finite())
+                    })
+                    .collect();
+                self.retain(&keep);
+            }
+            NanPolicy::Substitute(value) => {
+                for position in &mut self.positions {
+                    *position = Vec3::from_array(position.to_array().map(|v| {
+                        if v.is_finite() {
+                            v
+                        } else {
+                            value
+                        }
+                    }));
+                }
+                for values in self.metadata.values_mut() {
+                    values
+                        .iter_mut()
+                        .filter(|v| !v.is_finite())
+                        .for_each(|v| *v = value);
+                }
+                self.bounds = None;
+                self.stats.clear();
+            }
+        }
+
+        self.nan_policy = policy;
+        self
+    }
+
+    /// Get the NaN policy last applied with [`PointCloud::with_nan_policy`]
+    pub fn nan_policy(&self) -> NanPolicy {
+        self.nan_policy
+    }
+
+    /// Set the color used for non-finite values
+    pub fn with_bad_value_color(mut self, color: Vec4) -> Self {
+        self.bad_value_color = color;
+        self
+    }
+
+    /// Get the color used for non-finite values
+    pub fn bad_value_color(&self) -> Vec4 {
+        self.bad_value_color
+    }
+
+    /// Get the world-space position of a point in double precision
+    pub fn world_position(&self, index: usize) -> DVec3 {
+        self.origin + self.positions[index].as_dvec3()
+    }
+
+    /// Get point positions (relative to [`PointCloud::origin`])
     pub fn positions(&self) -> &[Vec3] {
         &self.positions
     }
@@ -123,6 +508,11 @@ impl PointCloud {
         self.sizes.as_deref()
     }
 
+    /// Get point normals (or None if not set)
+    pub fn normals(&self) -> Option<&[Vec3]> {
+        self.normals.as_deref()
+    }
+
     /// Get metadata field by name
     pub fn metadata(&self, key: &str) -> Option<&[f32]> {
         self.metadata.get(key).map(|v| v.as_slice())
@@ -133,6 +523,37 @@ impl PointCloud {
         self.metadata.keys().collect()
     }
 
+    /// Get categorical field by name
+    pub fn categorical(&self, key: &str) -> Option<&CategoricalField> {
+        self.categories.get(key)
+    }
+
+    /// Get all categorical field keys
+    pub fn categorical_keys(&self) -> Vec<&String> {
+        self.categories.keys().collect()
+    }
+
+    /// Get a vector field
+    pub fn vectors(&self, key: &str) -> Option<&[Vec3]> {
+        self.vectors.get(key).map(|v| v.as_slice())
+    }
+
+    /// Get all vector field keys
+    pub fn vector_keys(&self) -> Vec<&String> {
+        self.vectors.keys().collect()
+    }
+
+    /// Length of every vector in a vector field (NaN where non-finite)
+    pub fn vector_magnitudes(&self, key: &str) -> Option<Vec<f32>> {
+        let vectors = self.vectors.get(key)?;
+        Some(
+            vectors
+                .iter()
+                .map(|v| if v.is_finite() { v.length() } else { f32::NAN })
+                .collect(),
+        )
+    }
+
     /// Generate default colors (white) for all points
     pub fn generate_default_colors(&mut self) {
         if self.colors.is_none() {
@@ -164,7 +585,11 @@ impl PointCloud {
                 let g = (1.0 - (t - 0.5).abs() * 2.0).max(0.0);
                 let b = t.max(0.0);
 
-                Vec4::new(r, g, b, 1.0)
+                if pos.is_finite() {
+                    Vec4::new(r, g, b, 1.0)
+                } else {
+                    self.bad_value_color
+                }
             })
             .collect();
 
@@ -174,14 +599,16 @@ impl PointCloud {
     /// Apply colormap to points based on metadata field
     ///
     /// Maps a metadata field to colors using the specified colormap and scale.
+    /// The range is taken from the finite values only; NaN and infinite
+    /// values get the [bad-value color](PointCloud::bad_value_color).
     ///
     /// # Arguments
     /// * `field` - Name of the metadata field to use for coloring
     /// * `colormap` - Colormap to apply
     /// * `scale_type` - Scaling function (linear or log)
     ///
-    /// # Returns
-    /// Ok(()) if successful, Err if metadata field not found
+    /// # Errors
+    /// [`DataError::MissingField`] if the metadata field does not exist
     ///
     /// # Examples
     /// ```
@@ -200,26 +627,590 @@ impl PointCloud {
         field: &str,
         colormap: &dyn Colormap,
         scale_type: ScaleType,
-    ) -> Result<(), String> {
+    ) -> Result<(), DataError> {
+        self.apply_colormap_range(field, colormap, scale_type, ColorRange::MinMax)
+            .map(|_| ())
+    }
+
+    /// Apply a colormap over a chosen data range
+    ///
+    /// Like [`PointCloud::apply_colormap`], but values are scaled to `range`
+    /// and clamped to its ends. Percentile ranges use the cached
+    /// [field statistics](PointCloud::field_stats). Returns the resolved
+    /// `(min, max)`, e.g. for labelling a colorbar.
+    ///
+    /// # Errors
+    /// [`DataError::MissingField`] if the metadata field does not exist, and
+    /// [`DataError::InvalidPercentile`] for percentiles outside [0, 100] or
+    /// with `low > high`
+    ///
+    /// # Examples
+    /// ```
+    /// use glam::Vec3;
+    /// use viz_core::color::{ColorRange, ScaleType, Viridis};
+    /// use viz_core::data::PointCloud;
+    ///
+    /// let mut values: Vec<f32> = (0..100).map(|i| i as f32).collect();
+    /// values[99] = 1e6;
+    /// let mut cloud = PointCloud::new(vec![Vec3::ZERO; 100])
+    ///     .with_metadata("t".to_string(), values);
+    ///
+    /// // The outlier does not stretch the range
+    /// let (min, max) = cloud
+    ///     .apply_colormap_range("t", &Viridis, ScaleType::Linear, ColorRange::ROBUST)
+    ///     .unwrap();
+    /// assert!(min > 1.0 && max < 99.0);
+    /// ```
+    pub fn apply_colormap_range(
+        &mut self,
+        field: &str,
+        colormap: &dyn Colormap,
+        scale_type: ScaleType,
+        range: ColorRange,
+    ) -> Result<(f32, f32), DataError> {
+        // Percentiles come from the cached statistics, computed if needed
+        if let ColorRange::Percentile { .. } = range {
+            self.field_stats(field)?;
+        }
         let values = self
             .metadata
             .get(field)
-            .ok_or_else(|| format!("Metadata field '{}' not found", field))?;
-
-        // Find min/max for scaling
-        let min = values.iter().copied().fold(f32::INFINITY, f32::min);
-        let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+            .ok_or_else(|| DataError::MissingField(field.to_string()))?;
+        let (min, max) = range.resolve(values, self.stats.get(field))?;
 
         // Map values to colors
         let colors: Vec<Vec4> = values
             .iter()
             .map(|&value| {
+                if !value.is_finite() {
+                    return self.bad_value_color;
+                }
                 let t = ColorScale::map(value, min, max, scale_type);
                 colormap.sample(t)
             })
             .collect();
 
         self.colors = Some(colors);
+        Ok((min, max))
+    }
+
+    /// Color points by a categorical field using a qualitative palette
+    ///
+    /// Category `i` gets `palette.color(i)`; points without a category get
+    /// the [bad-value color](PointCloud::bad_value_color). Returns the legend
+    /// mapping each label to its color.
+    ///
+    /// # Errors
+    /// [`DataError::MissingField`] if the categorical field does not exist
+    ///
+    /// # Examples
+    /// ```
+    /// use glam::Vec3;
+    /// use viz_core::color::Tab10;
+    /// use viz_core::data::{CategoricalField, PointCloud};
+    ///
+    /// let mut cloud = PointCloud::new(vec![Vec3::ZERO, Vec3::ONE])
+    ///     .with_categorical("species", CategoricalField::from_labels([Some("oak"), Some("pine")]));
+    ///
+    /// let legend = cloud.apply_palette("species", &Tab10).unwrap();
+    /// assert_eq!(legend.entries.len(), 2);
+    /// assert_eq!(legend.color_of("pine"), Some(cloud.colors().unwrap()[1]));
+    /// ```
+    pub fn apply_palette(
+        &mut self,
+        field: &str,
+        palette: &dyn Palette,
+    ) -> Result<Legend, DataError> {
+        let categories = self
+            .categories
+            .get(field)
+            .ok_or_else(|| DataError::MissingField(field.to_string()))?;
+
+        let colors: Vec<Vec4> = categories
+            .codes()
+            .iter()
+            .map(|&code| match code {
+                CategoricalField::MISSING => self.bad_value_color,
+                code => palette.color(code as usize),
+            })
+            .collect();
+
+        let entries = categories
+            .labels()
+            .iter()
+            .enumerate()
+            .map(|(i, label)| LegendEntry {
+                label: label.clone(),
+                color: palette.color(i),
+            })
+            .collect();
+
+        self.colors = Some(colors);
+        Ok(Legend {
+            title: field.to_string(),
+            entries,
+        })
+    }
+
+    /// Statistics of a metadata field, computed on first use and cached
+    ///
+    /// The cache entry is dropped when the field is replaced or the points
+    /// change.
+    ///
+    /// # Errors
+    /// [`DataError::MissingField`] if the metadata field does not exist
+    pub fn field_stats(&mut self, field: &str) -> Result<&FieldStats, DataError> {
+        let values = self
+            .metadata
+            .get(field)
+            .ok_or_else(|| DataError::MissingField(field.to_string()))?;
+        Ok(self
+            .stats
+            .entry(field.to_string())
+            .or_insert_with(|| FieldStats::from_values(values)))
+    }
+
+    /// Select the points that match a filter
+    ///
+    /// # Errors
+    /// [`DataError::MissingField`] if the filter references an unknown field
+    pub fn select(&self, filter: &Filter) -> Result<Selection, DataError> {
+        Ok(Selection::from_mask(&filter.mask(self)?))
+    }
+
+    /// Select the points for which `predicate(index)` returns true
+    pub fn select_by(&self, mut predicate: impl FnMut(usize) -> bool) -> Selection {
+        Selection::from_indices((0..self.positions.len()).filter(|&i| predicate(i)))
+    }
+
+    /// Copy the selected points into a new cloud
+    ///
+    /// Colors, sizes, normals, metadata and categorical fields stay aligned
+    /// with the positions; the name, origin and color settings are kept and
+    /// the bounds are recomputed for the subset.
+    ///
+    /// # Panics
+    /// Panics if the selection contains an index out of range
+    ///
+    /// # Examples
+    /// ```
+    /// use glam::Vec3;
+    /// use viz_core::data::{Dataset, Filter, PointCloud};
+    ///
+    /// let cloud = PointCloud::new(vec![Vec3::ZERO, Vec3::ONE, Vec3::splat(2.0)])
+    ///     .with_metadata("t".to_string(), vec![1.0, 2.0, 3.0]);
+    ///
+    /// let hot = cloud.subset(&cloud.select(&Filter::ge("t", 2.0)).unwrap());
+    /// assert_eq!(hot.metadata("t").unwrap(), &[2.0, 3.0]);
+    /// assert_eq!(hot.bounds().min, Vec3::ONE);
+    /// ```
+    pub fn subset(&self, selection: &Selection) -> PointCloud {
+        let indices = selection.indices();
+        PointCloud {
+            positions: gather(&self.positions, indices),
+            colors: self.colors.as_ref().map(|colors| gather(colors, indices)),
+            sizes: self.sizes.as_ref().map(|sizes| gather(sizes, indices)),
+            normals: self
+                .normals
+                .as_ref()
+                .map(|normals| gather(normals, indices)),
+            metadata: self
+                .metadata
+                .iter()
+                .map(|(key, values)| (key.clone(), gather(values, indices)))
+                .collect(),
+            categories: self
+                .categories
+                .iter()
+                .map(|(key, field)| (key.clone(), field.gather(indices)))
+                .collect(),
+            vectors: self
+                .vectors
+                .iter()
+                .map(|(key, vectors)| (key.clone(), gather(vectors, indices)))
+                .collect(),
+            field_info: self.field_info.clone(),
+            origin: self.origin,
+            nan_policy: self.nan_policy,
+            bad_value_color: self.bad_value_color,
+            bounds: None,
+            stats: HashMap::new(),
+            max_points: None,
+            oldest: 0,
+            changed: None,
+            name: self.name.clone(),
+        }
+    }
+
+    /// Copy the points that match a filter into a new cloud
+    ///
+    /// Shorthand for [`PointCloud::select`] followed by [`PointCloud::subset`].
+    pub fn filter(&self, filter: &Filter) -> Result<PointCloud, DataError> {
+        Ok(self.subset(&self.select(filter)?))
+    }
+
+    /// Bounding box of the selected points, ignoring non-finite positions
+    pub fn selection_bounds(&self, selection: &Selection) -> Bounds3D {
+        let positions: Vec<Vec3> = selection
+            .indices()
+            .iter()
+            .map(|&i| self.positions[i])
+            .collect();
+        Bounds3D::from_points(&positions)
+    }
+
+    /// Blend two clouds with the same points, from `self` at `t = 0` to
+    /// `other` at `t = 1`
+    ///
+    /// Positions, colors, sizes, metadata and vector fields present in both
+    /// clouds are interpolated linearly and normals are renormalized. Everything
+    /// else, including categorical fields, comes from the nearer cloud.
+    /// Positions stay relative to `self`'s origin.
+    ///
+    /// # Errors
+    /// [`DataError::LengthMismatch`] if the clouds differ in point count
+    pub fn lerp(&self, other: &PointCloud, t: f32) -> Result<PointCloud, DataError> {
+        if other.positions.len() != self.positions.len() {
+            return Err(DataError::LengthMismatch {
+                field: "positions".to_string(),
+                expected: self.positions.len(),
+                found: other.positions.len(),
+            });
+        }
+
+        let near = if t < 0.5 { self } else { other };
+        let shift = (other.origin - self.origin).as_vec3();
+        let lerp_f32 = |a: f32, b: f32| a + (b - a) * t;
+
+        Ok(PointCloud {
+            positions: blend(&self.positions, &other.positions, |a, b| {
+                a.lerp(b + shift, t)
+            }),
+            colors: match (&self.colors, &other.colors) {
+                (Some(a), Some(b)) => Some(blend(a, b, |a, b| a.lerp(b, t))),
+                _ => near.colors.clone(),
+            },
+            sizes: match (&self.sizes, &other.sizes) {
+                (Some(a), Some(b)) => Some(blend(a, b, lerp_f32)),
+                _ => near.sizes.clone(),
+            },
+            normals: match (&self.normals, &other.normals) {
+                (Some(a), Some(b)) => Some(blend(a, b, |a, b| a.lerp(b, t).normalize_or_zero())),
+                _ => near.normals.clone(),
+            },
+            metadata: near
+                .metadata
+                .iter()
+                .map(|(key, values)| {
+                    let values = match (self.metadata.get(key), other.metadata.get(key)) {
+                        (Some(a), Some(b)) => blend(a, b, lerp_f32),
+                        _ => values.clone(),
+                    };
+                    (key.clone(), values)
+                })
+                .collect(),
+            categories: near.categories.clone(),
+            vectors: near
+                .vectors
+                .iter()
+                .map(|(key, vectors)| {
+                    let vectors = match (self.vectors.get(key), other.vectors.get(key)) {
+                        (Some(a), Some(b)) => blend(a, b, |a, b| a.lerp(b, t)),
+                        _ => vectors.clone(),
+                    };
+                    (key.clone(), vectors)
+                })
+                .collect(),
+            field_info: near.field_info.clone(),
+            origin: self.origin,
+            nan_policy: near.nan_policy,
+            bad_value_color: near.bad_value_color,
+            bounds: None,
+            stats: HashMap::new(),
+            max_points: None,
+            oldest: 0,
+            changed: None,
+            name: near.name.clone(),
+        })
+    }
+
+    /// Limit the cloud to its `max_points` newest points
+    ///
+    /// Points added with [`PointCloud::extend`] or [`PointCloud::push`]
+    /// beyond the limit overwrite the oldest ones in place, like a ring
+    /// buffer, so a full cloud is no longer in arrival order; see
+    /// [`PointCloud::oldest_index`]. If the cloud already holds more points,
+    /// the oldest are dropped.
+    ///
+    /// # Panics
+    /// Panics if `max_points` is zero
+    pub fn with_max_points(mut self, max_points: usize) -> Self {
+        assert!(max_points > 0, "A point cloud must keep at least one point");
+
+        self.rotate_to_arrival_order();
+        let excess = self.positions.len().saturating_sub(max_points);
+        if excess > 0 {
+            let keep: Vec<bool> = (0..self.positions.len()).map(|i| i >= excess).collect();
+            self.retain(&keep);
+        }
+        self.max_points = Some(max_points);
+        self
+    }
+
+    /// Get the point limit set with [`PointCloud::with_max_points`]
+    pub fn max_points(&self) -> Option<usize> {
+        self.max_points
+    }
+
+    /// Index of the oldest point
+    ///
+    /// Zero until a [limited](PointCloud::with_max_points) cloud is full;
+    /// after that, points run from here to the end and then from index 0 in
+    /// arrival order.
+    pub fn oldest_index(&self) -> usize {
+        self.oldest
+    }
+
+    /// Append the points of another cloud
+    ///
+    /// `batch` must carry the same fields: colors, sizes and normals on
+    /// both or neither, and the same metadata and categorical keys.
+    /// Categories are matched by label and the batch positions are shifted
+    /// to this cloud's origin. With a [point limit](PointCloud::with_max_points)
+    /// the newest points overwrite the oldest.
+    ///
+    /// Cached bounds grow with the new points and are only recomputed when
+    /// an overwritten point lay on the bounding box. The written indices are
+    /// collected for [`PointCloud::take_changed`].
+    ///
+    /// # Errors
+    /// [`DataError::FieldMismatch`] if a field is present in only one of
+    /// the clouds; nothing is appended
+    ///
+    /// # Examples
+    /// ```
+    /// use glam::Vec3;
+    /// use viz_core::data::{Dataset, PointCloud};
+    ///
+    /// let mut live = PointCloud::new(Vec::new()).with_max_points(3);
+    /// for i in 0..5 {
+    ///     live.push(Vec3::splat(i as f32)).unwrap();
+    /// }
+    ///
+    /// // The last three points, with the oldest (2) in slot 2
+    /// assert_eq!(live.positions(), &[Vec3::splat(3.0), Vec3::splat(4.0), Vec3::splat(2.0)]);
+    /// assert_eq!(live.oldest_index(), 2);
+    /// assert_eq!(live.bounds().min, Vec3::splat(2.0));
+    /// ```
+    pub fn extend(&mut self, batch: &PointCloud) -> Result<(), DataError> {
+        self.check_same_fields(batch)?;
+        if batch.positions.is_empty() {
+            return Ok(());
+        }
+
+        let capacity = self.max_points.unwrap_or(usize::MAX);
+        let len = self.positions.len();
+        // Batch points that would be overwritten by later ones right away
+        let skip = batch.positions.len().saturating_sub(capacity);
+        let incoming = batch.positions.len() - skip;
+        let appended = (capacity - len).min(incoming);
+        let overwritten = incoming - appended;
+
+        // Slots overwritten, oldest first
+        let start = self.oldest;
+        let overwritten_slots = (0..overwritten).map(|i| (start + i) % capacity);
+
+        if let Some(bounds) = self.bounds {
+            let on_boundary =
+                |p: Vec3| p.is_finite() && (p.cmpeq(bounds.min).any() || p.cmpeq(bounds.max).any());
+            if len == 0
+                || overwritten_slots
+                    .clone()
+                    .any(|i| on_boundary(self.positions[i]))
+            {
+                self.bounds = None;
+            }
+        }
+
+        let shift = (batch.origin - self.origin).as_vec3();
+        let positions: Vec<Vec3> = batch.positions[skip..].iter().map(|&p| p + shift).collect();
+        write_ring(&mut self.positions, &positions, appended, start);
+        if let (Some(colors), Some(new)) = (&mut self.colors, &batch.colors) {
+            write_ring(colors, &new[skip..], appended, start);
+        }
+        if let (Some(sizes), Some(new)) = (&mut self.sizes, &batch.sizes) {
+            write_ring(sizes, &new[skip..], appended, start);
+        }
+        if let (Some(normals), Some(new)) = (&mut self.normals, &batch.normals) {
+            write_ring(normals, &new[skip..], appended, start);
+        }
+        for (key, values) in &mut self.metadata {
+            write_ring(values, &batch.metadata[key][skip..], appended, start);
+        }
+        for (key, field) in &mut self.categories {
+            let codes = field.translate_codes(&batch.categories[key]);
+            write_ring(field.codes_mut(), &codes[skip..], appended, start);
+        }
+        for (key, vectors) in &mut self.vectors {
+            write_ring(vectors, &batch.vectors[key][skip..], appended, start);
+        }
+
+        match &mut self.bounds {
+            Some(bounds) => {
+                for &p in positions.iter().filter(|p| p.is_finite()) {
+                    bounds.expand_to_include(p);
+                }
+            }
+            None => {
+                self.compute_bounds();
+            }
+        }
+        self.stats.clear();
+
+        self.mark_changed(len..len + appended);
+        if overwritten > 0 {
+            self.oldest = (start + overwritten) % capacity;
+            if start + overwritten <= capacity {
+                self.mark_changed(start..start + overwritten);
+            } else {
+                self.mark_changed(0..capacity);
+            }
+        }
+        Ok(())
+    }
+
+    /// Append one point to a cloud that has positions only
+    ///
+    /// # Errors
+    /// [`DataError::FieldMismatch`] if the cloud has any other field
+    pub fn push(&mut self, position: Vec3) -> Result<(), DataError> {
+        self.extend(&PointCloud::new(vec![position]).with_origin(self.origin))
+    }
+
+    /// Indices written by [`PointCloud::extend`] since the last call, as
+    /// one covering range
+    ///
+    /// Pass the range to `Scatter3D::update` to upload only those points.
+    pub fn take_changed(&mut self) -> Option<Range<usize>> {
+        self.changed.take()
+    }
+
+    /// Check that another cloud carries the same per-point fields
+    fn check_same_fields(&self, other: &PointCloud) -> Result<(), DataError> {
+        let mismatch = |field: &str| {
+            Err(DataError::FieldMismatch {
+                field: field.to_string(),
+            })
+        };
+        if self.colors.is_some() != other.colors.is_some() {
+            return mismatch("colors");
+        }
+        if self.sizes.is_some() != other.sizes.is_some() {
+            return mismatch("sizes");
+        }
+        if self.normals.is_some() != other.normals.is_some() {
+            return mismatch("normals");
+        }
+        let keys = |cloud: &PointCloud| -> Vec<String> {
+            let mut keys: Vec<String> = cloud.metadata.keys().cloned().collect();
+            keys.extend(cloud.categories.keys().cloned());
+            keys.extend(cloud.vectors.keys().cloned());
+            keys
+        };
+        let (ours, theirs) = (keys(self), keys(other));
+        if let Some(key) = ours
+            .iter()
+            .find(|key| !theirs.contains(key))
+            .or_else(|| theirs.iter().find(|key| !ours.contains(key)))
+        {
+            return mismatch(key);
+        }
+        Ok(())
+    }
+
+    /// Reorder a full limited cloud so the oldest point comes first
+    fn rotate_to_arrival_order(&mut self) {
+        let oldest = std::mem::take(&mut self.oldest);
+        if oldest == 0 {
+            return;
+        }
+        self.positions.rotate_left(oldest);
+        if let Some(colors) = &mut self.colors {
+            colors.rotate_left(oldest);
+        }
+        if let Some(sizes) = &mut self.sizes {
+            sizes.rotate_left(oldest);
+        }
+        if let Some(normals) = &mut self.normals {
+            normals.rotate_left(oldest);
+        }
+        for values in self.metadata.values_mut() {
+            values.rotate_left(oldest);
+        }
+        for field in self.categories.values_mut() {
+            field.codes_mut().rotate_left(oldest);
+        }
+        for vectors in self.vectors.values_mut() {
+            vectors.rotate_left(oldest);
+        }
+        self.mark_changed(0..self.positions.len());
+    }
+
+    /// Add indices to the changed range
+    fn mark_changed(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        self.changed = Some(match self.changed.take() {
+            Some(changed) => changed.start.min(range.start)..changed.end.max(range.end),
+            None => range,
+        });
+    }
+
+    /// Keep only the points whose entry in `keep` is true
+    pub(crate) fn retain(&mut self, keep: &[bool]) {
+        // Dropping points from a wrapped ring would leave a gap, so go back
+        // to arrival order first
+        let mut keep = keep.to_vec();
+        keep.rotate_left(self.oldest);
+        self.rotate_to_arrival_order();
+        let keep = keep.as_slice();
+
+        filter(&mut self.positions, keep);
+        if let Some(colors) = &mut self.colors {
+            filter(colors, keep);
+        }
+        if let Some(sizes) = &mut self.sizes {
+            filter(sizes, keep);
+        }
+        if let Some(normals) = &mut self.normals {
+            filter(normals, keep);
+        }
+        for values in self.metadata.values_mut() {
+            filter(values, keep);
+        }
+        for field in self.categories.values_mut() {
+            filter(field.codes_mut(), keep);
+        }
+        for vectors in self.vectors.values_mut() {
+            filter(vectors, keep);
+        }
+        self.bounds = None;
+        self.stats.clear();
+        if self.changed.is_some() {
+            self.changed = Some(0..self.positions.len());
+        }
+    }
+
+    /// Check that a per-point field has one value per point
+    fn check_len(&self, field: &str, found: usize) -> Result<(), DataError> {
+        if found != self.positions.len() {
+            return Err(DataError::LengthMismatch {
+                field: field.to_string(),
+                expected: self.positions.len(),
+                found,
+            });
+        }
         Ok(())
     }
 
@@ -250,9 +1241,40 @@ impl Dataset for PointCloud {
     }
 }
 
+/// Copy the elements at `indices`
+fn gather<T: Copy>(values: &[T], indices: &[usize]) -> Vec<T> {
+    indices.iter().map(|&i| values[i]).collect()
+}
+
+/// Combine the elements of two equally long slices pairwise
+fn blend<T: Copy>(a: &[T], b: &[T], f: impl Fn(T, T) -> T) -> Vec<T> {
+    a.iter().zip(b).map(|(&a, &b)| f(a, b)).collect()
+}
+
+/// Append `new[..appended]` and write the rest over the slots from `start`
+/// on, wrapping around the end
+fn write_ring<T: Copy>(values: &mut Vec<T>, new: &[T], appended: usize, start: usize) {
+    let (append, overwrite) = new.split_at(appended);
+    values.extend_from_slice(append);
+    let capacity = values.len();
+    for (i, &value) in overwrite.iter().enumerate() {
+        values[(start + i) % capacity] = value;
+    }
+}
+
+/// Keep the elements whose entry in `keep` is true
+fn filter<T: Copy>(values: &mut Vec<T>, keep: &[bool]) {
+    let mut index = 0;
+    values.retain(|_| {
+        index += 1;
+        keep[index - 1]
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::color::Viridis;
 
     #[test]
     fn test_new() {
@@ -282,6 +1304,198 @@ mod tests {
         let _cloud = PointCloud::new(positions).with_colors(colors);
     }
 
+    #[test]
+    fn test_try_builders() {
+        let cloud = PointCloud::try_new(vec![Vec3::ZERO, Vec3::ONE])
+            .unwrap()
+            .try_with_sizes(vec![1.0, 2.0])
+            .unwrap()
+            .try_with_metadata("mass", vec![0.5, f32::NAN])
+            .unwrap();
+        assert_eq!(cloud.sizes().unwrap(), &[1.0, 2.0]);
+
+        let err = cloud.clone().try_with_colors(vec![Vec4::ONE]).unwrap_err();
+        assert_eq!(
+            err,
+            DataError::LengthMismatch {
+                field: "colors".to_string(),
+                expected: 2,
+                found: 1,
+            }
+        );
+
+        let err = cloud
+            .try_with_metadata("temperature", vec![1.0, 2.0, 3.0])
+            .unwrap_err();
+        assert!(
+            matches!(err, DataError::LengthMismatch { ref field, .. } if field == "temperature")
+        );
+
+        let err = PointCloud::try_new(vec![Vec3::ZERO, Vec3::new(0.0, f32::NAN, 0.0)]).unwrap_err();
+        assert_eq!(
+            err,
+            DataError::NonFinite {
+                field: "positions".to_string(),
+                index: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_apply_colormap_missing_field() {
+        let mut cloud = PointCloud::new(vec![Vec3::ZERO]);
+        let err = cloud
+            .apply_colormap("missing", &crate::color::Viridis, ScaleType::Linear)
+            .unwrap_err();
+
+        assert_eq!(err, DataError::MissingField("missing".to_string()));
+        assert!(cloud.colors().is_none());
+    }
+
+    /// Four points: one NaN position, one NaN metadata value
+    fn partial_nan_cloud() -> PointCloud {
+        PointCloud::new(vec![
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(f32::NAN, 1.0, 0.0),
+            Vec3::new(2.0, 2.0, 2.0),
+            Vec3::new(1.0, -1.0, 1.0),
+        ])
+        .with_sizes(vec![1.0, 2.0, 3.0, 4.0])
+        .with_metadata("t".to_string(), vec![10.0, 20.0, f32::NAN, 30.0])
+    }
+
+    #[test]
+    fn test_partial_nan_bounds_and_colormap() {
+        let mut cloud = partial_nan_cloud().with_bad_value_color(Vec4::new(1.0, 0.0, 1.0, 1.0));
+
+        let bounds = cloud.bounds();
+        assert_eq!(bounds.min, Vec3::new(0.0, -1.0, 0.0));
+        assert_eq!(bounds.max, Vec3::new(2.0, 2.0, 2.0));
+
+        cloud
+            .apply_colormap("t", &crate::color::Viridis, ScaleType::Linear)
+            .unwrap();
+        let colors = cloud.colors().unwrap();
+
+        // Range is [10, 30] from the finite values
+        assert_eq!(colors[0], crate::color::Viridis.sample(0.0));
+        assert_eq!(colors[3], crate::color::Viridis.sample(1.0));
+        assert_eq!(colors[2], Vec4::new(1.0, 0.0, 1.0, 1.0));
+        assert!(colors.iter().all(|c| c.is_finite()));
+    }
+
+    #[test]
+    fn test_nan_policy_drop() {
+        let cloud = partial_nan_cloud().with_nan_policy(NanPolicy::Drop);
+
+        assert_eq!(cloud.nan_policy(), NanPolicy::Drop);
+        assert_eq!(cloud.positions(), &[Vec3::ZERO, Vec3::new(1.0, -1.0, 1.0)]);
+        assert_eq!(cloud.sizes().unwrap(), &[1.0, 4.0]);
+        assert_eq!(cloud.metadata("t").unwrap(), &[10.0, 30.0]);
+    }
+
+    #[test]
+    fn test_nan_policy_substitute_and_keep() {
+        let cloud = partial_nan_cloud().with_nan_policy(NanPolicy::Substitute(0.0));
+        assert_eq!(cloud.len(), 4);
+        assert_eq!(cloud.positions()[1], Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(cloud.metadata("t").unwrap(), &[10.0, 20.0, 0.0, 30.0]);
+
+        let cloud = partial_nan_cloud().with_nan_policy(NanPolicy::Keep);
+        assert_eq!(cloud.len(), 4);
+        assert!(cloud.positions()[1].x.is_nan());
+    }
+
+    #[test]
+    fn test_height_colors_with_nan() {
+        let mut cloud = partial_nan_cloud();
+        cloud.generate_height_colors();
+
+        let colors = cloud.colors().unwrap();
+        assert_eq!(colors[1], PointCloud::DEFAULT_BAD_VALUE_COLOR);
+        assert!(colors.iter().all(|c| c.is_finite()));
+    }
+
+    #[test]
+    fn test_apply_palette_with_missing_category() {
+        use crate::color::Tab10;
+
+        let classes =
+            CategoricalField::from_labels([Some("ground"), None, Some("water"), Some("ground")]);
+        let mut cloud = PointCloud::new(vec![Vec3::ZERO; 4]).with_categorical("class", classes);
+
+        let legend = cloud.apply_palette("class", &Tab10).unwrap();
+        assert_eq!(legend.title, "class");
+        assert_eq!(legend.entries.len(), 2);
+        assert_eq!(legend.entries[1].label, "water");
+
+        let colors = cloud.colors().unwrap();
+        assert_eq!(colors[0], Tab10.color(0));
+        assert_eq!(colors[0], colors[3]);
+        assert_eq!(colors[1], PointCloud::DEFAULT_BAD_VALUE_COLOR);
+        assert_eq!(legend.color_of("water"), Some(colors[2]));
+
+        assert!(matches!(
+            cloud.apply_palette("species", &Tab10),
+            Err(DataError::MissingField(_))
+        ));
+
+        // Dropping points keeps categorical codes aligned
+        let cloud = cloud
+            .with_metadata("t".to_string(), vec![1.0, f32::NAN, 2.0, 3.0])
+            .with_nan_policy(NanPolicy::Drop);
+        assert_eq!(cloud.categorical("class").unwrap().codes(), &[0, 1, 0]);
+    }
+
+    #[test]
+    fn test_filter_subset_keeps_fields_aligned() {
+        let cloud = PointCloud::new(vec![
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(5.0, 1.0, 0.0),
+            Vec3::new(-2.0, 3.0, 1.0),
+            Vec3::new(4.0, 4.0, 4.0),
+        ])
+        .with_colors(vec![Vec4::X, Vec4::Y, Vec4::Z, Vec4::W])
+        .with_sizes(vec![1.0, 2.0, 3.0, 4.0])
+        .with_metadata("temperature".to_string(), vec![280.0, 310.0, 305.0, 320.0])
+        .with_metadata("class".to_string(), vec![2.0, 2.0, 6.0, 2.0])
+        .with_categorical(
+            "species",
+            CategoricalField::from_labels([Some("oak"), Some("pine"), Some("oak"), None]),
+        )
+        .with_origin(DVec3::new(100.0, 0.0, 0.0))
+        .with_name("plot");
+        let filter = Filter::gt("temperature", 300.0).and(Filter::eq("class", 2.0));
+
+        let selection = cloud.select(&filter).unwrap();
+        assert_eq!(selection.indices(), &[1, 3]);
+        assert_eq!(
+            cloud.selection_bounds(&selection).min,
+            Vec3::new(4.0, 1.0, 0.0)
+        );
+
+        let subset = cloud.filter(&filter).unwrap();
+        assert_eq!(subset.name(), "plot");
+        assert_eq!(subset.origin(), cloud.origin());
+        assert_eq!(
+            subset.positions(),
+            &[Vec3::new(5.0, 1.0, 0.0), Vec3::new(4.0, 4.0, 4.0)]
+        );
+        assert_eq!(subset.colors().unwrap(), &[Vec4::Y, Vec4::W]);
+        assert_eq!(subset.sizes().unwrap(), &[2.0, 4.0]);
+        assert_eq!(subset.metadata("temperature").unwrap(), &[310.0, 320.0]);
+        let species = subset.categorical("species").unwrap();
+        assert_eq!(species.label(0), Some("pine"));
+        assert_eq!(species.label(1), None);
+
+        let bounds = subset.bounds();
+        assert_eq!(bounds.min, Vec3::new(4.0, 1.0, 0.0));
+        assert_eq!(bounds.max, Vec3::new(5.0, 4.0, 4.0));
+
+        let near_origin = cloud.select_by(|i| cloud.positions()[i].length() < 4.0);
+        assert_eq!(near_origin.indices(), &[0, 2]);
+    }
+
     #[test]
     fn test_with_metadata() {
         let positions = vec![Vec3::ZERO, Vec3::ONE, Vec3::X];
@@ -295,6 +1509,72 @@ mod tests {
         assert!(cloud.metadata("pressure").is_none());
     }
 
+    #[test]
+    fn test_with_vectors() {
+        let cloud = PointCloud::new(vec![Vec3::ZERO, Vec3::ONE, Vec3::X]).with_vectors(
+            "velocity",
+            vec![Vec3::new(3.0, 4.0, 0.0), Vec3::ZERO, Vec3::NAN],
+        );
+
+        assert_eq!(cloud.vector_keys(), vec!["velocity"]);
+        assert_eq!(
+            cloud.vectors("velocity").unwrap()[0],
+            Vec3::new(3.0, 4.0, 0.0)
+        );
+        let magnitudes = cloud.vector_magnitudes("velocity").unwrap();
+        assert_eq!(&magnitudes[..2], &[5.0, 0.0]);
+        assert!(magnitudes[2].is_nan());
+        assert!(cloud.vectors("force").is_none());
+
+        let subset = cloud.subset(&Selection::from_indices(vec![2, 0]));
+        assert_eq!(
+            subset.vectors("velocity").unwrap()[0],
+            Vec3::new(3.0, 4.0, 0.0)
+        );
+
+        assert!(matches!(
+            cloud.try_with_vectors("force", vec![Vec3::X]),
+            Err(DataError::LengthMismatch {
+                expected: 3,
+                found: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_field_stats_cache_invalidation() {
+        let mut cloud = PointCloud::new(vec![Vec3::ZERO, Vec3::ONE, Vec3::X])
+            .with_metadata("t".to_string(), vec![1.0, f32::NAN, 3.0]);
+        assert_eq!(cloud.field_stats("t").unwrap().mean(), 2.0);
+        assert_eq!(cloud.field_stats("t").unwrap().nan_count(), 1);
+
+        let mut cloud = cloud.with_nan_policy(NanPolicy::Substitute(5.0));
+        assert_eq!(cloud.field_stats("t").unwrap().mean(), 3.0);
+
+        let mut cloud = cloud.with_metadata("t".to_string(), vec![0.0; 3]);
+        assert_eq!(cloud.field_stats("t").unwrap().max(), 0.0);
+        assert_eq!(
+            cloud.field_stats("pressure").unwrap_err(),
+            DataError::MissingField("pressure".to_string())
+        );
+    }
+
+    #[test]
+    fn test_colormap_range_rejects_bad_percentiles() {
+        let mut cloud = PointCloud::new(vec![Vec3::ZERO, Vec3::ONE])
+            .with_metadata("t".to_string(), vec![1.0, 2.0]);
+
+        for (low, high) in [(-1.0, 50.0), (2.0, 101.0), (60.0, 40.0), (f32::NAN, 98.0)] {
+            let range = ColorRange::Percentile { low, high };
+            let err = cloud
+                .apply_colormap_range("t", &Viridis, ScaleType::Linear, range)
+                .unwrap_err();
+            assert!(matches!(err, DataError::InvalidPercentile { .. }));
+        }
+        assert!(cloud.colors().is_none());
+    }
+
     #[test]
     fn test_bounds() {
         let positions = vec![
@@ -345,10 +1625,115 @@ mod tests {
         assert!(colors[1].z > colors[1].x);
     }
 
+    /// Batch of points at x = `xs` with a matching `t` field and category
+    fn stream_batch(xs: &[f32], label: &str) -> PointCloud {
+        PointCloud::new(xs.iter().map(|&x| Vec3::new(x, 0.0, 0.0)).collect())
+            .with_metadata("t".to_string(), xs.to_vec())
+            .with_categorical(
+                "kind",
+                CategoricalField::from_labels(xs.iter().map(|_| Some(label))),
+            )
+    }
+
+    #[test]
+    fn test_extend_ring_buffer() {
+        let mut cloud = stream_batch(&[0.0, 1.0], "a").with_max_points(4);
+        cloud.extend(&stream_batch(&[2.0], "b")).unwrap();
+        assert_eq!(cloud.take_changed(), Some(2..3));
+        assert_eq!(cloud.bounds().max.x, 2.0);
+
+        // Fills the last slot, then overwrites the two oldest
+        cloud.extend(&stream_batch(&[3.0, 4.0, 5.0], "a")).unwrap();
+        assert_eq!(cloud.metadata("t").unwrap(), &[4.0, 5.0, 2.0, 3.0]);
+        assert_eq!(cloud.oldest_index(), 2);
+        assert_eq!(cloud.take_changed(), Some(0..4));
+        assert_eq!(cloud.take_changed(), None);
+        assert_eq!(cloud.bounds().min.x, 2.0);
+        assert_eq!(cloud.categorical("kind").unwrap().label(2), Some("b"));
+        assert_eq!(cloud.categorical("kind").unwrap().label(0), Some("a"));
+
+        // A batch larger than the limit keeps only its newest points
+        cloud
+            .extend(&stream_batch(&[6.0, 7.0, 8.0, 9.0, 10.0], "c"))
+            .unwrap();
+        assert_eq!(cloud.metadata("t").unwrap(), &[9.0, 10.0, 7.0, 8.0]);
+        assert_eq!(cloud.bounds().min.x, 7.0);
+
+        // Dropping points restores arrival order
+        let cloud = cloud.with_max_points(2);
+        assert_eq!(cloud.metadata("t").unwrap(), &[9.0, 10.0]);
+        assert_eq!(cloud.oldest_index(), 0);
+
+        let mut plain = PointCloud::new(Vec::new());
+        plain.push(Vec3::ONE).unwrap();
+        assert_eq!(
+            plain.extend(&stream_batch(&[1.0], "a")),
+            Err(DataError::FieldMismatch {
+                field: "t".to_string()
+            })
+        );
+        assert_eq!(plain.len(), 1);
+    }
+
+    #[test]
+    fn test_origin() {
+        let origin = DVec3::new(500_000.0, 4_000_000.0, 100.0);
+        let cloud = PointCloud::new(vec![Vec3::new(0.25, -0.5, 1.0)]).with_origin(origin);
+
+        assert_eq!(cloud.origin(), origin);
+        assert_eq!(
+            cloud.world_position(0),
+            DVec3::new(500_000.25, 3_999_999.5, 101.0)
+        );
+    }
+
+    #[test]
+    fn test_lerp() {
+        let a = PointCloud::new(vec![Vec3::ZERO, Vec3::X])
+            .with_metadata("t".to_string(), vec![0.0, 10.0])
+            .with_categorical("kind", CategoricalField::from_labels([Some("a"), None]))
+            .with_vectors("v", vec![Vec3::X, Vec3::ZERO])
+            .with_name("a");
+        let b = PointCloud::new(vec![Vec3::Y, Vec3::X])
+            .with_origin(DVec3::new(2.0, 0.0, 0.0))
+            .with_metadata("t".to_string(), vec![1.0, 20.0])
+            .with_metadata("only_b".to_string(), vec![5.0, 6.0])
+            .with_vectors("v", vec![Vec3::Y, Vec3::Z * 4.0])
+            .with_name("b");
+
+        let mid = a.lerp(&b, 0.25).unwrap();
+        assert_eq!(
+            mid.positions(),
+            &[Vec3::new(0.5, 0.25, 0.0), Vec3::new(1.5, 0.0, 0.0)]
+        );
+        assert_eq!(mid.metadata("t").unwrap(), &[0.25, 12.5]);
+        assert_eq!(mid.name(), "a");
+        assert!(mid.categorical("kind").is_some());
+        assert!(mid.metadata("only_b").is_none());
+        assert_eq!(
+            mid.vectors("v").unwrap(),
+            &[Vec3::new(0.75, 0.25, 0.0), Vec3::Z]
+        );
+
+        let late = a.lerp(&b, 0.75).unwrap();
+        assert_eq!(late.name(), "b");
+        assert_eq!(late.metadata("only_b").unwrap(), &[5.0, 6.0]);
+        assert_eq!(late.origin(), DVec3::ZERO);
+
+        let short = PointCloud::new(vec![Vec3::ZERO]);
+        assert!(matches!(
+            a.lerp(&short, 0.5),
+            Err(DataError::LengthMismatch {
+                expected: 2,
+                found: 1,
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn test_name() {
-        let cloud = PointCloud::new(vec![Vec3::ZERO])
-            .with_name("My Data");
+        let cloud = PointCloud::new(vec![Vec3::ZERO]).with_name("My Data");
 
         assert_eq!(cloud.name(), "My Data");
     }
@@ -0,0 +1,103 @@
+/// A sorted set of point indices into a dataset
+///
+/// Produced by [`PointCloud::select`](super::PointCloud::select) and consumed
+/// by [`PointCloud::subset`](super::PointCloud::subset) or a renderer that
+/// draws only the selected points.
+///
+/// # Examples
+/// ```
+/// use viz_core::data::Selection;
+///
+/// let a = Selection::from_indices([4, 1, 1, 7]);
+/// let b = Selection::from_mask(&[false, true, false, false, false, false, false, true]);
+///
+/// assert_eq!(a.indices(), &[1, 4, 7]);
+/// assert_eq!(a.intersection(&b).indices(), &[1, 7]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Selection {
+    /// Selected indices in ascending order, without duplicates
+    indices: Vec<usize>,
+}
+
+impl Selection {
+    /// Select every index in `0..len`
+    pub fn all(len: usize) -> Self {
+        Self {
+            indices: (0..len).collect(),
+        }
+    }
+
+    /// Select the indices whose mask entry is true
+    pub fn from_mask(mask: &[bool]) -> Self {
+        Self {
+            indices: mask
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &keep)| keep.then_some(i))
+                .collect(),
+        }
+    }
+
+    /// Select the given indices, in any order and with repeats allowed
+    pub fn from_indices(indices: impl IntoIterator<Item = usize>) -> Self {
+        let mut indices: Vec<usize> = indices.into_iter().collect();
+        indices.sort_unstable();
+        indices.dedup();
+        Self { indices }
+    }
+
+    /// Selected indices in ascending order
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// Number of selected indices
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Check if nothing is selected
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Check if an index is selected
+    pub fn contains(&self, index: usize) -> bool {
+        self.indices.binary_search(&index).is_ok()
+    }
+
+    /// Indices selected in both
+    pub fn intersection(&self, other: &Selection) -> Selection {
+        Self {
+            indices: self
+                .indices
+                .iter()
+                .copied()
+                .filter(|&i| other.contains(i))
+                .collect(),
+        }
+    }
+
+    /// Indices selected in either
+    pub fn union(&self, other: &Selection) -> Selection {
+        Self::from_indices(self.indices.iter().chain(&other.indices).copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_operations() {
+        let a = Selection::from_indices([5, 2, 9]);
+        let b = Selection::from_indices([9, 3, 2]);
+
+        assert_eq!(a.union(&b).indices(), &[2, 3, 5, 9]);
+        assert_eq!(a.intersection(&b).indices(), &[2, 9]);
+        assert!(a.contains(5) && !a.contains(3));
+        assert_eq!(Selection::all(3).indices(), &[0, 1, 2]);
+        assert!(Selection::from_mask(&[false, false]).is_empty());
+    }
+}
@@ -0,0 +1,261 @@
+/// Upper limit on the number of bins chosen by [`Binning::Auto`]
+const MAX_AUTO_BINS: usize = 256;
+
+/// Summary statistics of a metadata field
+///
+/// Non-finite values (NaN and ±inf) are counted separately and left out of
+/// every other statistic. A sorted copy of the finite values is kept so that
+/// any quantile or histogram can be computed without another pass over the
+/// field.
+///
+/// # Examples
+/// ```
+/// use viz_core::data::FieldStats;
+///
+/// let stats = FieldStats::from_values(&[4.0, 1.0, f32::NAN, 3.0, 2.0]);
+///
+/// assert_eq!(stats.count(), 4);
+/// assert_eq!(stats.nan_count(), 1);
+/// assert_eq!(stats.mean(), 2.5);
+/// assert_eq!(stats.median(), 2.5);
+/// assert_eq!(stats.quantile(1.0), 4.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldStats {
+    /// Finite values in ascending order
+    sorted: Vec<f32>,
+    nan_count: usize,
+    mean: f64,
+    std_dev: f64,
+}
+
+impl FieldStats {
+    /// Compute the statistics of a slice of values
+    pub fn from_values(values: &[f32]) -> Self {
+        let mut sorted: Vec<f32> = values.iter().copied().filter(|v| v.is_finite()).collect();
+        sorted.sort_unstable_by(f32::total_cmp);
+        let nan_count = values.len() - sorted.len();
+
+        // Two passes in f64 keep the variance accurate for large offsets
+        let n = sorted.len() as f64;
+        let mean = sorted.iter().map(|&v| v as f64).sum::<f64>() / n;
+        let variance = sorted
+            .iter()
+            .map(|&v| (v as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n;
+
+        Self {
+            sorted,
+            nan_count,
+            mean,
+            std_dev: variance.sqrt(),
+        }
+    }
+
+    /// Number of finite values
+    pub fn count(&self) -> usize {
+        self.sorted.len()
+    }
+
+    /// Number of non-finite values
+    pub fn nan_count(&self) -> usize {
+        self.nan_count
+    }
+
+    /// Smallest finite value (NaN if there is none)
+    pub fn min(&self) -> f32 {
+        self.sorted.first().copied().unwrap_or(f32::NAN)
+    }
+
+    /// Largest finite value (NaN if there is none)
+    pub fn max(&self) -> f32 {
+        self.sorted.last().copied().unwrap_or(f32::NAN)
+    }
+
+    /// Mean of the finite values (NaN if there is none)
+    pub fn mean(&self) -> f32 {
+        self.mean as f32
+    }
+
+    /// Population standard deviation of the finite values
+    pub fn std_dev(&self) -> f32 {
+        self.std_dev as f32
+    }
+
+    /// Value below which a fraction `q` of the finite values lie
+    ///
+    /// Interpolates linearly between the two nearest values, so `quantile(0.0)`
+    /// is the minimum and `quantile(1.0)` the maximum. NaN if there are no
+    /// finite values.
+    ///
+    /// # Panics
+    /// Panics if `q` is outside [0, 1]
+    pub fn quantile(&self, q: f32) -> f32 {
+        assert!((0.0..=1.0).contains(&q), "Quantile must be in [0, 1]");
+
+        let Some(&last) = self.sorted.last() else {
+            return f32::NAN;
+        };
+        let position = q as f64 * (self.sorted.len() - 1) as f64;
+        let below = position.floor() as usize;
+        if below + 1 >= self.sorted.len() {
+            return last;
+        }
+
+        let fraction = (position - below as f64) as f32;
+        let (a, b) = (self.sorted[below], self.sorted[below + 1]);
+        a + (b - a) * fraction
+    }
+
+    /// Quantile given as a percentage in [0, 100]
+    pub fn percentile(&self, p: f32) -> f32 {
+        self.quantile(p / 100.0)
+    }
+
+    /// Median of the finite values
+    pub fn median(&self) -> f32 {
+        self.quantile(0.5)
+    }
+
+    /// Histogram of the finite values over their full range
+    pub fn histogram(&self, binning: Binning) -> Histogram {
+        self.histogram_in(binning, self.min(), self.max())
+    }
+
+    /// Histogram of the finite values within `[min, max]`
+    ///
+    /// Values outside the range are not counted, which keeps a few outliers
+    /// from squeezing the rest of the distribution into one bin.
+    pub fn histogram_in(&self, binning: Binning, min: f32, max: f32) -> Histogram {
+        let start = self.sorted.partition_point(|&v| v < min);
+        let end = self.sorted.partition_point(|&v| v <= max);
+        let values = &self.sorted[start..end.max(start)];
+
+        let bins = match binning {
+            Binning::Fixed(bins) => bins.max(1),
+            Binning::Auto => auto_bins(values),
+        };
+
+        // Bin i covers [min + i * width, min + (i + 1) * width); the last bin
+        // also holds the maximum
+        let width = (max - min) / bins as f32;
+        let mut counts = Vec::with_capacity(bins);
+        let mut previous = 0;
+        for i in 1..bins {
+            let edge = min + width * i as f32;
+            let next = values.partition_point(|&v| v < edge);
+            counts.push(next - previous);
+            previous = next;
+        }
+        counts.push(values.len() - previous);
+
+        Histogram { min, max, counts }
+    }
+}
+
+/// How to choose the number of histogram bins
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Binning {
+    /// Exactly this many bins (at least one)
+    Fixed(usize),
+    /// Freedman-Diaconis rule, falling back to Sturges' rule when the
+    /// interquartile range is zero
+    Auto,
+}
+
+/// Counts of values in equal-width bins
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    /// Lower edge of the first bin
+    pub min: f32,
+    /// Upper edge of the last bin
+    pub max: f32,
+    /// Number of values in each bin
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    /// Width of each bin
+    pub fn bin_width(&self) -> f32 {
+        (self.max - self.min) / self.counts.len() as f32
+    }
+
+    /// Bin edges, one more than the number of bins
+    pub fn bin_edges(&self) -> Vec<f32> {
+        let width = self.bin_width();
+        (0..=self.counts.len())
+            .map(|i| self.min + width * i as f32)
+            .collect()
+    }
+
+    /// Total number of values counted
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+}
+
+/// Bin count for sorted values by the Freedman-Diaconis rule
+fn auto_bins(sorted: &[f32]) -> usize {
+    let n = sorted.len();
+    if n < 2 {
+        return 1;
+    }
+
+    let range = (sorted[n - 1] - sorted[0]) as f64;
+    let iqr = (sorted[3 * (n - 1) / 4] - sorted[(n - 1) / 4]) as f64;
+    let bins = if iqr > 0.0 {
+        let width = 2.0 * iqr / (n as f64).cbrt();
+        (range / width).ceil() as usize
+    } else {
+        (n as f64).log2().ceil() as usize + 1
+    };
+    bins.clamp(1, MAX_AUTO_BINS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_moments_and_quantiles() {
+        let stats =
+            FieldStats::from_values(&[2.0, 4.0, 4.0, f32::INFINITY, 4.0, 5.0, 5.0, 7.0, 9.0]);
+
+        assert_eq!(stats.count(), 8);
+        assert_eq!(stats.nan_count(), 1);
+        assert_eq!((stats.min(), stats.max()), (2.0, 9.0));
+        assert_eq!(stats.mean(), 5.0);
+        assert_eq!(stats.std_dev(), 2.0);
+        assert_eq!(stats.median(), 4.5);
+        assert_eq!(stats.quantile(0.25), 4.0);
+        assert_eq!(stats.percentile(100.0), 9.0);
+
+        let empty = FieldStats::from_values(&[f32::NAN]);
+        assert_eq!(empty.count(), 0);
+        assert!(empty.min().is_nan() && empty.mean().is_nan() && empty.median().is_nan());
+    }
+
+    #[test]
+    fn test_histogram_binning() {
+        let values: Vec<f32> = (0..100).map(|i| i as f32).collect();
+        let stats = FieldStats::from_values(&values);
+
+        let fixed = stats.histogram(Binning::Fixed(4));
+        assert_eq!(fixed.counts, [25, 25, 25, 25]);
+        assert_eq!(fixed.bin_edges(), [0.0, 24.75, 49.5, 74.25, 99.0]);
+
+        // IQR 50, width 2 * 50 / 100^(1/3) = 21.5 -> 5 bins
+        let auto = stats.histogram(Binning::Auto);
+        assert_eq!(auto.counts.len(), 5);
+        assert_eq!(auto.total(), 100);
+
+        let clipped = stats.histogram_in(Binning::Fixed(2), 10.0, 19.0);
+        assert_eq!(clipped.counts, [5, 5]);
+
+        // A constant field fills one bin
+        let constant = FieldStats::from_values(&[3.0; 10]).histogram(Binning::Auto);
+        assert_eq!(constant.total(), 10);
+        assert_eq!(constant.counts.iter().filter(|&&c| c > 0).count(), 1);
+    }
+}
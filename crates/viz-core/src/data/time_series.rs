@@ -0,0 +1,530 @@
+use super::{Dataset, PointCloud};
+use crate::io::{self, FileFormat, LoadError};
+use crate::math::Bounds3D;
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// File-backed frames kept in memory at once by default
+const DEFAULT_CACHE_FRAMES: usize = 8;
+
+/// A sequence of point clouds over time, e.g. one per simulation timestep
+///
+/// Frames are held in memory or streamed from files on first use, keeping
+/// only the most recently used file-backed frames loaded (see
+/// [`TimeSeries::with_cache_frames`]). The current time selects a frame;
+/// between two frames with the same number of points the positions and
+/// numeric fields are interpolated, otherwise the earlier frame is shown.
+///
+/// # Examples
+/// ```
+/// use glam::Vec3;
+/// use viz_core::data::{PointCloud, TimeSeries};
+///
+/// let mut series = TimeSeries::new()
+///     .with_frame(0.0, PointCloud::new(vec![Vec3::ZERO]))
+///     .with_frame(1.0, PointCloud::new(vec![Vec3::X]))
+///     .with_frame(2.0, PointCloud::new(vec![Vec3::X, Vec3::Y]));
+///
+/// series.set_time(0.5);
+/// assert_eq!(series.current().unwrap().positions(), &[Vec3::new(0.5, 0.0, 0.0)]);
+///
+/// // The point count changes at t = 2, so frame 1 holds until then
+/// series.set_time(1.5);
+/// assert_eq!(series.current_index(), 1);
+/// assert_eq!(series.current().unwrap().positions(), &[Vec3::X]);
+/// ```
+#[derive(Debug)]
+pub struct TimeSeries {
+    /// Frames in time order
+    frames: Vec<Frame>,
+
+    /// Current time, within the frame times
+    time: f64,
+
+    /// Blend neighbouring frames with matching point counts
+    interpolation: bool,
+
+    /// Most file-backed frames kept loaded
+    cache_frames: usize,
+
+    /// Loaded frames and known bounds, shared by `&self` readers
+    cache: Mutex<FrameCache>,
+
+    /// Dataset name
+    name: String,
+}
+
+#[derive(Debug)]
+struct Frame {
+    time: f64,
+    source: FrameSource,
+}
+
+#[derive(Debug)]
+enum FrameSource {
+    Memory(Arc<PointCloud>),
+    File(PathBuf),
+}
+
+#[derive(Debug, Default)]
+struct FrameCache {
+    /// Loaded file-backed frames by index, least recently used first
+    loaded: VecDeque<(usize, Arc<PointCloud>)>,
+
+    /// Bounds of each frame, once it has been loaded
+    bounds: Vec<Option<Bounds3D>>,
+}
+
+impl FrameCache {
+    /// Keep a loaded frame as the most recently used, evicting the least
+    /// recently used beyond `limit`
+    fn insert(&mut self, index: usize, cloud: Arc<PointCloud>, limit: usize) {
+        self.bounds[index] = Some(cloud.bounds());
+        self.loaded.retain(|(i, _)| *i != index);
+        self.loaded.push_back((index, cloud));
+        while self.loaded.len() > limit {
+            self.loaded.pop_front();
+        }
+    }
+}
+
+impl Default for TimeSeries {
+    fn default() -> Self {
+        Self {
+            frames: Vec::new(),
+            time: 0.0,
+            interpolation: true,
+            cache_frames: DEFAULT_CACHE_FRAMES,
+            cache: Mutex::default(),
+            name: "Time Series".to_string(),
+        }
+    }
+}
+
+impl TimeSeries {
+    /// Create an empty time series
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stream every loadable file in a directory as one frame
+    ///
+    /// Files are ordered by the last number in their name (so `step_9`
+    /// comes before `step_10`), then by name, and frame `i` is at time `i`.
+    /// Nothing is loaded until a frame is first used. The series is named
+    /// after the directory.
+    ///
+    /// # Errors
+    /// [`LoadError::Io`] if the directory cannot be read
+    pub fn from_directory(dir: impl AsRef<Path>) -> Result<Self, LoadError> {
+        let dir = dir.as_ref();
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_file() && FileFormat::from_path(&path).is_some() {
+                paths.push(path);
+            }
+        }
+        paths.sort_by_cached_key(|path| {
+            let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned());
+            (stem.as_deref().and_then(last_number), path.clone())
+        });
+
+        let name = dir.file_name().map_or_else(
+            || dir.display().to_string(),
+            |n| n.to_string_lossy().into_owned(),
+        );
+        Ok(paths
+            .into_iter()
+            .enumerate()
+            .fold(Self::new().with_name(name), |series, (i, path)| {
+                series.with_file(i as f64, path)
+            }))
+    }
+
+    /// Add a frame held in memory
+    ///
+    /// # Panics
+    /// Panics if `time` is not finite
+    pub fn with_frame(mut self, time: f64, cloud: PointCloud) -> Self {
+        let bounds = cloud.bounds();
+        let index = self.insert(time, FrameSource::Memory(Arc::new(cloud)));
+        self.cache
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .bounds[index] = Some(bounds);
+        self
+    }
+
+    /// Add a frame loaded from a file (in any format [`io::load`] detects)
+    /// when it is first used
+    ///
+    /// # Panics
+    /// Panics if `time` is not finite
+    pub fn with_file(mut self, time: f64, path: impl Into<PathBuf>) -> Self {
+        self.insert(time, FrameSource::File(path.into()));
+        self
+    }
+
+    /// Keep at most `frames` file-backed frames loaded (default 8)
+    ///
+    /// # Panics
+    /// Panics if `frames` is zero
+    pub fn with_cache_frames(mut self, frames: usize) -> Self {
+        assert!(
+            frames > 0,
+            "A time series must keep at least one frame loaded"
+        );
+        self.cache_frames = frames;
+        self
+    }
+
+    /// Enable or disable interpolation between frames (enabled by default)
+    pub fn with_interpolation(mut self, interpolation: bool) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Set the dataset name
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Check if neighbouring frames are interpolated
+    pub fn interpolation(&self) -> bool {
+        self.interpolation
+    }
+
+    /// Enable or disable interpolation between frames
+    pub fn set_interpolation(&mut self, interpolation: bool) {
+        self.interpolation = interpolation;
+    }
+
+    /// Number of frames
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Time of every frame, in order
+    pub fn frame_times(&self) -> Vec<f64> {
+        self.frames.iter().map(|frame| frame.time).collect()
+    }
+
+    /// Times of the first and last frame, or None without frames
+    pub fn time_range(&self) -> Option<(f64, f64)> {
+        Some((self.frames.first()?.time, self.frames.last()?.time))
+    }
+
+    /// Current time
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Set the current time, clamped to the frame times
+    pub fn set_time(&mut self, time: f64) {
+        if let Some((start, end)) = self.time_range() {
+            self.time = time.clamp(start, end);
+        }
+    }
+
+    /// Jump to the time of a frame
+    ///
+    /// # Panics
+    /// Panics if `index` is out of range
+    pub fn set_frame(&mut self, index: usize) {
+        self.time = self.frames[index].time;
+    }
+
+    /// Index of the last frame at or before the current time
+    pub fn current_index(&self) -> usize {
+        self.frames
+            .partition_point(|frame| frame.time <= self.time)
+            .saturating_sub(1)
+    }
+
+    /// Get a frame, loading it if it is not in memory
+    ///
+    /// # Errors
+    /// Any [`LoadError`] from loading the frame's file
+    ///
+    /// # Panics
+    /// Panics if `index` is out of range
+    pub fn frame(&self, index: usize) -> Result<Arc<PointCloud>, LoadError> {
+        let path = match &self.frames[index].source {
+            FrameSource::Memory(cloud) => return Ok(cloud.clone()),
+            FrameSource::File(path) => path,
+        };
+
+        {
+            let mut cache = self.cache();
+            if let Some(position) = cache.loaded.iter().position(|(i, _)| *i == index) {
+                let entry = cache.loaded.remove(position).unwrap();
+                let cloud = entry.1.clone();
+                cache.loaded.push_back(entry);
+                return Ok(cloud);
+            }
+        }
+
+        // Load without holding the lock so other readers are not blocked
+        let cloud = Arc::new(io::load(path)?);
+        self.cache().insert(index, cloud.clone(), self.cache_frames);
+        Ok(cloud)
+    }
+
+    /// Check if a frame is in memory, so [`TimeSeries::frame`] returns it
+    /// without loading
+    ///
+    /// # Panics
+    /// Panics if `index` is out of range
+    pub fn is_loaded(&self, index: usize) -> bool {
+        match &self.frames[index].source {
+            FrameSource::Memory(_) => true,
+            FrameSource::File(_) => self.cache().loaded.iter().any(|(i, _)| *i == index),
+        }
+    }
+
+    /// File a frame is loaded from, or None if it is held in memory
+    ///
+    /// # Panics
+    /// Panics if `index` is out of range
+    pub fn frame_path(&self, index: usize) -> Option<&Path> {
+        match &self.frames[index].source {
+            FrameSource::Memory(_) => None,
+            FrameSource::File(path) => Some(path),
+        }
+    }
+
+    /// Store a frame loaded elsewhere, e.g. on a background thread, or
+    /// replace a frame with an edited copy such as one with normals
+    ///
+    /// File-backed frames go into the cache like frames loaded by
+    /// [`TimeSeries::frame`], so they may be evicted later.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of range
+    pub fn store_frame(&mut self, index: usize, cloud: PointCloud) {
+        let cloud = Arc::new(cloud);
+        let cache = self.cache.get_mut().unwrap_or_else(PoisonError::into_inner);
+        match &mut self.frames[index].source {
+            FrameSource::Memory(frame) => {
+                cache.bounds[index] = Some(cloud.bounds());
+                *frame = cloud;
+            }
+            FrameSource::File(_) => cache.insert(index, cloud, self.cache_frames),
+        }
+    }
+
+    /// Indices of the frames [`TimeSeries::current`] reads at the current
+    /// time: the frame at or before it, and the next one when blending
+    pub fn current_frames(&self) -> Range<usize> {
+        if self.frames.is_empty() {
+            return 0..0;
+        }
+        let index = self.current_index();
+        let blend = self.interpolation
+            && index + 1 < self.frames.len()
+            && self.time > self.frames[index].time;
+        index..index + 1 + usize::from(blend)
+    }
+
+    /// Point cloud at the current time
+    ///
+    /// Blends the two frames around the current time when interpolation is
+    /// enabled and they have the same number of points; otherwise returns
+    /// the frame at or before it. Without frames the cloud is empty.
+    ///
+    /// # Errors
+    /// Any [`LoadError`] from loading a frame's file
+    pub fn current(&self) -> Result<Arc<PointCloud>, LoadError> {
+        if self.frames.is_empty() {
+            return Ok(Arc::new(PointCloud::new(Vec::new()).with_name(&self.name)));
+        }
+
+        let index = self.current_index();
+        let cloud = self.frame(index)?;
+        let Some(next) = self.frames.get(index + 1) else {
+            return Ok(cloud);
+        };
+        let start = self.frames[index].time;
+        if !self.interpolation || self.time <= start {
+            return Ok(cloud);
+        }
+
+        let next_cloud = self.frame(index + 1)?;
+        let t = ((self.time - start) / (next.time - start)) as f32;
+        Ok(match cloud.lerp(&next_cloud, t) {
+            Ok(blended) => Arc::new(blended),
+            Err(_) => cloud,
+        })
+    }
+
+    /// Insert a frame after any with the same time; returns its index
+    fn insert(&mut self, time: f64, source: FrameSource) -> usize {
+        assert!(time.is_finite(), "Frame times must be finite");
+
+        let index = self.frames.partition_point(|frame| frame.time <= time);
+        self.frames.insert(index, Frame { time, source });
+
+        let cache = self.cache.get_mut().unwrap_or_else(PoisonError::into_inner);
+        cache.bounds.insert(index, None);
+        for (i, _) in &mut cache.loaded {
+            if *i >= index {
+                *i += 1;
+            }
+        }
+
+        if self.frames.len() == 1 {
+            self.time = time;
+        }
+        index
+    }
+
+    fn cache(&self) -> MutexGuard<'_, FrameCache> {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Dataset for TimeSeries {
+    /// Union of the bounds of every frame loaded so far (at least the
+    /// current one)
+    fn bounds(&self) -> Bounds3D {
+        if self.frames.is_empty() {
+            return Bounds3D::zero();
+        }
+
+        let known = self
+            .cache()
+            .bounds
+            .iter()
+            .flatten()
+            .copied()
+            .reduce(|a, b| a.union(&b));
+        match known {
+            Some(bounds) => bounds,
+            None => self
+                .frame(self.current_index())
+                .map_or_else(|_| Bounds3D::zero(), |cloud| cloud.bounds()),
+        }
+    }
+
+    /// Number of points in the current frame
+    fn len(&self) -> usize {
+        if self.frames.is_empty() {
+            return 0;
+        }
+        self.frame(self.current_index())
+            .map_or(0, |cloud| cloud.len())
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Value of the last run of digits in a file name
+fn last_number(name: &str) -> Option<u64> {
+    let end = name.rfind(|c: char| c.is_ascii_digit())? + 1;
+    let start = name[..end]
+        .rfind(|c: char| !c.is_ascii_digit())
+        .map_or(0, |i| i + 1);
+    name[start..end].parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::CsvWriter;
+    use glam::Vec3;
+
+    #[test]
+    fn test_time_selection_and_interpolation() {
+        let mut series = TimeSeries::new()
+            .with_frame(2.0, PointCloud::new(vec![Vec3::X * 4.0]))
+            .with_frame(0.0, PointCloud::new(vec![Vec3::ZERO]))
+            .with_frame(1.0, PointCloud::new(vec![Vec3::X * 2.0]));
+        assert_eq!(series.frame_times(), vec![0.0, 1.0, 2.0]);
+        assert_eq!(series.time_range(), Some((0.0, 2.0)));
+
+        series.set_time(1.25);
+        assert_eq!(series.current_index(), 1);
+        assert_eq!(series.current().unwrap().positions(), &[Vec3::X * 2.5]);
+
+        series.set_interpolation(false);
+        assert_eq!(series.current().unwrap().positions(), &[Vec3::X * 2.0]);
+
+        series.set_time(10.0);
+        assert_eq!(series.time(), 2.0);
+        assert_eq!(series.current_index(), 2);
+        assert_eq!(series.bounds().max, Vec3::X * 4.0);
+        assert_eq!(series.len(), 1);
+
+        let empty = TimeSeries::new();
+        assert!(empty.current().unwrap().is_empty());
+        assert_eq!(empty.len(), 0);
+    }
+
+    #[test]
+    fn test_streams_frames_from_directory() {
+        let dir = std::env::temp_dir().join(format!("viz_core_series_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for step in [2, 10, 9] {
+            let cloud = PointCloud::new(vec![Vec3::splat(step as f32); step]);
+            CsvWriter::new()
+                .write(&cloud, dir.join(format!("step_{step}.csv")))
+                .unwrap();
+        }
+        std::fs::write(dir.join("notes.txt"), "not a frame").unwrap();
+
+        let series = TimeSeries::from_directory(&dir)
+            .unwrap()
+            .with_cache_frames(1);
+        assert_eq!(series.frame_count(), 3);
+        assert_eq!(series.name(), dir.file_name().unwrap().to_str().unwrap());
+
+        assert_eq!(series.frame(1).unwrap().len(), 9);
+        assert_eq!(series.frame(2).unwrap().len(), 10);
+        assert_eq!(series.cache().loaded.len(), 1);
+
+        // Frames seen so far widen the bounds
+        let bounds = series.bounds();
+        assert_eq!(bounds.min, Vec3::splat(9.0));
+        assert_eq!(bounds.max, Vec3::splat(10.0));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_store_frames_loaded_elsewhere() {
+        let mut series = TimeSeries::new()
+            .with_file(0.0, "missing_0.csv")
+            .with_file(1.0, "missing_1.csv")
+            .with_frame(2.0, PointCloud::new(vec![Vec3::X]))
+            .with_cache_frames(1);
+        assert_eq!(series.frame_path(0), Some(Path::new("missing_0.csv")));
+        assert_eq!(series.frame_path(2), None);
+        assert!(!series.is_loaded(0));
+        assert!(series.is_loaded(2));
+
+        // Frames from another thread are used instead of loading the file
+        series.store_frame(0, PointCloud::new(vec![Vec3::ZERO]));
+        assert!(series.is_loaded(0));
+        assert_eq!(series.current().unwrap().positions(), &[Vec3::ZERO]);
+        assert_eq!(series.current_frames(), 0..1);
+
+        // Blending between frames 0 and 1 needs both
+        series.set_time(0.5);
+        assert_eq!(series.current_frames(), 0..2);
+        series.store_frame(1, PointCloud::new(vec![Vec3::Y * 2.0]));
+        assert!(!series.is_loaded(0));
+        series.set_interpolation(false);
+        assert_eq!(series.current_frames(), 0..1);
+
+        // In-memory frames are replaced, e.g. with normals added
+        series.store_frame(2, PointCloud::new(vec![Vec3::Z]));
+        assert_eq!(series.frame(2).unwrap().positions(), &[Vec3::Z]);
+        assert_eq!(series.bounds().max, Vec3::new(0.0, 2.0, 1.0));
+    }
+}
@@ -0,0 +1,43 @@
+use crate::data::DataError;
+use crate::io::LoadError;
+use crate::renderer::RenderError;
+use thiserror::Error;
+
+/// Any error produced by viz-core
+///
+/// Lets applications propagate rendering, data and loading failures with a
+/// single `?` instead of juggling each module's error type.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Render(#[from] RenderError),
+
+    #[error(transparent)]
+    Data(#[from] DataError),
+
+    #[error(transparent)]
+    Load(#[from] LoadError),
+}
+
+/// Result type using the crate-level [`Error`]
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fails() -> Result<()> {
+        Err(DataError::MissingField("density".to_string()))?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_conversion_keeps_message() {
+        let err = fails().unwrap_err();
+        assert!(matches!(err, Error::Data(DataError::MissingField(_))));
+        assert_eq!(err.to_string(), "Metadata field 'density' not found");
+
+        let err: Error = RenderError::NoAdapterFound.into();
+        assert_eq!(err.to_string(), "Failed to find suitable GPU adapter");
+    }
+}
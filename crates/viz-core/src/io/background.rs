@@ -0,0 +1,396 @@
+use super::parquet::file_stem;
+use super::{
+    CacheLoader, CsvLoader, FileFormat, LasLoader, LoadError, NpyLoader, ParquetLoader, PlyLoader,
+};
+use crate::data::PointCloud;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::runtime::Handle;
+use tokio::sync::{oneshot, watch};
+use tracing::{info, warn};
+
+/// Minimum number of bytes read between two progress reports
+const REPORT_INTERVAL: u64 = 1 << 20;
+
+/// Unit of a [`LoadProgress`] report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressUnit {
+    /// Bytes read from the file
+    Bytes,
+    /// Rows decoded (Parquet)
+    Rows,
+}
+
+/// Progress of a background load
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadProgress {
+    /// Amount processed so far
+    pub processed: u64,
+    /// Total amount, or 0 if not yet known
+    pub total: u64,
+    /// Unit of `processed` and `total`
+    pub unit: ProgressUnit,
+}
+
+impl LoadProgress {
+    /// Fraction completed in `[0, 1]`
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        (self.processed as f64 / self.total as f64).min(1.0) as f32
+    }
+}
+
+/// Runs file loads on a tokio runtime's blocking thread pool
+///
+/// Each load returns a [`LoadHandle`] that the render loop polls once per
+/// frame for progress and for the finished [`PointCloud`], so parsing never
+/// stalls the window.
+///
+/// # Examples
+/// ```no_run
+/// use viz_core::io::BackgroundLoader;
+///
+/// let runtime = tokio::runtime::Runtime::new().unwrap();
+/// let loader = BackgroundLoader::new(runtime.handle().clone());
+/// let mut handle = loader.spawn("survey.las");
+///
+/// // Once per frame:
+/// println!("{:.0}%", handle.progress().fraction() * 100.0);
+/// if let Some(result) = handle.try_finish() {
+///     let cloud = result.unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BackgroundLoader {
+    runtime: Handle,
+}
+
+impl BackgroundLoader {
+    /// Create a loader that spawns work on the given runtime
+    pub fn new(runtime: Handle) -> Self {
+        Self { runtime }
+    }
+
+    /// Start loading a file, choosing the loader from its content or
+    /// extension
+    ///
+    /// An unrecognized file is reported through the handle as
+    /// [`LoadError::UnknownFormat`].
+    pub fn spawn(&self, path: impl Into<PathBuf>) -> LoadHandle {
+        self.start(path.into(), None)
+    }
+
+    /// Start loading a file in the given format
+    pub fn spawn_format(&self, path: impl Into<PathBuf>, format: FileFormat) -> LoadHandle {
+        self.start(path.into(), Some(format))
+    }
+
+    fn start(&self, path: PathBuf, format: Option<FileFormat>) -> LoadHandle {
+        let (progress_tx, progress_rx) = watch::channel(LoadProgress {
+            processed: 0,
+            total: 0,
+            unit: ProgressUnit::Bytes,
+        });
+        let (result_tx, result_rx) = oneshot::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let reporter = Reporter {
+            sender: progress_tx,
+            cancelled: cancelled.clone(),
+        };
+        let task_path = path.clone();
+
+        self.runtime.spawn_blocking(move || {
+            let result = run_load(&task_path, format, &reporter);
+
+            match &result {
+                Ok(cloud) => info!(
+                    "Loaded {} points from {} in the background",
+                    cloud.positions().len(),
+                    task_path.display()
+                ),
+                Err(err) => warn!("Failed to load {}: {}", task_path.display(), err),
+            }
+
+            // The handle may have been dropped; nobody is waiting then
+            let _ = result_tx.send(result);
+        });
+
+        LoadHandle {
+            path,
+            progress: progress_rx,
+            result: result_rx,
+            cancelled,
+        }
+    }
+}
+
+/// Handle to a load running in the background
+///
+/// Dropping the handle does not stop the load; call
+/// [`LoadHandle::cancel`] first.
+#[derive(Debug)]
+pub struct LoadHandle {
+    path: PathBuf,
+    progress: watch::Receiver<LoadProgress>,
+    result: oneshot::Receiver<Result<PointCloud, LoadError>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl LoadHandle {
+    /// Path of the file being loaded
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Latest progress report
+    pub fn progress(&self) -> LoadProgress {
+        *self.progress.borrow()
+    }
+
+    /// Ask the load to stop; it finishes with [`LoadError::Cancelled`]
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [`LoadHandle::cancel`] has been called
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Take the result if the load has finished, without blocking
+    ///
+    /// Returns `Some` exactly once.
+    pub fn try_finish(&mut self) -> Option<Result<PointCloud, LoadError>> {
+        match self.result.try_recv() {
+            Ok(result) => Some(result),
+            Err(oneshot::error::TryRecvError::Empty) => None,
+            Err(oneshot::error::TryRecvError::Closed) => Some(Err(task_stopped())),
+        }
+    }
+
+    /// Wait for the load to finish
+    pub async fn finish(self) -> Result<PointCloud, LoadError> {
+        self.result.await.unwrap_or_else(|_| Err(task_stopped()))
+    }
+}
+
+/// Sending side of the progress channel, shared with the reader
+struct Reporter {
+    sender: watch::Sender<LoadProgress>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Reporter {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Publish progress, failing if the load was cancelled
+    fn report(&self, processed: u64, total: u64, unit: ProgressUnit) -> Result<(), LoadError> {
+        if self.is_cancelled() {
+            return Err(LoadError::Cancelled);
+        }
+        self.sender.send_replace(LoadProgress {
+            processed,
+            total,
+            unit,
+        });
+        Ok(())
+    }
+}
+
+/// Reader that reports its position and stops once cancelled
+struct ProgressReader<'a, R> {
+    inner: R,
+    position: u64,
+    last_report: u64,
+    total: u64,
+    reporter: &'a Reporter,
+}
+
+impl<'a, R> ProgressReader<'a, R> {
+    fn new(inner: R, total: u64, reporter: &'a Reporter) -> Self {
+        Self {
+            inner,
+            position: 0,
+            last_report: 0,
+            total,
+            reporter,
+        }
+    }
+
+    fn update(&mut self, force: bool) -> io::Result<()> {
+        if self.reporter.is_cancelled() {
+            return Err(io::Error::other(LoadError::Cancelled));
+        }
+        if force || self.position.abs_diff(self.last_report) >= REPORT_INTERVAL {
+            self.last_report = self.position;
+            self.reporter
+                .report(self.position, self.total, ProgressUnit::Bytes)
+                .map_err(io::Error::other)?;
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.position += n as u64;
+        self.update(n == 0)?;
+        Ok(n)
+    }
+}
+
+impl<R: Seek> Seek for ProgressReader<'_, R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.position = self.inner.seek(pos)?;
+        self.update(false)?;
+        Ok(self.position)
+    }
+}
+
+/// Body of a background load task
+fn run_load(
+    path: &Path,
+    format: Option<FileFormat>,
+    reporter: &Reporter,
+) -> Result<PointCloud, LoadError> {
+    let format = match format {
+        Some(format) => Some(format),
+        None => FileFormat::detect(path)?,
+    };
+    let result = match format {
+        Some(format) => load_blocking(path, format, reporter),
+        None => Err(LoadError::UnknownFormat(path.display().to_string())),
+    };
+
+    // A cancelled read surfaces as whatever error the parser makes of it
+    if reporter.is_cancelled() {
+        return Err(LoadError::Cancelled);
+    }
+    result
+}
+
+/// Load a file on the current thread, publishing progress as it goes
+fn load_blocking(
+    path: &Path,
+    format: FileFormat,
+    reporter: &Reporter,
+) -> Result<PointCloud, LoadError> {
+    let file = File::open(path)?;
+
+    if format == FileFormat::Parquet {
+        let cloud = ParquetLoader::new().load_with_progress(file, |rows, total| {
+            reporter.report(rows, total, ProgressUnit::Rows)
+        })?;
+        return Ok(cloud.with_name(file_stem(path)));
+    }
+
+    let total = file.metadata()?.len();
+    let reader = ProgressReader::new(file, total, reporter);
+
+    let cloud = match format {
+        FileFormat::Csv => CsvLoader::new().load_from_reader(reader)?,
+        FileFormat::Ply => PlyLoader::new().load_from_reader(reader)?,
+        FileFormat::Las => LasLoader::new().load_from_reader(BufReader::new(reader))?,
+        FileFormat::Npy => NpyLoader::new().load_npy_from_reader(BufReader::new(reader))?,
+        FileFormat::Npz => NpyLoader::new().load_npz_from_reader(BufReader::new(reader))?,
+        // Cached datasets keep their stored name
+        FileFormat::Cache => return CacheLoader::new().load_from_reader(BufReader::new(reader)),
+        FileFormat::Parquet => unreachable!("handled above"),
+    };
+
+    Ok(cloud.with_name(file_stem(path)))
+}
+
+fn task_stopped() -> LoadError {
+    LoadError::Io(io::Error::other(
+        "background load task stopped unexpectedly",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Dataset;
+    use std::io::Write;
+
+    fn write_csv(name: &str, rows: usize) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("viz_core_{}_{}.csv", name, std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "x,y,z,value").unwrap();
+        for i in 0..rows {
+            writeln!(file, "{},{},{},{}", i, i * 2, i * 3, i % 7).unwrap();
+        }
+        path
+    }
+
+    #[test]
+    fn test_background_load_reports_progress() {
+        let path = write_csv("background", 1000);
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let loader = BackgroundLoader::new(runtime.handle().clone());
+
+        let handle = loader.spawn(&path);
+        let progress_rx = handle.progress.clone();
+        let cloud = runtime.block_on(handle.finish()).unwrap();
+
+        assert_eq!(cloud.len(), 1000);
+        assert_eq!(
+            cloud.name(),
+            format!("viz_core_background_{}", std::process::id())
+        );
+
+        let progress = *progress_rx.borrow();
+        let size = std::fs::metadata(&path).unwrap().len();
+        assert_eq!(progress.unit, ProgressUnit::Bytes);
+        assert_eq!((progress.processed, progress.total), (size, size));
+        assert_eq!(progress.fraction(), 1.0);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_cancelled_load() {
+        let path = write_csv("cancelled", 10);
+        let (sender, _receiver) = watch::channel(LoadProgress {
+            processed: 0,
+            total: 0,
+            unit: ProgressUnit::Bytes,
+        });
+        let reporter = Reporter {
+            sender,
+            cancelled: Arc::new(AtomicBool::new(true)),
+        };
+
+        let result = run_load(&path, Some(FileFormat::Csv), &reporter);
+        assert!(matches!(result, Err(LoadError::Cancelled)));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_unknown_format() {
+        let path =
+            std::env::temp_dir().join(format!("viz_core_unknown_{}.xyz", std::process::id()));
+        std::fs::write(&path, "1 2 3\n").unwrap();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let loader = BackgroundLoader::new(runtime.handle().clone());
+
+        let result = runtime.block_on(loader.spawn(&path).finish());
+        assert!(matches!(result, Err(LoadError::UnknownFormat(_))));
+
+        let result = runtime.block_on(loader.spawn(path.with_extension("missing")).finish());
+        assert!(matches!(result, Err(LoadError::Io(_))));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,498 @@
+use super::LoadError;
+use crate::data::{CategoricalField, Dataset, FieldInfo, PointCloud};
+use bytemuck::Pod;
+use glam::{DVec3, Vec3, Vec4};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+use tracing::info;
+
+/// Magic bytes at the start of a cache file
+const MAGIC: &[u8; 8] = b"VIZCACHE";
+
+/// Format version; files with any other version are rejected
+const VERSION: u32 = 1;
+
+/// Header flag: a color column follows the positions
+const FLAG_COLORS: u32 = 1 << 0;
+
+/// Header flag: a size column follows the colors
+const FLAG_SIZES: u32 = 1 << 1;
+
+/// Header flag: a normal column follows the sizes
+const FLAG_NORMALS: u32 = 1 << 2;
+
+/// Number of elements read per call, which also caps the allocation made
+/// before the data is known to exist
+const CHUNK_ELEMENTS: usize = 1 << 20;
+
+/// Loader for the native binary cache format
+///
+/// A cache file stores a [`PointCloud`] exactly as it is held in memory, so
+/// reopening it is a handful of bulk reads instead of a parse. Files are
+/// written by [`CacheWriter`]. Unlike the other loaders, the dataset keeps
+/// the name it was cached with.
+///
+/// # Layout
+/// All values are little-endian.
+///
+/// | Field | Type |
+/// |-------|------|
+/// | magic `VIZCACHE` | 8 bytes |
+/// | version, flags | u32, u32 |
+/// | point count | u64 |
+/// | origin | 3 × f64 |
+/// | name | u32 length + UTF-8 |
+/// | metadata key count, keys | u32, then u32 length + UTF-8 each |
+/// | categorical key count | u32 |
+/// | per categorical field: key, label count, labels | string, u32, strings |
+/// | field attribute count | u32 |
+/// | per field: key, unit, long name, description | 4 strings (empty = none) |
+/// | per field: valid min, valid max | 2 × f32 (NaN = none) |
+/// | vector key count, keys | u32, then strings |
+/// | positions | N × 3 f32 |
+/// | colors (if flagged) | N × 4 f32 |
+/// | sizes (if flagged) | N f32 |
+/// | normals (if flagged) | N × 3 f32 |
+/// | metadata columns | N f32 each, in key order |
+/// | categorical codes | N u32 each, in key order |
+/// | vector columns | N × 3 f32 each, in key order |
+///
+/// Strings are a u32 length followed by UTF-8 bytes.
+///
+/// # Examples
+/// ```
+/// use glam::Vec3;
+/// use viz_core::data::PointCloud;
+/// use viz_core::io::{CacheLoader, CacheWriter};
+///
+/// let cloud = PointCloud::new(vec![Vec3::ZERO, Vec3::ONE]).with_name("survey");
+///
+/// let mut bytes = Vec::new();
+/// CacheWriter::new().write_to(&cloud, &mut bytes).unwrap();
+///
+/// let loaded = CacheLoader::new().load_from_reader(bytes.as_slice()).unwrap();
+/// assert_eq!(loaded.positions(), cloud.positions());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CacheLoader;
+
+impl CacheLoader {
+    /// Create a new cache loader
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Load a point cloud from a cache file
+    pub fn load(&self, path: impl AsRef<Path>) -> Result<PointCloud, LoadError> {
+        let path = path.as_ref();
+        let cloud = self.load_from_reader(BufReader::new(File::open(path)?))?;
+
+        info!(
+            "Loaded {} points from {}",
+            cloud.positions().len(),
+            path.display()
+        );
+
+        Ok(cloud)
+    }
+
+    /// Load a point cloud from any cache reader
+    pub fn load_from_reader<R: Read>(&self, mut reader: R) -> Result<PointCloud, LoadError> {
+        let mut magic = [0u8; 8];
+        read_exact(&mut reader, &mut magic)?;
+        if &magic != MAGIC {
+            return Err(malformed("missing 'VIZCACHE' magic"));
+        }
+
+        let version = read_u32(&mut reader)?;
+        if version != VERSION {
+            return Err(malformed(format!("unsupported version {}", version)));
+        }
+
+        let flags = read_u32(&mut reader)?;
+        let count = usize::try_from(read_u64(&mut reader)?)
+            .map_err(|_| malformed("point count does not fit in memory"))?;
+
+        let mut origin = [0.0f64; 3];
+        for axis in &mut origin {
+            *axis = f64::from_bits(read_u64(&mut reader)?);
+        }
+
+        let name = read_string(&mut reader)?;
+        let key_count = read_u32(&mut reader)?;
+        let keys = (0..key_count)
+            .map(|_| read_string(&mut reader))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut categories = Vec::new();
+        for _ in 0..read_u32(&mut reader)? {
+            let key = read_string(&mut reader)?;
+            let labels = (0..read_u32(&mut reader)?)
+                .map(|_| read_string(&mut reader))
+                .collect::<Result<Vec<_>, _>>()?;
+            categories.push((key, labels));
+        }
+
+        let mut field_info = Vec::new();
+        for _ in 0..read_u32(&mut reader)? {
+            let key = read_string(&mut reader)?;
+            let mut info = FieldInfo::new();
+            let unit = read_string(&mut reader)?;
+            if !unit.is_empty() {
+                info = info.with_unit(unit);
+            }
+            let long_name = read_string(&mut reader)?;
+            if !long_name.is_empty() {
+                info = info.with_long_name(long_name);
+            }
+            let description = read_string(&mut reader)?;
+            if !description.is_empty() {
+                info = info.with_description(description);
+            }
+            let min = f32::from_bits(read_u32(&mut reader)?);
+            let max = f32::from_bits(read_u32(&mut reader)?);
+            if min <= max {
+                info = info.with_valid_range(min, max);
+            }
+            field_info.push((key, info));
+        }
+
+        let vector_keys = (0..read_u32(&mut reader)?)
+            .map(|_| read_string(&mut reader))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let positions: Vec<Vec3> = read_column(&mut reader, count)?;
+        let mut cloud = PointCloud::new(positions)
+            .with_origin(DVec3::from_array(origin))
+            .with_name(name);
+
+        if flags & FLAG_COLORS != 0 {
+            cloud = cloud.with_colors(read_column::<Vec4, _>(&mut reader, count)?);
+        }
+        if flags & FLAG_SIZES != 0 {
+            cloud = cloud.with_sizes(read_column::<f32, _>(&mut reader, count)?);
+        }
+        if flags & FLAG_NORMALS != 0 {
+            cloud = cloud.with_normals(read_column::<Vec3, _>(&mut reader, count)?);
+        }
+        for key in keys {
+            let values = read_column::<f32, _>(&mut reader, count)?;
+            cloud = cloud.with_metadata(key, values);
+        }
+        for (key, labels) in categories {
+            let codes = read_column::<u32, _>(&mut reader, count)?;
+            let field =
+                CategoricalField::new(codes, labels).map_err(|err| malformed(err.to_string()))?;
+            cloud = cloud.with_categorical(key, field);
+        }
+        for key in vector_keys {
+            let vectors = read_column::<Vec3, _>(&mut reader, count)?;
+            cloud = cloud.with_vectors(key, vectors);
+        }
+        for (key, info) in field_info {
+            cloud = cloud.with_field_info(key, info);
+        }
+
+        Ok(cloud)
+    }
+}
+
+/// Writer for the native binary cache format
+///
+/// See [`CacheLoader`] for the file layout.
+#[derive(Debug, Clone, Default)]
+pub struct CacheWriter;
+
+impl CacheWriter {
+    /// Create a new cache writer
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Write a point cloud to a cache file
+    pub fn write(&self, cloud: &PointCloud, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(cloud, &mut writer)?;
+        writer.flush()
+    }
+
+    /// Write a point cloud to any writer
+    pub fn write_to<W: Write>(&self, cloud: &PointCloud, writer: &mut W) -> io::Result<()> {
+        let mut keys: Vec<&String> = cloud.metadata_keys();
+        keys.sort();
+
+        let mut flags = 0;
+        if cloud.colors().is_some() {
+            flags |= FLAG_COLORS;
+        }
+        if cloud.sizes().is_some() {
+            flags |= FLAG_SIZES;
+        }
+        if cloud.normals().is_some() {
+            flags |= FLAG_NORMALS;
+        }
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&flags.to_le_bytes())?;
+        writer.write_all(&(cloud.positions().len() as u64).to_le_bytes())?;
+        for axis in cloud.origin().to_array() {
+            writer.write_all(&axis.to_le_bytes())?;
+        }
+        write_string(writer, cloud.name())?;
+        writer.write_all(&(keys.len() as u32).to_le_bytes())?;
+        for key in &keys {
+            write_string(writer, key)?;
+        }
+
+        let mut categorical_keys: Vec<&String> = cloud.categorical_keys();
+        categorical_keys.sort();
+        let categories: Vec<&CategoricalField> = categorical_keys
+            .iter()
+            .filter_map(|key| cloud.categorical(key))
+            .collect();
+        writer.write_all(&(categories.len() as u32).to_le_bytes())?;
+        for (key, field) in categorical_keys.iter().zip(&categories) {
+            write_string(writer, key)?;
+            writer.write_all(&(field.labels().len() as u32).to_le_bytes())?;
+            for label in field.labels() {
+                write_string(writer, label)?;
+            }
+        }
+
+        let mut vector_keys: Vec<&String> = cloud.vector_keys();
+        vector_keys.sort();
+
+        let field_info: Vec<(&String, &FieldInfo)> = keys
+            .iter()
+            .chain(&categorical_keys)
+            .chain(&vector_keys)
+            .filter_map(|&key| Some((key, cloud.field_info(key)?)))
+            .collect();
+        writer.write_all(&(field_info.len() as u32).to_le_bytes())?;
+        for (key, info) in field_info {
+            write_string(writer, key)?;
+            write_string(writer, info.unit().unwrap_or_default())?;
+            write_string(writer, info.long_name().unwrap_or_default())?;
+            write_string(writer, info.description().unwrap_or_default())?;
+            let (min, max) = info.valid_range().unwrap_or((f32::NAN, f32::NAN));
+            writer.write_all(&min.to_le_bytes())?;
+            writer.write_all(&max.to_le_bytes())?;
+        }
+
+        writer.write_all(&(vector_keys.len() as u32).to_le_bytes())?;
+        for key in &vector_keys {
+            write_string(writer, key)?;
+        }
+
+        write_column(writer, cloud.positions())?;
+        if let Some(colors) = cloud.colors() {
+            write_column(writer, colors)?;
+        }
+        if let Some(sizes) = cloud.sizes() {
+            write_column(writer, sizes)?;
+        }
+        if let Some(normals) = cloud.normals() {
+            write_column(writer, normals)?;
+        }
+        for values in keys.iter().filter_map(|key| cloud.metadata(key)) {
+            write_column(writer, values)?;
+        }
+        for field in categories {
+            write_column(writer, field.codes())?;
+        }
+        for vectors in vector_keys.iter().filter_map(|key| cloud.vectors(key)) {
+            write_column(writer, vectors)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Read a column of 32-bit values, converting from little-endian
+fn read_column<T: Pod, R: Read>(reader: &mut R, count: usize) -> Result<Vec<T>, LoadError> {
+    let mut values: Vec<T> = Vec::with_capacity(count.min(CHUNK_ELEMENTS));
+
+    while values.len() < count {
+        let start = values.len();
+        let n = (count - start).min(CHUNK_ELEMENTS);
+        values.resize(start + n, T::zeroed());
+        read_exact(reader, bytemuck::cast_slice_mut(&mut values[start..]))?;
+    }
+
+    if cfg!(target_endian = "big") {
+        for v in bytemuck::cast_slice_mut::<T, u32>(&mut values) {
+            *v = u32::from_le(*v);
+        }
+    }
+
+    Ok(values)
+}
+
+/// Write a column of 32-bit values as little-endian
+fn write_column<T: Pod, W: Write>(writer: &mut W, values: &[T]) -> io::Result<()> {
+    if cfg!(target_endian = "little") {
+        return writer.write_all(bytemuck::cast_slice(values));
+    }
+
+    for v in bytemuck::cast_slice::<T, u32>(values) {
+        writer.write_all(&v.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+fn read_exact<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<(), LoadError> {
+    reader.read_exact(buf).map_err(|err| match err.kind() {
+        ErrorKind::UnexpectedEof => malformed("file ends early"),
+        _ => LoadError::Io(err),
+    })
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, LoadError> {
+    let mut bytes = [0u8; 4];
+    read_exact(reader, &mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, LoadError> {
+    let mut bytes = [0u8; 8];
+    read_exact(reader, &mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_string<R: Read>(reader: &mut R) -> Result<String, LoadError> {
+    let len = read_u32(reader)? as usize;
+    let mut bytes = Vec::new();
+    reader
+        .take(len as u64)
+        .read_to_end(&mut bytes)
+        .map_err(LoadError::Io)?;
+    if bytes.len() != len {
+        return Err(malformed("file ends early"));
+    }
+    String::from_utf8(bytes).map_err(|_| malformed("string is not valid UTF-8"))
+}
+
+fn write_string<W: Write>(writer: &mut W, value: &str) -> io::Result<()> {
+    writer.write_all(&(value.len() as u32).to_le_bytes())?;
+    writer.write_all(value.as_bytes())
+}
+
+fn malformed(message: impl Into<String>) -> LoadError {
+    LoadError::Malformed {
+        format: "cache",
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_cloud() -> PointCloud {
+        PointCloud::new(vec![Vec3::new(0.5, -1.0, 2.0), Vec3::new(3.0, 4.0, -5.25)])
+            .with_colors(vec![Vec4::new(1.0, 0.0, 0.0, 1.0), Vec4::splat(0.5)])
+            .with_sizes(vec![1.0, 3.0])
+            .with_normals(vec![Vec3::X, Vec3::new(0.0, 0.6, 0.8)])
+            .with_metadata("density".to_string(), vec![0.1, f32::NAN])
+            .with_metadata("mass".to_string(), vec![7.0, 8.0])
+            .with_categorical(
+                "class",
+                CategoricalField::from_labels([None, Some("water")]),
+            )
+            .with_field_info(
+                "density",
+                FieldInfo::new()
+                    .with_unit("kg/m^3")
+                    .with_valid_range(0.0, 1.0),
+            )
+            .with_field_info("class", FieldInfo::new().with_description("Surface type"))
+            .with_vectors("flow", vec![Vec3::Y, Vec3::new(0.5, f32::NAN, 1.0)])
+            .with_field_info("flow", FieldInfo::new().with_unit("m/s"))
+            .with_origin(DVec3::new(512_000.25, 4_100_000.5, 12.0))
+            .with_name("survey")
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let cloud = sample_cloud();
+        let mut bytes = Vec::new();
+        CacheWriter::new().write_to(&cloud, &mut bytes).unwrap();
+
+        let loaded = CacheLoader::new()
+            .load_from_reader(bytes.as_slice())
+            .unwrap();
+
+        assert_eq!(loaded.name(), "survey");
+        assert_eq!(loaded.origin(), cloud.origin());
+        assert_eq!(loaded.positions(), cloud.positions());
+        assert_eq!(loaded.colors(), cloud.colors());
+        assert_eq!(loaded.sizes(), cloud.sizes());
+        assert_eq!(loaded.normals(), cloud.normals());
+        assert_eq!(loaded.metadata("mass"), cloud.metadata("mass"));
+        assert!(loaded.metadata("density").unwrap()[1].is_nan());
+        assert_eq!(loaded.categorical("class"), cloud.categorical("class"));
+        assert_eq!(loaded.field_info("density"), cloud.field_info("density"));
+        assert_eq!(loaded.field_info("class"), cloud.field_info("class"));
+        assert!(loaded.field_info("mass").is_none());
+        assert_eq!(loaded.vectors("flow").unwrap()[0], Vec3::Y);
+        assert!(loaded.vectors("flow").unwrap()[1].y.is_nan());
+        assert_eq!(loaded.field_info("flow"), cloud.field_info("flow"));
+        assert_eq!(loaded.bounds(), cloud.bounds());
+    }
+
+    #[test]
+    fn test_optional_columns_absent() {
+        let cloud = PointCloud::new(vec![Vec3::ONE; 3]);
+        let mut bytes = Vec::new();
+        CacheWriter::new().write_to(&cloud, &mut bytes).unwrap();
+
+        // 48 fixed header bytes, the name, the four key counts, then 3 × 12
+        // bytes
+        let header_len = 48 + 4 + cloud.name().len() + 4 + 4 + 4 + 4;
+        assert_eq!(bytes.len(), header_len + 36);
+
+        let loaded = CacheLoader::new()
+            .load_from_reader(bytes.as_slice())
+            .unwrap();
+        assert_eq!(loaded.len(), 3);
+        assert!(loaded.colors().is_none());
+        assert!(loaded.sizes().is_none());
+        assert!(loaded.normals().is_none());
+        assert!(loaded.metadata_keys().is_empty());
+    }
+
+    #[test]
+    fn test_rejects_bad_magic_version_and_truncation() {
+        let mut bytes = Vec::new();
+        CacheWriter::new()
+            .write_to(&sample_cloud(), &mut bytes)
+            .unwrap();
+        let loader = CacheLoader::new();
+
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[0] = b'X';
+        let mut wrong_version = bytes.clone();
+        wrong_version[8] = 99;
+        let mut later_version = bytes.clone();
+        later_version[8] = 2;
+        let truncated = &bytes[..bytes.len() - 1];
+
+        for data in [
+            &wrong_magic[..],
+            &wrong_version[..],
+            &later_version[..],
+            truncated,
+        ] {
+            let err = loader.load_from_reader(data).unwrap_err();
+            assert!(matches!(
+                err,
+                LoadError::Malformed {
+                    format: "cache",
+                    ..
+                }
+            ));
+        }
+    }
+}
@@ -0,0 +1,722 @@
+use super::LoadError;
+use crate::data::{CategoricalBuilder, PointCloud};
+use glam::{DVec3, Vec3};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use tracing::{debug, info};
+
+/// Loader that builds a [`PointCloud`] from a CSV file
+///
+/// Three columns are mapped to the x/y/z positions. Every other column whose
+/// values are all numeric becomes a metadata field, with empty cells stored
+/// as NaN. Columns containing text become categorical fields, labelled with
+/// each cell's text as written and with empty cells marked as missing.
+/// Numbers that come before the first text cell keep their text only if the
+/// column has a limited number of distinct values so far; otherwise they are
+/// labelled with the parsed value.
+///
+/// # Examples
+/// ```
+/// use viz_core::data::Dataset;
+/// use viz_core::io::CsvLoader;
+///
+/// let data = "lon,lat,depth,temperature\n1.0,2.0,3.0,280.5\n4.0,5.0,6.0,281.0\n";
+///
+/// let cloud = CsvLoader::new()
+///     .with_position_columns("lon", "lat", "depth")
+///     .load_from_reader(data.as_bytes())
+///     .unwrap();
+///
+/// assert_eq!(cloud.len(), 2);
+/// assert_eq!(cloud.metadata("temperature").unwrap(), &[280.5, 281.0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CsvLoader {
+    /// Column names used for the x, y and z coordinates
+    position_columns: [String; 3],
+
+    /// Explicit metadata columns (None = every other numeric column)
+    metadata_columns: Option<Vec<String>>,
+
+    /// Field delimiter
+    delimiter: u8,
+
+    /// Whether the first record is a header row
+    has_headers: bool,
+}
+
+/// Distinct cell texts kept per numeric column before it is given up as
+/// continuous data
+const MAX_CELL_LABELS: usize = 1024;
+
+/// Parsing state of a metadata column
+enum MetadataColumn {
+    /// All values so far parsed as numbers, with the distinct cell texts
+    /// kept in case a later cell turns the column categorical
+    Numeric {
+        values: Vec<f32>,
+        labels: Option<CellLabels>,
+    },
+    /// A non-numeric value was found; the column holds category labels
+    Categorical(CategoricalBuilder),
+}
+
+/// Rows parsed from a CSV file
+struct Table {
+    /// Positions, empty if no position columns were read
+    positions: Vec<Vec3>,
+    /// Metadata columns with their header names
+    columns: Vec<(String, MetadataColumn)>,
+}
+
+/// Text of the distinct values of a numeric column
+///
+/// Only kept while the column has few distinct values, i.e. still looks
+/// like category codes; past [`MAX_CELL_LABELS`] it is dropped and labels
+/// are derived from the parsed values instead.
+#[derive(Default)]
+struct CellLabels {
+    /// Text of the first cell seen with each value, keyed by its bits
+    texts: HashMap<u32, String>,
+    /// Text of the first cell that parsed to NaN, e.g. `NaN`
+    nan_text: Option<String>,
+    /// Rows whose text parsed to NaN, which empty cells also store
+    nan_rows: Vec<usize>,
+}
+
+impl CellLabels {
+    /// Record the text of a non-empty cell; false once over the limit
+    fn push(&mut self, row: usize, value: f32, cell: &str) -> bool {
+        if value.is_nan() {
+            self.nan_text.get_or_insert_with(|| cell.to_string());
+            self.nan_rows.push(row);
+        } else {
+            self.texts
+                .entry(value.to_bits())
+                .or_insert_with(|| cell.to_string());
+        }
+        self.texts.len() + self.nan_rows.len() <= MAX_CELL_LABELS
+    }
+
+    /// Label of the cell at `row` holding `value`
+    fn label(&self, row: usize, value: f32) -> Option<&str> {
+        if value.is_nan() {
+            self.nan_rows
+                .binary_search(&row)
+                .ok()
+                .and(self.nan_text.as_deref())
+        } else {
+            self.texts.get(&value.to_bits()).map(String::as_str)
+        }
+    }
+}
+
+impl CsvLoader {
+    /// Create a loader that reads positions from columns named `x`, `y` and `z`
+    pub fn new() -> Self {
+        Self {
+            position_columns: ["x".to_string(), "y".to_string(), "z".to_string()],
+            metadata_columns: None,
+            delimiter: b',',
+            has_headers: true,
+        }
+    }
+
+    /// Set the columns used for the x, y and z coordinates
+    ///
+    /// Without a header row, columns are named by their zero-based index
+    /// (`"0"`, `"1"`, ...).
+    pub fn with_position_columns(
+        mut self,
+        x: impl Into<String>,
+        y: impl Into<String>,
+        z: impl Into<String>,
+    ) -> Self {
+        self.position_columns = [x.into(), y.into(), z.into()];
+        self
+    }
+
+    /// Restrict metadata to the given columns
+    ///
+    /// Unlike auto-detected columns, these are required and numeric: a
+    /// missing column or a non-numeric value is reported as an error.
+    pub fn with_metadata_columns<I, S>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.metadata_columns = Some(columns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set the field delimiter (default: `,`)
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Set whether the first record is a header row (default: true)
+    pub fn with_headers(mut self, has_headers: bool) -> Self {
+        self.has_headers = has_headers;
+        self
+    }
+
+    /// Load a point cloud from a CSV file
+    ///
+    /// The dataset is named after the file stem.
+    pub fn load(&self, path: impl AsRef<Path>) -> Result<PointCloud, LoadError> {
+        let path = path.as_ref();
+        let file = File::open(path)?;
+        let cloud = self.load_from_reader(file)?;
+
+        info!(
+            "Loaded {} points from {}",
+            cloud.positions().len(),
+            path.display()
+        );
+
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+
+        Ok(cloud.with_name(name))
+    }
+
+    /// Load a point cloud from any CSV reader
+    pub fn load_from_reader<R: Read>(&self, reader: R) -> Result<PointCloud, LoadError> {
+        let table = self.read_table(reader, &self.position_columns)?;
+
+        let mut cloud = PointCloud::new(table.positions);
+        for (name, column) in table.columns {
+            cloud = match column {
+                MetadataColumn::Numeric { values, .. } => cloud.with_metadata(name, values),
+                MetadataColumn::Categorical(labels) => {
+                    cloud.with_categorical(name, labels.finish())
+                }
+            };
+        }
+
+        Ok(cloud)
+    }
+
+    /// Load the numeric columns of a CSV file without placing any points
+    ///
+    /// For tables with no coordinate columns, e.g. to pass to
+    /// [`PointCloud::from_pca`].
+    pub fn load_columns(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<Vec<(String, Vec<f32>)>, LoadError> {
+        self.load_columns_from_reader(File::open(path)?)
+    }
+
+    /// Load the numeric columns from any CSV reader, in file order
+    ///
+    /// The position columns are ignored. Every numeric column is returned
+    /// (or only the [metadata columns](Self::with_metadata_columns), if
+    /// set); text columns are skipped. Empty cells are stored as NaN.
+    ///
+    /// # Examples
+    /// ```
+    /// use viz_core::data::PointCloud;
+    /// use viz_core::io::CsvLoader;
+    ///
+    /// let data = "height,weight,species\n1.6,60,cat\n1.8,80,dog\n1.7,75,cat\n";
+    /// let columns = CsvLoader::new()
+    ///     .load_columns_from_reader(data.as_bytes())
+    ///     .unwrap();
+    /// assert_eq!(columns[0], ("height".to_string(), vec![1.6, 1.8, 1.7]));
+    /// assert_eq!(columns.len(), 2);
+    ///
+    /// let (cloud, _) = PointCloud::from_pca(columns).unwrap();
+    /// assert_eq!(cloud.positions().len(), 3);
+    /// ```
+    pub fn load_columns_from_reader<R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<Vec<(String, Vec<f32>)>, LoadError> {
+        let table = self.read_table(reader, &[])?;
+
+        Ok(table
+            .columns
+            .into_iter()
+            .filter_map(|(name, column)| match column {
+                MetadataColumn::Numeric { values, .. } => Some((name, values)),
+                MetadataColumn::Categorical(_) => None,
+            })
+            .collect())
+    }
+
+    /// Parse positions from the named columns (if any) and the metadata
+    /// columns, returned with their header names
+    fn read_table<R: Read>(
+        &self,
+        reader: R,
+        position_columns: &[String],
+    ) -> Result<Table, LoadError> {
+        let mut reader = ::csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .has_headers(self.has_headers)
+            .trim(::csv::Trim::All)
+            .from_reader(reader);
+
+        // Without headers we need the first record to know the column count
+        let mut first = None;
+        let headers: Vec<String> = if self.has_headers {
+            reader.headers()?.iter().map(str::to_string).collect()
+        } else {
+            let mut record = ::csv::StringRecord::new();
+            if reader.read_record(&mut record)? {
+                let names = (0..record.len()).map(|i| i.to_string()).collect();
+                first = Some(record);
+                names
+            } else {
+                Vec::new()
+            }
+        };
+
+        let position_indices = position_columns
+            .iter()
+            .map(|name| column_index(&headers, name))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let metadata_indices: Vec<usize> = match &self.metadata_columns {
+            Some(names) => names
+                .iter()
+                .map(|name| column_index(&headers, name))
+                .collect::<Result<_, _>>()?,
+            None => (0..headers.len())
+                .filter(|i| !position_indices.contains(i))
+                .collect(),
+        };
+        let strict = self.metadata_columns.is_some();
+
+        let mut positions = Vec::new();
+        let mut metadata: Vec<MetadataColumn> = metadata_indices
+            .iter()
+            .map(|_| MetadataColumn::Numeric {
+                values: Vec::new(),
+                labels: (!strict).then(CellLabels::default),
+            })
+            .collect();
+
+        for (row_index, record) in first
+            .into_iter()
+            .map(Ok)
+            .chain(reader.records())
+            .enumerate()
+        {
+            let record = record?;
+            let row = record
+                .position()
+                .map(|p| p.line())
+                .unwrap_or(row_index as u64 + 1);
+
+            if !position_indices.is_empty() {
+                let mut coords = [0.0f32; 3];
+                for (coord, &col) in coords.iter_mut().zip(&position_indices) {
+                    *coord = parse_field(&record, col, row, &headers)?;
+                }
+                positions.push(Vec3::from_array(coords));
+            }
+
+            for (column, &col) in metadata.iter_mut().zip(&metadata_indices) {
+                let cell = record.get(col).unwrap_or("");
+                let (values, cell_labels) = match column {
+                    MetadataColumn::Numeric { values, labels } => (values, labels),
+                    MetadataColumn::Categorical(labels) => {
+                        labels.push(Some(cell).filter(|cell| !cell.is_empty()));
+                        continue;
+                    }
+                };
+
+                if cell.is_empty() {
+                    values.push(f32::NAN);
+                    continue;
+                }
+
+                match cell.parse::<f32>() {
+                    Ok(value) => {
+                        let row = values.len();
+                        if cell_labels
+                            .as_mut()
+                            .is_some_and(|labels| !labels.push(row, value, cell))
+                        {
+                            debug!(
+                                "Column '{}' has too many values to keep as text",
+                                headers[col]
+                            );
+                            *cell_labels = None;
+                        }
+                        values.push(value);
+                    }
+                    Err(_) if strict => {
+                        return Err(invalid_value(row, &headers[col], cell));
+                    }
+                    Err(_) => {
+                        debug!(
+                            "Column '{}' is categorical (value '{}' at row {})",
+                            headers[col], cell, row
+                        );
+
+                        // Label earlier rows with their text as written, or
+                        // with the parsed value if there were too many
+                        let mut labels = CategoricalBuilder::new();
+                        for (i, &value) in values.iter().enumerate() {
+                            match cell_labels {
+                                Some(cell_labels) => labels.push(cell_labels.label(i, value)),
+                                None if value.is_nan() => labels.push(None),
+                                None => labels.push(Some(&value.to_string())),
+                            }
+                        }
+                        labels.push(Some(cell));
+                        *column = MetadataColumn::Categorical(labels);
+                    }
+                }
+            }
+        }
+
+        let columns = metadata_indices
+            .iter()
+            .map(|&col| headers[col].clone())
+            .zip(metadata)
+            .collect();
+
+        Ok(Table { positions, columns })
+    }
+}
+
+impl Default for CsvLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writer that saves point clouds as CSV
+///
+/// Columns are `x`, `y`, `z`, then `red`, `green`, `blue`, `alpha` (0-1) if
+/// the cloud has colors, `size` if it has sizes, the metadata fields in
+/// sorted key order, then the categorical fields as labels (empty when
+/// missing), also in sorted key order. Positions are written in world coordinates, i.e. with
+/// the cloud's origin added back.
+///
+/// # Examples
+/// ```
+/// use glam::Vec3;
+/// use viz_core::data::PointCloud;
+/// use viz_core::io::CsvWriter;
+///
+/// let cloud = PointCloud::new(vec![Vec3::new(1.0, 2.0, 3.0)])
+///     .with_metadata("mass".to_string(), vec![0.5]);
+///
+/// let mut bytes = Vec::new();
+/// CsvWriter::new().write_to(&cloud, &mut bytes).unwrap();
+/// assert_eq!(String::from_utf8(bytes).unwrap(), "x,y,z,mass\n1,2,3,0.5\n");
+/// ```
+#[derive(Debug, Clone)]
+pub struct CsvWriter {
+    /// Field delimiter
+    delimiter: u8,
+}
+
+impl CsvWriter {
+    /// Create a comma-separated writer
+    pub fn new() -> Self {
+        Self { delimiter: b',' }
+    }
+
+    /// Set the field delimiter (default: `,`)
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Write a point cloud to a CSV file
+    pub fn write(&self, cloud: &PointCloud, path: impl AsRef<Path>) -> io::Result<()> {
+        self.write_to(cloud, File::create(path)?)
+    }
+
+    /// Write a point cloud to any writer
+    pub fn write_to<W: Write>(&self, cloud: &PointCloud, writer: W) -> io::Result<()> {
+        let mut writer = ::csv::WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .from_writer(writer);
+
+        let mut keys: Vec<&String> = cloud.metadata_keys();
+        keys.sort();
+        let fields: Vec<&[f32]> = keys.iter().filter_map(|key| cloud.metadata(key)).collect();
+        let mut categorical_keys: Vec<&String> = cloud.categorical_keys();
+        categorical_keys.sort();
+        let categories: Vec<_> = categorical_keys
+            .iter()
+            .filter_map(|key| cloud.categorical(key))
+            .collect();
+        let colors = cloud.colors();
+        let sizes = cloud.sizes();
+
+        let mut header = vec!["x", "y", "z"];
+        if colors.is_some() {
+            header.extend(["red", "green", "blue", "alpha"]);
+        }
+        if sizes.is_some() {
+            header.push("size");
+        }
+        header.extend(keys.iter().map(|key| key.as_str()));
+        header.extend(categorical_keys.iter().map(|key| key.as_str()));
+        writer.write_record(&header)?;
+
+        // Without an origin the f32 positions are exact; avoid widening them
+        let shifted = cloud.origin() != DVec3::ZERO;
+        let mut record = Vec::with_capacity(header.len());
+        for (i, position) in cloud.positions().iter().enumerate() {
+            record.clear();
+            if shifted {
+                let world = cloud.world_position(i);
+                record.extend(world.to_array().map(|v| v.to_string()));
+            } else {
+                record.extend(position.to_array().map(|v| v.to_string()));
+            }
+            if let Some(colors) = colors {
+                record.extend(colors[i].to_array().map(|v| v.to_string()));
+            }
+            if let Some(sizes) = sizes {
+                record.push(sizes[i].to_string());
+            }
+            record.extend(fields.iter().map(|field| field[i].to_string()));
+            record.extend(
+                categories
+                    .iter()
+                    .map(|field| field.label(i).unwrap_or_default().to_string()),
+            );
+            writer.write_record(&record)?;
+        }
+
+        writer.flush()
+    }
+}
+
+impl Default for CsvWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Find the index of a named column
+fn column_index(headers: &[String], name: &str) -> Result<usize, LoadError> {
+    headers
+        .iter()
+        .position(|header| header == name)
+        .ok_or_else(|| LoadError::MissingColumn(name.to_string()))
+}
+
+/// Parse a required numeric field
+fn parse_field(
+    record: &::csv::StringRecord,
+    col: usize,
+    row: u64,
+    headers: &[String],
+) -> Result<f32, LoadError> {
+    let cell = record.get(col).unwrap_or("");
+    cell.parse::<f32>()
+        .map_err(|_| invalid_value(row, &headers[col], cell))
+}
+
+fn invalid_value(row: u64, column: &str, value: &str) -> LoadError {
+    LoadError::InvalidValue {
+        row,
+        column: column.to_string(),
+        value: value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{CategoricalField, Dataset};
+
+    #[test]
+    fn test_default_columns() {
+        let data = "x,y,z\n0,0,0\n1,2,3\n";
+        let cloud = CsvLoader::new().load_from_reader(data.as_bytes()).unwrap();
+
+        assert_eq!(cloud.len(), 2);
+        assert_eq!(cloud.positions()[1], Vec3::new(1.0, 2.0, 3.0));
+        assert!(cloud.metadata_keys().is_empty());
+    }
+
+    #[test]
+    fn test_column_mapping_and_metadata() {
+        let data = "id,b,a,c,temp\n7,1,2,3,300\n8,4,5,6,310\n";
+        let cloud = CsvLoader::new()
+            .with_position_columns("a", "b", "c")
+            .load_from_reader(data.as_bytes())
+            .unwrap();
+
+        assert_eq!(cloud.positions()[0], Vec3::new(2.0, 1.0, 3.0));
+        assert_eq!(cloud.metadata("temp").unwrap(), &[300.0, 310.0]);
+        assert_eq!(cloud.metadata("id").unwrap(), &[7.0, 8.0]);
+        assert!(cloud.metadata("a").is_none());
+    }
+
+    #[test]
+    fn test_text_column_is_categorical() {
+        let data =
+            "x,y,z,species,mass,plot\n0,0,0,oak,1.5,3\n1,1,1,pine,,B\n2,2,2,,2,\n3,3,3,oak,2,3\n";
+        let cloud = CsvLoader::new().load_from_reader(data.as_bytes()).unwrap();
+
+        assert!(cloud.metadata("species").is_none());
+        let species = cloud.categorical("species").unwrap();
+        assert_eq!(species.labels(), &["oak", "pine"]);
+        assert_eq!(species.codes(), &[0, 1, CategoricalField::MISSING, 0]);
+
+        // Numeric rows before the first label become labels themselves
+        let plot = cloud.categorical("plot").unwrap();
+        assert_eq!(plot.labels(), &["3", "B"]);
+        assert_eq!(plot.codes(), &[0, 1, CategoricalField::MISSING, 0]);
+
+        let mass = cloud.metadata("mass").unwrap();
+        assert_eq!(mass[0], 1.5);
+        assert!(mass[1].is_nan());
+    }
+
+    #[test]
+    fn test_categorical_labels_keep_cell_text() {
+        let data = "x,y,z,code
+0,0,0,3.0
+0,0,0,007
+0,0,0,NaN
+0,0,0,A
+0,0,0,3.0
+0,0,0,007
+";
+        let cloud = CsvLoader::new().load_from_reader(data.as_bytes()).unwrap();
+
+        // Cells before and after the first label map to the same categories
+        let code = cloud.categorical("code").unwrap();
+        assert_eq!(code.labels(), &["3.0", "007", "NaN", "A"]);
+        assert_eq!(code.codes(), &[0, 1, 2, 3, 0, 1]);
+    }
+
+    #[test]
+    fn test_many_numeric_values_before_label() {
+        let mut data = String::from("x,y,z,code\n");
+        for i in 0..MAX_CELL_LABELS + 1 {
+            data.push_str(&format!("0,0,0,{}.50\n", i));
+        }
+        data.push_str("0,0,0,\n0,0,0,A\n");
+        let cloud = CsvLoader::new().load_from_reader(data.as_bytes()).unwrap();
+
+        // Too many distinct values to keep their text, so the labels come
+        // from the parsed values
+        let code = cloud.categorical("code").unwrap();
+        assert_eq!(code.label(0), Some("0.5"));
+        assert_eq!(code.label(MAX_CELL_LABELS), Some("1024.5"));
+        assert_eq!(code.label(MAX_CELL_LABELS + 1), None);
+        assert_eq!(code.label(MAX_CELL_LABELS + 2), Some("A"));
+    }
+
+    #[test]
+    fn test_missing_position_column() {
+        let data = "x,y,w\n0,0,0\n";
+        let err = CsvLoader::new()
+            .load_from_reader(data.as_bytes())
+            .unwrap_err();
+
+        assert!(matches!(err, LoadError::MissingColumn(ref name) if name == "z"));
+    }
+
+    #[test]
+    fn test_load_columns_without_positions() {
+        let data = "a,label,b\n1,p,\n2,q,4\n";
+        let columns = CsvLoader::new()
+            .load_columns_from_reader(data.as_bytes())
+            .unwrap();
+
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[0], ("a".to_string(), vec![1.0, 2.0]));
+        assert_eq!(columns[1].0, "b");
+        assert!(columns[1].1[0].is_nan());
+        assert_eq!(columns[1].1[1], 4.0);
+
+        let err = CsvLoader::new()
+            .with_metadata_columns(["a", "c"])
+            .load_columns_from_reader(data.as_bytes())
+            .unwrap_err();
+        assert!(matches!(err, LoadError::MissingColumn(ref name) if name == "c"));
+    }
+
+    #[test]
+    fn test_invalid_position_reports_row_and_column() {
+        let data = "x,y,z\n0,0,0\n1,oops,1\n";
+        let err = CsvLoader::new()
+            .load_from_reader(data.as_bytes())
+            .unwrap_err();
+
+        match err {
+            LoadError::InvalidValue { row, column, value } => {
+                assert_eq!(row, 3);
+                assert_eq!(column, "y");
+                assert_eq!(value, "oops");
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn test_explicit_metadata_is_strict() {
+        let data = "x,y,z,label\n0,0,0,1\n1,1,1,abc\n";
+        let err = CsvLoader::new()
+            .with_metadata_columns(["label"])
+            .load_from_reader(data.as_bytes())
+            .unwrap_err();
+
+        assert!(matches!(err, LoadError::InvalidValue { row: 3, .. }));
+    }
+
+    #[test]
+    fn test_headerless_with_delimiter() {
+        let data = "1;2;3;9\n4;5;6;8\n";
+        let cloud = CsvLoader::new()
+            .with_headers(false)
+            .with_delimiter(b';')
+            .with_position_columns("0", "1", "2")
+            .load_from_reader(data.as_bytes())
+            .unwrap();
+
+        assert_eq!(cloud.len(), 2);
+        assert_eq!(cloud.positions()[1], Vec3::new(4.0, 5.0, 6.0));
+        assert_eq!(cloud.metadata("3").unwrap(), &[9.0, 8.0]);
+    }
+
+    #[test]
+    fn test_writer_round_trip() {
+        let cloud = PointCloud::new(vec![Vec3::new(0.5, 1.0, 2.0), Vec3::new(-1.0, 0.0, 3.25)])
+            .with_sizes(vec![2.0, 4.0])
+            .with_metadata("temp".to_string(), vec![300.0, f32::NAN])
+            .with_categorical(
+                "class",
+                CategoricalField::from_labels([None, Some("water")]),
+            )
+            .with_origin(DVec3::new(1000.0, 0.0, 0.0));
+
+        let mut bytes = Vec::new();
+        CsvWriter::new()
+            .with_delimiter(b';')
+            .write_to(&cloud, &mut bytes)
+            .unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+        assert!(text.starts_with("x;y;z;size;temp;class\n1000.5;1;2;2;300;\n"));
+
+        let loaded = CsvLoader::new()
+            .with_delimiter(b';')
+            .load_from_reader(text.as_bytes())
+            .unwrap();
+        assert_eq!(loaded.positions()[1], Vec3::new(999.0, 0.0, 3.25));
+        assert_eq!(loaded.metadata("size").unwrap(), &[2.0, 4.0]);
+        assert!(loaded.metadata("temp").unwrap()[1].is_nan());
+        assert_eq!(loaded.categorical("class").unwrap().label(1), Some("water"));
+    }
+}
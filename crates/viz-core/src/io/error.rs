@@ -0,0 +1,52 @@
+use thiserror::Error;
+
+/// Errors that can occur while loading a dataset from disk
+#[derive(Debug, Error)]
+pub enum LoadError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("CSV parse error: {0}")]
+    Csv(#[from] ::csv::Error),
+
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] ::parquet::errors::ParquetError),
+
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] ::arrow::error::ArrowError),
+
+    #[error("Archive error: {0}")]
+    Zip(#[from] ::zip::result::ZipError),
+
+    #[error("Column '{0}' not found")]
+    MissingColumn(String),
+
+    #[error("Invalid value '{value}' at row {row}, column '{column}'")]
+    InvalidValue {
+        row: u64,
+        column: String,
+        value: String,
+    },
+
+    #[error("Malformed {format} data: {message}")]
+    Malformed {
+        format: &'static str,
+        message: String,
+    },
+
+    #[error("Column '{column}' has unsupported type {data_type}")]
+    UnsupportedType { column: String, data_type: String },
+
+    #[error("Unrecognized file format: {0}")]
+    UnknownFormat(String),
+
+    #[error("Loading was cancelled")]
+    Cancelled,
+
+    #[error("Array '{name}' has shape {found:?}, expected {expected}")]
+    ShapeMismatch {
+        name: String,
+        expected: String,
+        found: Vec<usize>,
+    },
+}
@@ -0,0 +1,152 @@
+use super::{CacheLoader, CsvLoader, LasLoader, LoadError, NpyLoader, ParquetLoader, PlyLoader};
+use crate::data::PointCloud;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// File formats that can be loaded into a [`PointCloud`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileFormat {
+    /// Comma-separated values (`.csv`)
+    Csv,
+    /// Apache Parquet (`.parquet`, `.pq`)
+    Parquet,
+    /// Polygon file format (`.ply`)
+    Ply,
+    /// ASPRS LAS lidar (`.las`)
+    Las,
+    /// NumPy array (`.npy`)
+    Npy,
+    /// NumPy archive (`.npz`)
+    Npz,
+    /// Native binary cache (`.vzc`)
+    Cache,
+}
+
+impl FileFormat {
+    /// Detect the format from a file extension (case-insensitive)
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        Some(match extension.as_str() {
+            "csv" => Self::Csv,
+            "parquet" | "pq" => Self::Parquet,
+            "ply" => Self::Ply,
+            "las" => Self::Las,
+            "npy" => Self::Npy,
+            "npz" => Self::Npz,
+            "vzc" => Self::Cache,
+            _ => return None,
+        })
+    }
+
+    /// Detect a binary format from the first bytes of a file
+    ///
+    /// Text formats other than PLY (i.e. CSV) have no signature and are only
+    /// recognized by extension.
+    pub fn sniff(header: &[u8]) -> Option<Self> {
+        if header.starts_with(b"PAR1") {
+            Some(Self::Parquet)
+        } else if header.starts_with(b"LASF") {
+            Some(Self::Las)
+        } else if header.starts_with(b"\x93NUMPY") {
+            Some(Self::Npy)
+        } else if header.starts_with(b"PK\x03\x04") {
+            Some(Self::Npz)
+        } else if header.starts_with(b"VIZCACHE") {
+            Some(Self::Cache)
+        } else if header.starts_with(b"ply") && header.get(3).is_some_and(u8::is_ascii_whitespace) {
+            Some(Self::Ply)
+        } else {
+            None
+        }
+    }
+
+    /// Detect the format of a file from its content, then its extension
+    pub fn detect(path: impl AsRef<Path>) -> Result<Option<Self>, LoadError> {
+        let path = path.as_ref();
+        let mut header = Vec::with_capacity(8);
+        File::open(path)?.take(8).read_to_end(&mut header)?;
+
+        Ok(Self::sniff(&header).or_else(|| Self::from_path(path)))
+    }
+
+    /// Human-readable format name
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Csv => "CSV",
+            Self::Parquet => "Parquet",
+            Self::Ply => "PLY",
+            Self::Las => "LAS",
+            Self::Npy => "NumPy",
+            Self::Npz => "NumPy archive",
+            Self::Cache => "Cache",
+        }
+    }
+
+    /// Load a file with the default loader for this format
+    pub fn load(self, path: impl AsRef<Path>) -> Result<PointCloud, LoadError> {
+        let path = path.as_ref();
+        match self {
+            Self::Csv => CsvLoader::new().load(path),
+            Self::Parquet => ParquetLoader::new().load(path),
+            Self::Ply => PlyLoader::new().load(path),
+            Self::Las => LasLoader::new().load(path),
+            Self::Npy | Self::Npz => NpyLoader::new().load(path),
+            Self::Cache => CacheLoader::new().load(path),
+        }
+    }
+}
+
+/// Load a file, choosing the loader from its content or extension
+///
+/// # Examples
+/// ```no_run
+/// let cloud = viz_core::io::load("survey.las").unwrap();
+/// ```
+pub fn load(path: impl AsRef<Path>) -> Result<PointCloud, LoadError> {
+    let path = path.as_ref();
+    let format = FileFormat::detect(path)?
+        .ok_or_else(|| LoadError::UnknownFormat(path.display().to_string()))?;
+    format.load(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_path() {
+        assert_eq!(FileFormat::from_path("a/b.CSV"), Some(FileFormat::Csv));
+        assert_eq!(FileFormat::from_path("snap.pq"), Some(FileFormat::Parquet));
+        assert_eq!(FileFormat::from_path("run.npz"), Some(FileFormat::Npz));
+        assert_eq!(FileFormat::from_path("cloud.vzc"), Some(FileFormat::Cache));
+        assert_eq!(FileFormat::from_path("notes.txt"), None);
+        assert_eq!(FileFormat::from_path("no_extension"), None);
+    }
+
+    #[test]
+    fn test_sniff() {
+        assert_eq!(
+            FileFormat::sniff(b"PAR1\x15\x04"),
+            Some(FileFormat::Parquet)
+        );
+        assert_eq!(FileFormat::sniff(b"LASF\0\0"), Some(FileFormat::Las));
+        assert_eq!(FileFormat::sniff(b"ply\r\nformat"), Some(FileFormat::Ply));
+        assert_eq!(FileFormat::sniff(b"plyx"), None);
+        assert_eq!(FileFormat::sniff(b"x,y,z\n"), None);
+        assert_eq!(FileFormat::sniff(b""), None);
+    }
+
+    #[test]
+    fn test_detect_prefers_content() {
+        let path = std::env::temp_dir().join(format!("viz_core_sniff_{}.dat", std::process::id()));
+        std::fs::write(&path, b"ply\nformat ascii 1.0\n").unwrap();
+        assert_eq!(FileFormat::detect(&path).unwrap(), Some(FileFormat::Ply));
+
+        std::fs::write(&path, b"1,2,3\n").unwrap();
+        let err = load(&path).unwrap_err();
+        assert!(matches!(err, LoadError::UnknownFormat(_)));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,556 @@
+use super::{LoadError, MAX_PREALLOCATED_POINTS};
+use crate::data::{FieldInfo, PointCloud};
+use glam::{DVec3, Vec3, Vec4};
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use tracing::info;
+
+/// Size of the LAS 1.2 public header block (smallest supported)
+const MIN_HEADER_SIZE: usize = 227;
+
+/// Fields of the LAS public header block needed to decode points
+#[derive(Debug, Clone)]
+struct LasHeader {
+    version: (u8, u8),
+    offset_to_points: u64,
+    point_format: u8,
+    record_length: usize,
+    point_count: u64,
+    scale: DVec3,
+    offset: DVec3,
+    min: DVec3,
+    max: DVec3,
+}
+
+impl LasHeader {
+    fn parse(bytes: &[u8]) -> Result<Self, LoadError> {
+        if bytes.len() < MIN_HEADER_SIZE || &bytes[0..4] != b"LASF" {
+            return Err(malformed("missing 'LASF' signature"));
+        }
+
+        let version = (bytes[24], bytes[25]);
+        if version.0 != 1 || !(2..=4).contains(&version.1) {
+            return Err(malformed(format!(
+                "unsupported version {}.{} (expected 1.2-1.4)",
+                version.0, version.1
+            )));
+        }
+
+        let header_size = u16_at(bytes, 94) as usize;
+        let format_byte = bytes[104];
+        if format_byte & 0xC0 != 0 {
+            return Err(malformed("compressed (LAZ) point data is not supported"));
+        }
+        let point_format = format_byte & 0x3F;
+        if point_format > 10 {
+            return Err(malformed(format!("unknown point format {}", point_format)));
+        }
+
+        let record_length = u16_at(bytes, 105) as usize;
+        if record_length < point_format_length(point_format) {
+            return Err(malformed(format!(
+                "record length {} too short for point format {}",
+                record_length, point_format
+            )));
+        }
+
+        // LAS 1.4 moved the point count to a 64-bit field
+        let legacy_count = u32_at(bytes, 107) as u64;
+        let point_count = if version.1 >= 4 && header_size >= 255 && bytes.len() >= 255 {
+            match u64_at(bytes, 247) {
+                0 => legacy_count,
+                count => count,
+            }
+        } else {
+            legacy_count
+        };
+
+        let dvec = |at: usize| {
+            DVec3::new(
+                f64_at(bytes, at),
+                f64_at(bytes, at + 8),
+                f64_at(bytes, at + 16),
+            )
+        };
+
+        Ok(Self {
+            version,
+            offset_to_points: u32_at(bytes, 96) as u64,
+            point_format,
+            record_length,
+            point_count,
+            scale: dvec(131),
+            offset: dvec(155),
+            max: DVec3::new(f64_at(bytes, 179), f64_at(bytes, 195), f64_at(bytes, 211)),
+            min: DVec3::new(f64_at(bytes, 187), f64_at(bytes, 203), f64_at(bytes, 219)),
+        })
+    }
+
+    /// Whether this point format uses the LAS 1.4 (6-10) record layout
+    fn extended(&self) -> bool {
+        self.point_format >= 6
+    }
+
+    /// Byte offset of the GPS time field, if present
+    fn gps_time_offset(&self) -> Option<usize> {
+        match self.point_format {
+            1 | 3 | 4 | 5 => Some(20),
+            6..=10 => Some(22),
+            _ => None,
+        }
+    }
+
+    /// Byte offset of the RGB fields, if present
+    fn rgb_offset(&self) -> Option<usize> {
+        match self.point_format {
+            2 => Some(20),
+            3 | 5 => Some(28),
+            7 | 8 | 10 => Some(30),
+            _ => None,
+        }
+    }
+}
+
+/// Minimum record length of each point data format
+fn point_format_length(format: u8) -> usize {
+    match format {
+        0 => 20,
+        1 => 28,
+        2 => 26,
+        3 => 34,
+        4 => 57,
+        5 => 63,
+        6 => 30,
+        7 => 36,
+        8 => 38,
+        9 => 59,
+        _ => 67,
+    }
+}
+
+/// Loader for uncompressed LAS 1.2-1.4 lidar files
+///
+/// LAS coordinates are georeferenced doubles, so positions are stored
+/// relative to an origin ([`PointCloud::origin`]) to keep centimetre
+/// precision in f32. By default the origin is the center of the header
+/// bounds.
+///
+/// Per-point attributes become metadata fields:
+/// - `intensity`
+/// - `classification`
+/// - `return_number`
+/// - `gps_time` (point formats with GPS time; seconds since the earliest
+///   point, because absolute GPS time does not fit in f32)
+///
+/// Each field gets a long name and description (and a unit for
+/// `gps_time`) as its [`FieldInfo`]. RGB values become colors when the
+/// point format has them.
+///
+/// # Examples
+/// ```no_run
+/// use viz_core::io::LasLoader;
+///
+/// let cloud = LasLoader::new().load("survey_tile.las").unwrap();
+/// println!("origin: {:?}", cloud.origin());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LasLoader {
+    /// Explicit origin (None = center of header bounds)
+    origin: Option<DVec3>,
+}
+
+impl LasLoader {
+    /// Create a loader that centers the cloud on its header bounds
+    pub fn new() -> Self {
+        Self { origin: None }
+    }
+
+    /// Subtract a fixed origin instead of the bounds center
+    ///
+    /// Use the same origin for neighbouring tiles so they line up.
+    pub fn with_origin(mut self, origin: DVec3) -> Self {
+        self.origin = Some(origin);
+        self
+    }
+
+    /// Load a point cloud from a LAS file
+    ///
+    /// The dataset is named after the file stem.
+    pub fn load(&self, path: impl AsRef<Path>) -> Result<PointCloud, LoadError> {
+        let path = path.as_ref();
+        let cloud = self.load_from_reader(BufReader::new(File::open(path)?))?;
+
+        info!(
+            "Loaded {} points from {} (origin {:?})",
+            cloud.positions().len(),
+            path.display(),
+            cloud.origin()
+        );
+
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+
+        Ok(cloud.with_name(name))
+    }
+
+    /// Load a point cloud from any seekable LAS reader
+    pub fn load_from_reader<R: Read + Seek>(&self, mut reader: R) -> Result<PointCloud, LoadError> {
+        let mut header_bytes = vec![0u8; 375];
+        let read = read_up_to(&mut reader, &mut header_bytes)?;
+        let header = LasHeader::parse(&header_bytes[..read])?;
+
+        let origin = self.origin.unwrap_or((header.min + header.max) * 0.5);
+        let count = usize::try_from(header.point_count)
+            .map_err(|_| malformed("point count exceeds addressable memory"))?;
+
+        reader.seek(SeekFrom::Start(header.offset_to_points))?;
+
+        // Don't trust a corrupt header to size allocations up front
+        let capacity = count.min(MAX_PREALLOCATED_POINTS);
+        let mut positions = Vec::with_capacity(capacity);
+        let mut intensity = Vec::with_capacity(capacity);
+        let mut classification = Vec::with_capacity(capacity);
+        let mut return_number = Vec::with_capacity(capacity);
+        let mut gps_time: Vec<f64> = Vec::new();
+        let mut rgb: Vec<[u16; 3]> = Vec::new();
+
+        let mut record = vec![0u8; header.record_length];
+        for _ in 0..count {
+            reader.read_exact(&mut record).map_err(|e| match e.kind() {
+                std::io::ErrorKind::UnexpectedEof => malformed("point data ends early"),
+                _ => LoadError::Io(e),
+            })?;
+
+            let raw = DVec3::new(
+                i32_at(&record, 0) as f64,
+                i32_at(&record, 4) as f64,
+                i32_at(&record, 8) as f64,
+            );
+            let world = raw * header.scale + header.offset;
+            positions.push((world - origin).as_vec3());
+
+            intensity.push(u16_at(&record, 12) as f32);
+
+            if header.extended() {
+                return_number.push((record[14] & 0x0F) as f32);
+                classification.push(record[16] as f32);
+            } else {
+                return_number.push((record[14] & 0x07) as f32);
+                classification.push((record[15] & 0x1F) as f32);
+            }
+
+            if let Some(at) = header.gps_time_offset() {
+                gps_time.push(f64_at(&record, at));
+            }
+            if let Some(at) = header.rgb_offset() {
+                rgb.push([
+                    u16_at(&record, at),
+                    u16_at(&record, at + 2),
+                    u16_at(&record, at + 4),
+                ]);
+            }
+        }
+
+        let mut cloud = PointCloud::new(positions)
+            .with_origin(origin)
+            .with_metadata("intensity".to_string(), intensity)
+            .with_metadata("classification".to_string(), classification)
+            .with_metadata("return_number".to_string(), return_number)
+            .with_field_info(
+                "intensity",
+                FieldInfo::new()
+                    .with_long_name("Intensity")
+                    .with_description("Pulse return magnitude")
+                    .with_valid_range(0.0, u16::MAX as f32),
+            )
+            .with_field_info(
+                "classification",
+                FieldInfo::new()
+                    .with_long_name("Classification")
+                    .with_description("ASPRS class code, e.g. 2 = ground, 6 = building"),
+            )
+            .with_field_info(
+                "return_number",
+                FieldInfo::new()
+                    .with_long_name("Return number")
+                    .with_description("Return of the pulse, 1 for the first"),
+            );
+
+        if !gps_time.is_empty() {
+            let start = gps_time.iter().copied().fold(f64::INFINITY, f64::min);
+            info!("GPS time relative to {:.6}", start);
+            let relative = gps_time.iter().map(|t| (t - start) as f32).collect();
+            cloud = cloud
+                .with_metadata("gps_time".to_string(), relative)
+                .with_field_info(
+                    "gps_time",
+                    FieldInfo::new()
+                        .with_long_name("GPS time")
+                        .with_unit("s")
+                        .with_description(format!("Seconds after GPS time {:.6}", start)),
+                );
+        }
+
+        if !rgb.is_empty() {
+            cloud = cloud.with_colors(rgb_colors(&rgb));
+        }
+
+        info!(
+            "LAS {}.{} point format {}",
+            header.version.0, header.version.1, header.point_format
+        );
+
+        Ok(cloud)
+    }
+}
+
+/// Convert LAS RGB values to normalized colors
+///
+/// The spec says 16-bit, but many writers store 8-bit values; if no channel
+/// exceeds 255 the values are treated as 8-bit.
+fn rgb_colors(rgb: &[[u16; 3]]) -> Vec<Vec4> {
+    let max = rgb.iter().flatten().copied().max().unwrap_or(0);
+    let scale = if max <= 255 { 255.0 } else { 65535.0 };
+
+    rgb.iter()
+        .map(|&[r, g, b]| (Vec3::new(r as f32, g as f32, b as f32) / scale).extend(1.0))
+        .collect()
+}
+
+/// Read as many bytes as are available, up to the buffer length
+fn read_up_to<R: Read>(reader: &mut R, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut total = 0;
+    while total < buffer.len() {
+        match reader.read(&mut buffer[total..])? {
+            0 => break,
+            n => total += n,
+        }
+    }
+    Ok(total)
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().expect("4 bytes"))
+}
+
+fn i32_at(bytes: &[u8], at: usize) -> i32 {
+    i32::from_le_bytes(bytes[at..at + 4].try_into().expect("4 bytes"))
+}
+
+fn u64_at(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().expect("8 bytes"))
+}
+
+fn f64_at(bytes: &[u8], at: usize) -> f64 {
+    f64::from_le_bytes(bytes[at..at + 8].try_into().expect("8 bytes"))
+}
+
+fn malformed(message: impl Into<String>) -> LoadError {
+    LoadError::Malformed {
+        format: "LAS",
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Dataset;
+    use std::io::Cursor;
+
+    /// A test point: world coordinates, intensity, class, return, GPS time, RGB
+    struct TestPoint {
+        xyz: [f64; 3],
+        intensity: u16,
+        class: u8,
+        return_number: u8,
+        gps_time: f64,
+        rgb: [u16; 3],
+    }
+
+    fn test_points() -> Vec<TestPoint> {
+        vec![
+            TestPoint {
+                xyz: [500_000.01, 4_100_000.02, 120.5],
+                intensity: 100,
+                class: 2,
+                return_number: 1,
+                gps_time: 300_000_000.5,
+                rgb: [65535, 0, 0],
+            },
+            TestPoint {
+                xyz: [500_010.05, 4_100_020.07, 130.25],
+                intensity: 900,
+                class: 6,
+                return_number: 3,
+                gps_time: 300_000_002.0,
+                rgb: [0, 32768, 65535],
+            },
+        ]
+    }
+
+    /// Encode a minimal LAS file with one VLR-free header
+    fn build_las(minor: u8, format: u8, points: &[TestPoint]) -> Vec<u8> {
+        let header_size: u16 = match minor {
+            2 => 227,
+            3 => 235,
+            _ => 375,
+        };
+        let record_length = point_format_length(format) as u16;
+        let scale: f64 = 0.01;
+        let offset: [f64; 3] = [500_000.0, 4_100_000.0, 0.0];
+
+        let mut h = vec![0u8; header_size as usize];
+        h[0..4].copy_from_slice(b"LASF");
+        h[24] = 1;
+        h[25] = minor;
+        h[94..96].copy_from_slice(&header_size.to_le_bytes());
+        h[96..100].copy_from_slice(&(header_size as u32).to_le_bytes());
+        h[104] = format;
+        h[105..107].copy_from_slice(&record_length.to_le_bytes());
+        h[107..111].copy_from_slice(&(points.len() as u32).to_le_bytes());
+        for axis in 0..3 {
+            h[131 + axis * 8..139 + axis * 8].copy_from_slice(&scale.to_le_bytes());
+            h[155 + axis * 8..163 + axis * 8].copy_from_slice(&offset[axis].to_le_bytes());
+            let values = points.iter().map(|p| p.xyz[axis]);
+            let max = values.clone().fold(f64::MIN, f64::max);
+            let min = values.fold(f64::MAX, f64::min);
+            h[179 + axis * 16..187 + axis * 16].copy_from_slice(&max.to_le_bytes());
+            h[187 + axis * 16..195 + axis * 16].copy_from_slice(&min.to_le_bytes());
+        }
+        if minor >= 4 {
+            h[107..111].copy_from_slice(&0u32.to_le_bytes());
+            h[247..255].copy_from_slice(&(points.len() as u64).to_le_bytes());
+        }
+
+        let header = LasHeader::parse(&h).unwrap();
+        for p in points {
+            let mut r = vec![0u8; record_length as usize];
+            for axis in 0..3 {
+                let raw = ((p.xyz[axis] - offset[axis]) / scale).round() as i32;
+                r[axis * 4..axis * 4 + 4].copy_from_slice(&raw.to_le_bytes());
+            }
+            r[12..14].copy_from_slice(&p.intensity.to_le_bytes());
+            if header.extended() {
+                r[14] = p.return_number | (3 << 4);
+                r[16] = p.class;
+            } else {
+                r[14] = p.return_number | (3 << 3);
+                r[15] = p.class;
+            }
+            if let Some(at) = header.gps_time_offset() {
+                r[at..at + 8].copy_from_slice(&p.gps_time.to_le_bytes());
+            }
+            if let Some(at) = header.rgb_offset() {
+                for (i, c) in p.rgb.iter().enumerate() {
+                    r[at + i * 2..at + i * 2 + 2].copy_from_slice(&c.to_le_bytes());
+                }
+            }
+            h.extend_from_slice(&r);
+        }
+        h
+    }
+
+    fn check_common(cloud: &PointCloud) {
+        let points = test_points();
+        assert_eq!(cloud.len(), 2);
+
+        for (i, p) in points.iter().enumerate() {
+            let world = cloud.world_position(i);
+            let expected = DVec3::from_array(p.xyz);
+            assert!(
+                (world - expected).abs().max_element() < 0.006,
+                "{world:?} vs {expected:?}"
+            );
+        }
+
+        assert_eq!(cloud.metadata("intensity").unwrap(), &[100.0, 900.0]);
+        assert_eq!(cloud.metadata("classification").unwrap(), &[2.0, 6.0]);
+        assert_eq!(cloud.metadata("return_number").unwrap(), &[1.0, 3.0]);
+    }
+
+    #[test]
+    fn test_las12_format3() {
+        let bytes = build_las(2, 3, &test_points());
+        let cloud = LasLoader::new()
+            .load_from_reader(Cursor::new(bytes))
+            .unwrap();
+
+        check_common(&cloud);
+
+        // Origin is the bounds center, so local positions stay small
+        assert!(cloud
+            .positions()
+            .iter()
+            .all(|p| p.abs().max_element() < 20.0));
+        assert_eq!(cloud.metadata("gps_time").unwrap(), &[0.0, 1.5]);
+        assert_eq!(cloud.field_label("gps_time"), "GPS time [s]");
+
+        let colors = cloud.colors().unwrap();
+        assert_eq!(colors[0], Vec4::new(1.0, 0.0, 0.0, 1.0));
+        assert!((colors[1].y - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_las14_format7_with_fixed_origin() {
+        let bytes = build_las(4, 7, &test_points());
+        let origin = DVec3::new(500_000.0, 4_100_000.0, 100.0);
+        let cloud = LasLoader::new()
+            .with_origin(origin)
+            .load_from_reader(Cursor::new(bytes))
+            .unwrap();
+
+        check_common(&cloud);
+        assert_eq!(cloud.origin(), origin);
+        assert!(cloud.colors().is_some());
+        assert!(cloud.metadata("gps_time").is_some());
+    }
+
+    #[test]
+    fn test_format0_has_no_colors_or_gps() {
+        let bytes = build_las(3, 0, &test_points());
+        let cloud = LasLoader::new()
+            .load_from_reader(Cursor::new(bytes))
+            .unwrap();
+
+        check_common(&cloud);
+        assert!(cloud.colors().is_none());
+        assert!(cloud.metadata("gps_time").is_none());
+    }
+
+    #[test]
+    fn test_rejects_laz_and_truncated_files() {
+        let mut bytes = build_las(2, 1, &test_points());
+        bytes[104] |= 0x80;
+        let err = LasLoader::new()
+            .load_from_reader(Cursor::new(bytes))
+            .unwrap_err();
+        assert!(matches!(err, LoadError::Malformed { format: "LAS", .. }));
+
+        let mut bytes = build_las(2, 1, &test_points());
+        bytes.truncate(bytes.len() - 4);
+        let err = LasLoader::new()
+            .load_from_reader(Cursor::new(bytes))
+            .unwrap_err();
+        assert!(matches!(err, LoadError::Malformed { format: "LAS", .. }));
+
+        let err = LasLoader::new()
+            .load_from_reader(Cursor::new(b"not a las file".to_vec()))
+            .unwrap_err();
+        assert!(matches!(err, LoadError::Malformed { format: "LAS", .. }));
+    }
+
+    #[test]
+    fn test_eight_bit_rgb() {
+        let colors = rgb_colors(&[[255, 0, 128]]);
+        assert_eq!(colors[0].x, 1.0);
+        assert!((colors[0].z - 128.0 / 255.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,25 @@
+#[cfg(not(target_arch = "wasm32"))]
+mod background;
+mod cache;
+mod csv;
+mod error;
+mod format;
+mod las;
+mod npy;
+mod parquet;
+mod ply;
+
+pub use self::csv::{CsvLoader, CsvWriter};
+pub use self::parquet::ParquetLoader;
+#[cfg(not(target_arch = "wasm32"))]
+pub use background::{BackgroundLoader, LoadHandle, LoadProgress, ProgressUnit};
+pub use cache::{CacheLoader, CacheWriter};
+pub use error::LoadError;
+pub use format::{load, FileFormat};
+pub use las::LasLoader;
+pub use npy::NpyLoader;
+pub use ply::{PlyFormat, PlyLoader, PlyWriter};
+
+/// Upper bound on points preallocated from a header count, so a corrupt
+/// header cannot force a huge allocation before any data is read
+const MAX_PREALLOCATED_POINTS: usize = 1 << 24;
@@ -0,0 +1,583 @@
+use super::{LoadError, MAX_PREALLOCATED_POINTS};
+use crate::data::PointCloud;
+use glam::Vec3;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Magic prefix of a `.npy` file
+const NPY_MAGIC: &[u8; 6] = b"\x93NUMPY";
+
+/// Magic prefix of a zip archive (`.npz`)
+const ZIP_MAGIC: &[u8; 4] = b"PK\x03\x04";
+
+/// Number of elements decoded per read when streaming array data
+const CHUNK_ELEMENTS: usize = 64 * 1024;
+
+/// Element type of a NumPy array
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DType {
+    Float { size: usize },
+    Int { size: usize },
+    UInt { size: usize },
+    Bool,
+}
+
+impl DType {
+    fn size(self) -> usize {
+        match self {
+            Self::Float { size } | Self::Int { size } | Self::UInt { size } => size,
+            Self::Bool => 1,
+        }
+    }
+
+    /// Decode one element to f32
+    fn decode(self, bytes: &[u8], big_endian: bool) -> f32 {
+        macro_rules! decode {
+            ($t:ty) => {{
+                let array = bytes.try_into().expect("element size matches dtype");
+                if big_endian {
+                    <$t>::from_be_bytes(array) as f32
+                } else {
+                    <$t>::from_le_bytes(array) as f32
+                }
+            }};
+        }
+
+        match self {
+            Self::Float { size: 4 } => decode!(f32),
+            Self::Float { .. } => decode!(f64),
+            Self::Int { size: 1 } => decode!(i8),
+            Self::Int { size: 2 } => decode!(i16),
+            Self::Int { size: 4 } => decode!(i32),
+            Self::Int { .. } => decode!(i64),
+            Self::UInt { size: 1 } => decode!(u8),
+            Self::UInt { size: 2 } => decode!(u16),
+            Self::UInt { size: 4 } => decode!(u32),
+            Self::UInt { .. } => decode!(u64),
+            Self::Bool => (bytes[0] != 0) as u8 as f32,
+        }
+    }
+}
+
+/// A decoded NumPy array, converted to f32
+#[derive(Debug)]
+struct NpyArray {
+    shape: Vec<usize>,
+    fortran_order: bool,
+    /// Values in file order
+    values: Vec<f32>,
+}
+
+impl NpyArray {
+    /// Interpret an (N, 3) array as positions
+    fn into_positions(self, name: &str) -> Result<Vec<Vec3>, LoadError> {
+        let n = match self.shape.as_slice() {
+            [n, 3] => *n,
+            _ => return Err(shape_mismatch(name, "(N, 3)", self.shape)),
+        };
+
+        let v = &self.values;
+        Ok(if self.fortran_order {
+            (0..n)
+                .map(|i| Vec3::new(v[i], v[n + i], v[2 * n + i]))
+                .collect()
+        } else {
+            v.chunks_exact(3).map(Vec3::from_slice).collect()
+        })
+    }
+
+    /// Interpret an (N,) array as a metadata field of length `len`
+    fn into_field(self, name: &str, len: usize) -> Result<Vec<f32>, LoadError> {
+        match self.shape.as_slice() {
+            [n] if *n == len => Ok(self.values),
+            _ => Err(shape_mismatch(name, &format!("({},)", len), self.shape)),
+        }
+    }
+}
+
+/// Loader for NumPy `.npy` and `.npz` files
+///
+/// A `.npy` file must hold an (N, 3) position array. In a `.npz` archive the
+/// (N, 3) array named by [`NpyLoader::with_positions_key`] (default
+/// `positions`) becomes the positions and every (N,) array becomes a
+/// metadata field. float32/float64 (and integer/bool) arrays in C or Fortran
+/// order are supported.
+///
+/// # Examples
+/// ```no_run
+/// use viz_core::io::NpyLoader;
+///
+/// // Saved from Python with:
+/// //   np.savez("run.npz", positions=xyz, temperature=t, density=rho)
+/// let cloud = NpyLoader::new().load("run.npz").unwrap();
+/// assert!(cloud.metadata("temperature").is_some());
+/// ```
+#[derive(Debug, Clone)]
+pub struct NpyLoader {
+    /// Name of the (N, 3) array in an archive
+    positions_key: String,
+
+    /// Explicit metadata arrays (None = every other (N,) array)
+    metadata_keys: Option<Vec<String>>,
+}
+
+impl NpyLoader {
+    /// Create a loader that reads positions from the `positions` array
+    pub fn new() -> Self {
+        Self {
+            positions_key: "positions".to_string(),
+            metadata_keys: None,
+        }
+    }
+
+    /// Set the name of the (N, 3) position array inside `.npz` archives
+    pub fn with_positions_key(mut self, key: impl Into<String>) -> Self {
+        self.positions_key = key.into();
+        self
+    }
+
+    /// Restrict metadata to the given archive arrays
+    ///
+    /// Unlike auto-detected arrays, these are required: a missing array, an
+    /// unsupported dtype or a wrong shape is reported as an error.
+    pub fn with_metadata_keys<I, S>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.metadata_keys = Some(keys.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Load a `.npy` or `.npz` file, detected from its content
+    ///
+    /// The dataset is named after the file stem.
+    pub fn load(&self, path: impl AsRef<Path>) -> Result<PointCloud, LoadError> {
+        let path = path.as_ref();
+        let mut file = BufReader::new(File::open(path)?);
+
+        let mut magic = [0u8; 4];
+        file.read_exact(&mut magic)?;
+        file.rewind()?;
+
+        let cloud = if &magic == ZIP_MAGIC {
+            self.load_npz_from_reader(file)?
+        } else {
+            self.load_npy_from_reader(file)?
+        };
+
+        info!(
+            "Loaded {} points from {}",
+            cloud.positions().len(),
+            path.display()
+        );
+
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+
+        Ok(cloud.with_name(name))
+    }
+
+    /// Load positions from a single `.npy` (N, 3) array
+    pub fn load_npy_from_reader<R: Read>(&self, reader: R) -> Result<PointCloud, LoadError> {
+        let array = read_npy(reader, "array")?;
+        Ok(PointCloud::new(array.into_positions("array")?))
+    }
+
+    /// Load positions and metadata from a `.npz` archive
+    pub fn load_npz_from_reader<R: Read + Seek>(&self, reader: R) -> Result<PointCloud, LoadError> {
+        let mut archive = zip::ZipArchive::new(reader)?;
+
+        let entries: Vec<(String, String)> = archive
+            .file_names()
+            .filter_map(|file| {
+                let key = file.strip_suffix(".npy")?;
+                Some((key.to_string(), file.to_string()))
+            })
+            .collect();
+        let entry_name = |key: &str| {
+            entries
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, file)| file.clone())
+                .ok_or_else(|| LoadError::MissingColumn(key.to_string()))
+        };
+
+        let positions_file = entry_name(&self.positions_key)?;
+        let positions = read_npy(archive.by_name(&positions_file)?, &self.positions_key)?
+            .into_positions(&self.positions_key)?;
+        let len = positions.len();
+
+        let strict = self.metadata_keys.is_some();
+        let keys: Vec<String> = match &self.metadata_keys {
+            Some(keys) => keys.clone(),
+            None => entries
+                .iter()
+                .map(|(key, _)| key.clone())
+                .filter(|key| key != &self.positions_key)
+                .collect(),
+        };
+
+        let mut cloud = PointCloud::new(positions);
+        for key in keys {
+            let file = entry_name(&key)?;
+            let field =
+                read_npy(archive.by_name(&file)?, &key).and_then(|a| a.into_field(&key, len));
+
+            match field {
+                Ok(values) => cloud = cloud.with_metadata(key, values),
+                Err(err) if strict => return Err(err),
+                Err(err) => warn!("Skipping array '{}': {}", key, err),
+            }
+        }
+
+        Ok(cloud)
+    }
+}
+
+impl Default for NpyLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse a `.npy` stream into an f32 array
+fn read_npy<R: Read>(mut reader: R, name: &str) -> Result<NpyArray, LoadError> {
+    let mut preamble = [0u8; 8];
+    reader
+        .read_exact(&mut preamble)
+        .map_err(|_| malformed("file too short"))?;
+    if &preamble[..6] != NPY_MAGIC {
+        return Err(malformed("missing '\\x93NUMPY' magic"));
+    }
+
+    let header_len = match preamble[6] {
+        1 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_le_bytes(len) as usize
+        }
+        2 | 3 => {
+            let mut len = [0u8; 4];
+            reader.read_exact(&mut len)?;
+            u32::from_le_bytes(len) as usize
+        }
+        version => return Err(malformed(format!("unsupported version {}", version))),
+    };
+
+    let mut header = vec![0u8; header_len];
+    reader.read_exact(&mut header)?;
+    let header = String::from_utf8_lossy(&header);
+
+    let descr = header_value(&header, "descr")
+        .map(|v| v.trim_matches(|c| c == '\'' || c == '"'))
+        .ok_or_else(|| malformed("header has no 'descr'"))?;
+    let (dtype, big_endian) = parse_descr(descr).ok_or_else(|| LoadError::UnsupportedType {
+        column: name.to_string(),
+        data_type: descr.to_string(),
+    })?;
+
+    let fortran_order = header_value(&header, "fortran_order") == Some("True");
+
+    let shape_str =
+        header_value(&header, "shape").ok_or_else(|| malformed("header has no 'shape'"))?;
+    let shape = shape_str
+        .trim_matches(|c| c == '(' || c == ')')
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| malformed(format!("invalid shape {}", shape_str)))?;
+
+    let count = shape
+        .iter()
+        .try_fold(1usize, |acc, &d| acc.checked_mul(d))
+        .ok_or_else(|| malformed("shape overflows"))?;
+
+    // Decode in chunks so f64 arrays never need a second full-size buffer
+    let size = dtype.size();
+    let mut values = Vec::with_capacity(count.min(MAX_PREALLOCATED_POINTS));
+    let mut buffer = vec![0u8; CHUNK_ELEMENTS.min(count.max(1)) * size];
+    let mut remaining = count;
+    while remaining > 0 {
+        let n = remaining.min(CHUNK_ELEMENTS);
+        let bytes = &mut buffer[..n * size];
+        reader
+            .read_exact(bytes)
+            .map_err(|_| malformed("array data ends early"))?;
+        values.extend(
+            bytes
+                .chunks_exact(size)
+                .map(|b| dtype.decode(b, big_endian)),
+        );
+        remaining -= n;
+    }
+
+    Ok(NpyArray {
+        shape,
+        fortran_order,
+        values,
+    })
+}
+
+/// Extract the raw value of a key from the header dictionary
+fn header_value<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    let start = header
+        .find(&format!("'{}'", key))
+        .or_else(|| header.find(&format!("\"{}\"", key)))?;
+    let rest = header[start + key.len() + 2..]
+        .trim_start()
+        .strip_prefix(':')?
+        .trim_start();
+
+    let end = if rest.starts_with('(') {
+        rest.find(')')? + 1
+    } else {
+        rest.find([',', '}']).unwrap_or(rest.len())
+    };
+    Some(rest[..end].trim())
+}
+
+/// Parse a dtype description such as `<f4`; returns (dtype, big_endian)
+fn parse_descr(descr: &str) -> Option<(DType, bool)> {
+    // Checked splits: an empty or non-ASCII descr is unsupported, not a panic
+    let (order, rest) = descr.split_at_checked(1)?;
+    let big_endian = match order {
+        "<" | "|" => false,
+        ">" => true,
+        "=" => cfg!(target_endian = "big"),
+        _ => return None,
+    };
+
+    let (kind, size) = rest.split_at_checked(1)?;
+    let size: usize = size.parse().ok()?;
+    let dtype = match (kind, size) {
+        ("f", 4 | 8) => DType::Float { size },
+        ("i", 1 | 2 | 4 | 8) => DType::Int { size },
+        ("u", 1 | 2 | 4 | 8) => DType::UInt { size },
+        ("b", 1) => DType::Bool,
+        _ => return None,
+    };
+    Some((dtype, big_endian))
+}
+
+fn shape_mismatch(name: &str, expected: &str, found: Vec<usize>) -> LoadError {
+    LoadError::ShapeMismatch {
+        name: name.to_string(),
+        expected: expected.to_string(),
+        found,
+    }
+}
+
+fn malformed(message: impl Into<String>) -> LoadError {
+    LoadError::Malformed {
+        format: "NumPy",
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Dataset;
+    use std::io::{Cursor, Write};
+    use zip::write::FileOptions;
+
+    /// Encode a version 1.0 `.npy` file
+    fn npy(descr: &str, fortran_order: bool, shape: &str, data: &[u8]) -> Vec<u8> {
+        let mut header = format!(
+            "{{'descr': '{}', 'fortran_order': {}, 'shape': {}, }}",
+            descr,
+            if fortran_order { "True" } else { "False" },
+            shape
+        );
+        // Pad to a multiple of 64 bytes including the trailing newline
+        while (10 + header.len() + 1) % 64 != 0 {
+            header.push(' ');
+        }
+        header.push('\n');
+
+        let mut bytes = NPY_MAGIC.to_vec();
+        bytes.extend_from_slice(&[1, 0]);
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    fn f32_bytes(values: &[f32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    fn f64_be_bytes(values: &[f64]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    fn npz(entries: &[(&str, Vec<u8>)], method: zip::CompressionMethod) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, bytes) in entries {
+            let options = FileOptions::default().compression_method(method);
+            writer.start_file(format!("{}.npy", name), options).unwrap();
+            writer.write_all(bytes).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_npy_c_order_f32() {
+        let data = f32_bytes(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0]);
+        let bytes = npy("<f4", false, "(2, 3)", &data);
+        let cloud = NpyLoader::new()
+            .load_npy_from_reader(bytes.as_slice())
+            .unwrap();
+
+        assert_eq!(cloud.len(), 2);
+        assert_eq!(cloud.positions()[1], Vec3::new(3.0, 4.0, 5.0));
+    }
+
+    #[test]
+    fn test_npy_fortran_order_big_endian_f64() {
+        // Column-major: x0 x1 y0 y1 z0 z1
+        let data = f64_be_bytes(&[0.0, 1.0, 10.0, 11.0, 20.0, 21.0]);
+        let bytes = npy(">f8", true, "(2, 3)", &data);
+        let cloud = NpyLoader::new()
+            .load_npy_from_reader(bytes.as_slice())
+            .unwrap();
+
+        assert_eq!(cloud.positions()[0], Vec3::new(0.0, 10.0, 20.0));
+        assert_eq!(cloud.positions()[1], Vec3::new(1.0, 11.0, 21.0));
+    }
+
+    #[test]
+    fn test_npy_wrong_shape() {
+        let bytes = npy("<f4", false, "(3,)", &f32_bytes(&[0.0, 1.0, 2.0]));
+        let err = NpyLoader::new()
+            .load_npy_from_reader(bytes.as_slice())
+            .unwrap_err();
+
+        assert!(matches!(err, LoadError::ShapeMismatch { ref found, .. } if found == &[3]));
+    }
+
+    #[test]
+    fn test_npy_unsupported_dtype() {
+        let bytes = npy("<U8", false, "(1, 3)", &[0; 96]);
+        let err = NpyLoader::new()
+            .load_npy_from_reader(bytes.as_slice())
+            .unwrap_err();
+
+        assert!(
+            matches!(err, LoadError::UnsupportedType { ref data_type, .. } if data_type == "<U8")
+        );
+    }
+
+    #[test]
+    fn test_npy_malformed_descr() {
+        for descr in ["", "<", "é4", "<é4"] {
+            let bytes = npy(descr, false, "(1, 3)", &f32_bytes(&[0.0; 3]));
+            let err = NpyLoader::new()
+                .load_npy_from_reader(bytes.as_slice())
+                .unwrap_err();
+
+            assert!(
+                matches!(err, LoadError::UnsupportedType { ref data_type, .. } if data_type == descr)
+            );
+        }
+    }
+
+    #[test]
+    fn test_npy_truncated() {
+        let bytes = npy("<f4", false, "(2, 3)", &f32_bytes(&[0.0, 1.0, 2.0]));
+        let err = NpyLoader::new()
+            .load_npy_from_reader(bytes.as_slice())
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            LoadError::Malformed {
+                format: "NumPy",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_npz_positions_and_metadata() {
+        let positions = npy(
+            "<f4",
+            false,
+            "(2, 3)",
+            &f32_bytes(&[0.0, 0.0, 0.0, 1.0, 1.0, 1.0]),
+        );
+        let temperature = npy("<f4", false, "(2,)", &f32_bytes(&[300.0, 310.0]));
+        let labels = npy(
+            "<i8",
+            false,
+            "(2,)",
+            &[7i64, 9]
+                .iter()
+                .flat_map(|v| v.to_le_bytes())
+                .collect::<Vec<_>>(),
+        );
+        let grid = npy("<f4", false, "(2, 2)", &f32_bytes(&[0.0; 4]));
+
+        for method in [
+            zip::CompressionMethod::Stored,
+            zip::CompressionMethod::Deflated,
+        ] {
+            let bytes = npz(
+                &[
+                    ("positions", positions.clone()),
+                    ("temperature", temperature.clone()),
+                    ("labels", labels.clone()),
+                    ("grid", grid.clone()),
+                ],
+                method,
+            );
+            let cloud = NpyLoader::new()
+                .load_npz_from_reader(Cursor::new(bytes))
+                .unwrap();
+
+            assert_eq!(cloud.len(), 2);
+            assert_eq!(cloud.metadata("temperature").unwrap(), &[300.0, 310.0]);
+            assert_eq!(cloud.metadata("labels").unwrap(), &[7.0, 9.0]);
+            // Arrays that are not (N,) are skipped in auto mode
+            assert!(cloud.metadata("grid").is_none());
+        }
+    }
+
+    #[test]
+    fn test_npz_explicit_keys_are_strict() {
+        let positions = npy("<f4", false, "(2, 3)", &f32_bytes(&[0.0; 6]));
+        let short = npy("<f4", false, "(1,)", &f32_bytes(&[1.0]));
+        let bytes = npz(
+            &[("xyz", positions), ("mass", short)],
+            zip::CompressionMethod::Stored,
+        );
+
+        let err = NpyLoader::new()
+            .with_positions_key("xyz")
+            .with_metadata_keys(["mass"])
+            .load_npz_from_reader(Cursor::new(bytes.clone()))
+            .unwrap_err();
+        assert!(matches!(err, LoadError::ShapeMismatch { ref name, .. } if name == "mass"));
+
+        let err = NpyLoader::new()
+            .load_npz_from_reader(Cursor::new(bytes))
+            .unwrap_err();
+        assert!(matches!(err, LoadError::MissingColumn(ref key) if key == "positions"));
+    }
+
+    #[test]
+    fn test_header_value() {
+        let header = "{'descr': '<f8', 'fortran_order': False, 'shape': (10, 3), }";
+        assert_eq!(header_value(header, "descr"), Some("'<f8'"));
+        assert_eq!(header_value(header, "fortran_order"), Some("False"));
+        assert_eq!(header_value(header, "shape"), Some("(10, 3)"));
+        assert_eq!(header_value(header, "missing"), None);
+    }
+}
@@ -0,0 +1,682 @@
+use super::{LoadError, MAX_PREALLOCATED_POINTS};
+use crate::data::{ArrowDataset, CategoricalBuilder, Dataset, FieldInfo, PointCloud};
+use arrow::array::{Array, ArrayRef, AsArray, Float32Array, RecordBatch};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Float32Type, Schema};
+use glam::Vec3;
+use parquet::arrow::arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder};
+use parquet::arrow::ProjectionMask;
+use parquet::file::reader::ChunkReader;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+use tracing::{debug, info};
+
+/// Loader that reads Parquet files into a [`PointCloud`] or [`ArrowDataset`]
+///
+/// Only the selected columns are decoded, and the file is streamed one
+/// record batch at a time so peak memory stays close to the size of the
+/// output rather than twice the file. String columns (plain or
+/// dictionary-encoded) become categorical fields of the point cloud.
+///
+/// Field metadata in the Arrow schema with CF-style keys (`units` or
+/// `unit`, `long_name`, `description`, and `valid_range` or
+/// `valid_min`/`valid_max`) becomes the fields' [`FieldInfo`].
+///
+/// # Examples
+/// ```no_run
+/// use viz_core::io::ParquetLoader;
+///
+/// let cloud = ParquetLoader::new()
+///     .with_position_columns("pos_x", "pos_y", "pos_z")
+///     .with_metadata_columns(["density", "temperature"])
+///     .load("snapshot_0042.parquet")
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParquetLoader {
+    /// Column names used for the x, y and z coordinates
+    position_columns: [String; 3],
+
+    /// Explicit metadata columns (None = every other numeric column)
+    metadata_columns: Option<Vec<String>>,
+
+    /// Maximum number of rows decoded per batch
+    batch_size: usize,
+}
+
+/// Columns selected from a Parquet schema
+struct ColumnSelection {
+    /// Position column names in x, y, z order
+    positions: [String; 3],
+    /// Metadata column names
+    metadata: Vec<String>,
+    /// String column names, loaded as categorical fields
+    categorical: Vec<String>,
+    /// Attributes of the metadata and categorical columns that have any
+    info: Vec<(String, FieldInfo)>,
+}
+
+impl ParquetLoader {
+    /// Create a loader that reads positions from columns named `x`, `y` and `z`
+    pub fn new() -> Self {
+        Self {
+            position_columns: ["x".to_string(), "y".to_string(), "z".to_string()],
+            metadata_columns: None,
+            batch_size: 64 * 1024,
+        }
+    }
+
+    /// Set the columns used for the x, y and z coordinates
+    pub fn with_position_columns(
+        mut self,
+        x: impl Into<String>,
+        y: impl Into<String>,
+        z: impl Into<String>,
+    ) -> Self {
+        self.position_columns = [x.into(), y.into(), z.into()];
+        self
+    }
+
+    /// Restrict metadata to the given numeric columns
+    ///
+    /// By default every other numeric column in the schema is loaded as
+    /// metadata, and every string column as a categorical field. Setting
+    /// explicit columns turns off the categorical fields.
+    pub fn with_metadata_columns<I, S>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.metadata_columns = Some(columns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set the maximum number of rows decoded at once (default: 65536)
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Load a Parquet file into a point cloud
+    ///
+    /// The dataset is named after the file stem.
+    pub fn load(&self, path: impl AsRef<Path>) -> Result<PointCloud, LoadError> {
+        let path = path.as_ref();
+        let cloud = self.load_from_reader(File::open(path)?)?;
+
+        info!(
+            "Loaded {} points from {}",
+            cloud.positions().len(),
+            path.display()
+        );
+
+        Ok(cloud.with_name(file_stem(path)))
+    }
+
+    /// Load Parquet data from any chunk reader into a point cloud
+    ///
+    /// Float32 columns are copied straight out of the Arrow buffers; other
+    /// numeric types are cast one batch at a time. Null metadata values are
+    /// stored as NaN.
+    pub fn load_from_reader<R: ChunkReader + 'static>(
+        &self,
+        reader: R,
+    ) -> Result<PointCloud, LoadError> {
+        self.load_with_progress(reader, |_, _| Ok(()))
+    }
+
+    /// Load into a point cloud, calling `on_batch(rows_done, total_rows)`
+    /// after every record batch
+    ///
+    /// An error returned from the callback aborts the load.
+    pub(crate) fn load_with_progress<R, F>(
+        &self,
+        reader: R,
+        mut on_batch: F,
+    ) -> Result<PointCloud, LoadError>
+    where
+        R: ChunkReader + 'static,
+        F: FnMut(u64, u64) -> Result<(), LoadError>,
+    {
+        let (batches, selection, num_rows) = self.open(reader)?;
+
+        // The row count comes from the file footer, so don't trust it blindly
+        let capacity = num_rows.min(MAX_PREALLOCATED_POINTS);
+        let mut positions = Vec::with_capacity(capacity);
+        let mut metadata: Vec<Vec<f32>> = selection
+            .metadata
+            .iter()
+            .map(|_| Vec::with_capacity(capacity))
+            .collect();
+        let mut categories: Vec<CategoricalBuilder> = selection
+            .categorical
+            .iter()
+            .map(|_| CategoricalBuilder::new())
+            .collect();
+
+        let mut row_offset = 0u64;
+        for batch in batches {
+            let batch = batch?;
+            let [xs, ys, zs] = position_arrays(&batch, &selection.positions, row_offset)?;
+
+            positions.extend(
+                xs.values()
+                    .iter()
+                    .zip(ys.values().iter())
+                    .zip(zs.values().iter())
+                    .map(|((&x, &y), &z)| Vec3::new(x, y, z)),
+            );
+
+            for (values, name) in metadata.iter_mut().zip(&selection.metadata) {
+                let array = float_column(&batch, name)?;
+                if array.null_count() == 0 {
+                    values.extend_from_slice(array.values());
+                } else {
+                    values.extend(array.iter().map(|v| v.unwrap_or(f32::NAN)));
+                }
+            }
+
+            for (labels, name) in categories.iter_mut().zip(&selection.categorical) {
+                let array = string_column(&batch, name)?;
+                for label in array.as_string::<i32>() {
+                    labels.push(label);
+                }
+            }
+
+            row_offset += batch.num_rows() as u64;
+            debug!("Decoded {} of {} rows", row_offset, num_rows);
+            on_batch(row_offset, num_rows as u64)?;
+        }
+
+        let mut cloud = PointCloud::new(positions);
+        for (values, name) in metadata.into_iter().zip(selection.metadata) {
+            cloud = cloud.with_metadata(name, values);
+        }
+        for (labels, name) in categories.into_iter().zip(selection.categorical) {
+            cloud = cloud.with_categorical(name, labels.finish());
+        }
+        for (name, info) in selection.info {
+            cloud = cloud.with_field_info(name, info);
+        }
+
+        Ok(cloud)
+    }
+
+    /// Load a Parquet file into an Arrow-backed dataset
+    ///
+    /// The dataset is named after the file stem.
+    pub fn load_arrow(&self, path: impl AsRef<Path>) -> Result<ArrowDataset, LoadError> {
+        let path = path.as_ref();
+        let dataset = self.load_arrow_from_reader(File::open(path)?)?;
+
+        info!("Loaded {} rows from {}", dataset.len(), path.display());
+
+        Ok(dataset.with_name(file_stem(path)))
+    }
+
+    /// Load Parquet data from any chunk reader into an Arrow-backed dataset
+    ///
+    /// Selected columns are kept as Float32 Arrow arrays, so column access on
+    /// the returned dataset borrows the decoded buffers without copying.
+    pub fn load_arrow_from_reader<R: ChunkReader + 'static>(
+        &self,
+        reader: R,
+    ) -> Result<ArrowDataset, LoadError> {
+        let (batches, selection, _) = self.open(reader)?;
+
+        let mut columns: Vec<String> = selection.positions.to_vec();
+        columns.extend(selection.metadata.iter().cloned());
+
+        let mut row_offset = 0u64;
+        let mut chunks = Vec::new();
+        for batch in batches {
+            let batch = batch?;
+            let arrays = columns
+                .iter()
+                .map(|name| float_column(&batch, name))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for (array, name) in arrays.iter().zip(&selection.positions) {
+                reject_nulls(array, name, row_offset)?;
+            }
+
+            row_offset += batch.num_rows() as u64;
+            chunks.push(arrays);
+        }
+
+        Ok(ArrowDataset::from_columns(columns, chunks))
+    }
+
+    /// Load the numeric columns of a Parquet file without placing any points
+    ///
+    /// For tables with no coordinate columns, e.g. to pass to
+    /// [`PointCloud::from_pca`].
+    pub fn load_columns(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<Vec<(String, Vec<f32>)>, LoadError> {
+        self.load_columns_from_reader(File::open(path)?)
+    }
+
+    /// Load the numeric columns from any chunk reader, in schema order
+    ///
+    /// The position columns are ignored. Every numeric column is returned
+    /// (or only the [metadata columns](Self::with_metadata_columns), if
+    /// set); string columns are skipped. Null values are stored as NaN.
+    pub fn load_columns_from_reader<R: ChunkReader + 'static>(
+        &self,
+        reader: R,
+    ) -> Result<Vec<(String, Vec<f32>)>, LoadError> {
+        let (batches, names, num_rows) = self.open_with(reader, |schema| {
+            let names: Vec<String> = match &self.metadata_columns {
+                Some(names) => {
+                    for name in names {
+                        let field = schema
+                            .field_with_name(name)
+                            .map_err(|_| LoadError::MissingColumn(name.clone()))?;
+                        check_numeric(name, field.data_type())?;
+                    }
+                    names.clone()
+                }
+                None => schema
+                    .fields()
+                    .iter()
+                    .filter(|field| field.data_type().is_numeric())
+                    .map(|field| field.name().clone())
+                    .collect(),
+            };
+            Ok((names.clone(), names))
+        })?;
+
+        let capacity = num_rows.min(MAX_PREALLOCATED_POINTS);
+        let mut columns: Vec<Vec<f32>> =
+            names.iter().map(|_| Vec::with_capacity(capacity)).collect();
+        for batch in batches {
+            let batch = batch?;
+            for (values, name) in columns.iter_mut().zip(&names) {
+                let array = float_column(&batch, name)?;
+                values.extend(array.iter().map(|v| v.unwrap_or(f32::NAN)));
+            }
+        }
+
+        Ok(names.into_iter().zip(columns).collect())
+    }
+
+    /// Open a reader, resolve the column selection and start streaming batches
+    fn open<R: ChunkReader + 'static>(
+        &self,
+        reader: R,
+    ) -> Result<(ParquetRecordBatchReader, ColumnSelection, usize), LoadError> {
+        self.open_with(reader, |schema| {
+            let selection = self.select_columns(schema)?;
+            let columns = selection
+                .positions
+                .iter()
+                .chain(&selection.metadata)
+                .chain(&selection.categorical)
+                .cloned()
+                .collect();
+            Ok((selection, columns))
+        })
+    }
+
+    /// Open a reader and stream only the columns picked by `select`
+    ///
+    /// `select` returns its own selection plus the names of the columns to
+    /// decode. The row count is the one claimed by the file footer.
+    fn open_with<R, T, F>(
+        &self,
+        reader: R,
+        select: F,
+    ) -> Result<(ParquetRecordBatchReader, T, usize), LoadError>
+    where
+        R: ChunkReader + 'static,
+        F: FnOnce(&Schema) -> Result<(T, Vec<String>), LoadError>,
+    {
+        let builder = ParquetRecordBatchReaderBuilder::try_new(reader)?;
+        let schema = builder.schema().clone();
+        let num_rows = builder.metadata().file_metadata().num_rows().max(0) as usize;
+
+        let (selection, columns) = select(&schema)?;
+        let indices: Vec<usize> = columns
+            .iter()
+            .map(|name| schema.index_of(name))
+            .collect::<Result<_, _>>()?;
+
+        let mask = ProjectionMask::roots(builder.parquet_schema(), indices);
+        let batches = builder
+            .with_projection(mask)
+            .with_batch_size(self.batch_size)
+            .build()?;
+
+        Ok((batches, selection, num_rows))
+    }
+
+    /// Pick position and metadata columns from the file schema
+    fn select_columns(&self, schema: &Schema) -> Result<ColumnSelection, LoadError> {
+        for name in &self.position_columns {
+            let field = schema
+                .field_with_name(name)
+                .map_err(|_| LoadError::MissingColumn(name.clone()))?;
+            check_numeric(name, field.data_type())?;
+        }
+
+        let (metadata, categorical) = match &self.metadata_columns {
+            Some(names) => {
+                for name in names {
+                    let field = schema
+                        .field_with_name(name)
+                        .map_err(|_| LoadError::MissingColumn(name.clone()))?;
+                    check_numeric(name, field.data_type())?;
+                }
+                (names.clone(), Vec::new())
+            }
+            None => {
+                let other = || {
+                    schema
+                        .fields()
+                        .iter()
+                        .filter(|field| !self.position_columns.contains(field.name()))
+                };
+                (
+                    other()
+                        .filter(|field| field.data_type().is_numeric())
+                        .map(|field| field.name().clone())
+                        .collect(),
+                    other()
+                        .filter(|field| is_string(field.data_type()))
+                        .map(|field| field.name().clone())
+                        .collect(),
+                )
+            }
+        };
+
+        let info = metadata
+            .iter()
+            .chain(&categorical)
+            .filter_map(|name| {
+                let info = field_info(schema.field_with_name(name).ok()?.metadata());
+                (!info.is_empty()).then(|| (name.clone(), info))
+            })
+            .collect();
+
+        Ok(ColumnSelection {
+            positions: self.position_columns.clone(),
+            metadata,
+            categorical,
+            info,
+        })
+    }
+}
+
+impl Default for ParquetLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Field attributes from CF-style Arrow field metadata
+fn field_info(metadata: &HashMap<String, String>) -> FieldInfo {
+    let mut info = FieldInfo::new();
+    if let Some(unit) = metadata.get("units").or_else(|| metadata.get("unit")) {
+        info = info.with_unit(unit.trim());
+    }
+    if let Some(long_name) = metadata.get("long_name") {
+        info = info.with_long_name(long_name.trim());
+    }
+    if let Some(description) = metadata.get("description") {
+        info = info.with_description(description.trim());
+    }
+
+    // `valid_range` is "min max" or "min,max", optionally in brackets
+    let mut range = metadata.get("valid_range").map_or(Vec::new(), |range| {
+        range
+            .split(|c: char| c == ',' || c.is_whitespace() || c == '[' || c == ']')
+            .filter_map(|part| part.parse::<f32>().ok())
+            .collect()
+    });
+    range.resize(2, f32::NAN);
+    let bound = |key: &str, fallback: f32| {
+        metadata
+            .get(key)
+            .and_then(|value| value.trim().parse::<f32>().ok())
+            .unwrap_or(fallback)
+    };
+    let min = bound("valid_min", range[0]);
+    let max = bound("valid_max", range[1]);
+    if !min.is_nan() || !max.is_nan() {
+        let min = if min.is_nan() { f32::NEG_INFINITY } else { min };
+        let max = if max.is_nan() { f32::INFINITY } else { max };
+        if min <= max {
+            info = info.with_valid_range(min, max);
+        }
+    }
+    info
+}
+
+/// Ensure a column can be converted to f32
+fn check_numeric(name: &str, data_type: &DataType) -> Result<(), LoadError> {
+    if data_type.is_numeric() {
+        Ok(())
+    } else {
+        Err(LoadError::UnsupportedType {
+            column: name.to_string(),
+            data_type: data_type.to_string(),
+        })
+    }
+}
+
+/// Check for plain or dictionary-encoded string columns
+fn is_string(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Utf8 | DataType::LargeUtf8 => true,
+        DataType::Dictionary(_, values) => is_string(values),
+        _ => false,
+    }
+}
+
+/// Get a string column from a batch as a Utf8 array, casting if needed
+fn string_column(batch: &RecordBatch, name: &str) -> Result<ArrayRef, LoadError> {
+    let column: &ArrayRef = batch
+        .column_by_name(name)
+        .ok_or_else(|| LoadError::MissingColumn(name.to_string()))?;
+
+    if column.data_type() == &DataType::Utf8 {
+        Ok(column.clone())
+    } else {
+        Ok(cast(column, &DataType::Utf8)?)
+    }
+}
+
+/// Get a column from a batch as a Float32 array, casting if needed
+fn float_column(batch: &RecordBatch, name: &str) -> Result<Float32Array, LoadError> {
+    let column: &ArrayRef = batch
+        .column_by_name(name)
+        .ok_or_else(|| LoadError::MissingColumn(name.to_string()))?;
+
+    let array = if column.data_type() == &DataType::Float32 {
+        column.clone()
+    } else {
+        cast(column, &DataType::Float32)?
+    };
+
+    Ok(array.as_primitive::<Float32Type>().clone())
+}
+
+/// Get the three position columns, rejecting null coordinates
+fn position_arrays(
+    batch: &RecordBatch,
+    names: &[String; 3],
+    row_offset: u64,
+) -> Result<[Float32Array; 3], LoadError> {
+    let column = |i: usize| -> Result<Float32Array, LoadError> {
+        let array = float_column(batch, &names[i])?;
+        reject_nulls(&array, &names[i], row_offset)?;
+        Ok(array)
+    };
+
+    Ok([column(0)?, column(1)?, column(2)?])
+}
+
+/// Report the first null value in a column as an invalid value
+fn reject_nulls(array: &Float32Array, name: &str, row_offset: u64) -> Result<(), LoadError> {
+    match (0..array.len()).find(|&i| array.is_null(i)) {
+        Some(row) => Err(LoadError::InvalidValue {
+            row: row_offset + row as u64 + 1,
+            column: name.to_string(),
+            value: "null".to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Dataset name derived from a file path
+pub(crate) fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Float64Array, Int32Array, StringArray};
+    use arrow::datatypes::Field;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use std::sync::Arc;
+
+    /// Write a small Parquet file with two row groups to a temp path
+    fn write_test_file(name: &str) -> std::path::PathBuf {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("x", DataType::Float32, false),
+            Field::new("y", DataType::Float64, false),
+            Field::new("z", DataType::Int32, false),
+            Field::new("temperature", DataType::Float32, true).with_metadata(HashMap::from([
+                ("units".to_string(), "K".to_string()),
+                ("long_name".to_string(), "Temperature".to_string()),
+                ("valid_range".to_string(), "[0, 1000]".to_string()),
+            ])),
+            Field::new("label", DataType::Utf8, true),
+        ]));
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Float32Array::from(vec![0.0, 1.0, 2.0, 3.0])),
+                Arc::new(Float64Array::from(vec![0.5, 1.5, 2.5, 3.5])),
+                Arc::new(Int32Array::from(vec![10, 20, 30, 40])),
+                Arc::new(Float32Array::from(vec![
+                    Some(300.0),
+                    None,
+                    Some(310.0),
+                    Some(320.0),
+                ])),
+                Arc::new(StringArray::from(vec![
+                    Some("oak"),
+                    None,
+                    Some("pine"),
+                    Some("oak"),
+                ])),
+            ],
+        )
+        .unwrap();
+
+        let path =
+            std::env::temp_dir().join(format!("viz_core_{}_{}.parquet", name, std::process::id()));
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(File::create(&path).unwrap(), schema, Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        path
+    }
+
+    #[test]
+    fn test_load_point_cloud() {
+        let path = write_test_file("load");
+        let cloud = ParquetLoader::new().with_batch_size(1).load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(cloud.len(), 4);
+        assert_eq!(cloud.positions()[2], Vec3::new(2.0, 2.5, 30.0));
+
+        let temperature = cloud.metadata("temperature").unwrap();
+        assert_eq!(temperature[0], 300.0);
+        assert!(temperature[1].is_nan());
+
+        // Field metadata becomes the field's attributes
+        assert_eq!(cloud.field_label("temperature"), "Temperature [K]");
+        let info = cloud.field_info("temperature").unwrap();
+        assert_eq!(info.valid_range(), Some((0.0, 1000.0)));
+        assert!(cloud.field_info("label").is_none());
+
+        // String columns are loaded as categorical fields
+        assert!(cloud.metadata("label").is_none());
+        let label = cloud.categorical("label").unwrap();
+        assert_eq!(label.labels(), &["oak", "pine"]);
+        assert_eq!(label.label(1), None);
+        assert_eq!(label.codes()[3], 0);
+        assert!(cloud.name().starts_with("viz_core_load"));
+    }
+
+    #[test]
+    fn test_missing_column() {
+        let path = write_test_file("missing");
+        let err = ParquetLoader::new()
+            .with_position_columns("x", "y", "w")
+            .load(&path)
+            .unwrap_err();
+        std::fs::remove_file(&path).ok();
+
+        assert!(matches!(err, LoadError::MissingColumn(ref name) if name == "w"));
+    }
+
+    #[test]
+    fn test_non_numeric_column_rejected() {
+        let path = write_test_file("non_numeric");
+        let err = ParquetLoader::new()
+            .with_metadata_columns(["label"])
+            .load(&path)
+            .unwrap_err();
+        std::fs::remove_file(&path).ok();
+
+        assert!(matches!(err, LoadError::UnsupportedType { ref column, .. } if column == "label"));
+    }
+
+    #[test]
+    fn test_load_columns() {
+        let path = write_test_file("columns");
+        let columns = ParquetLoader::new().load_columns(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let names: Vec<&str> = columns.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["x", "y", "z", "temperature"]);
+        assert_eq!(columns[2].1, vec![10.0, 20.0, 30.0, 40.0]);
+        assert!(columns[3].1[1].is_nan());
+    }
+
+    #[test]
+    fn test_load_arrow_dataset() {
+        let path = write_test_file("arrow");
+        let dataset = ParquetLoader::new()
+            .with_metadata_columns(["temperature"])
+            .load_arrow(&path)
+            .unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(dataset.len(), 4);
+        assert_eq!(dataset.bounds().max, Vec3::new(3.0, 3.5, 40.0));
+
+        let chunks = dataset.column_chunks("x").unwrap();
+        let xs: Vec<f32> = chunks.iter().flat_map(|c| c.iter().copied()).collect();
+        assert_eq!(xs, vec![0.0, 1.0, 2.0, 3.0]);
+
+        let cloud = dataset.to_point_cloud();
+        assert_eq!(cloud.positions()[3], Vec3::new(3.0, 3.5, 40.0));
+        assert!(cloud.metadata("temperature").unwrap()[1].is_nan());
+    }
+}
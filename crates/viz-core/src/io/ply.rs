@@ -0,0 +1,904 @@
+use super::{LoadError, MAX_PREALLOCATED_POINTS};
+use crate::data::{CategoricalField, PointCloud};
+use glam::{DVec3, Vec3, Vec4};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use tracing::info;
+
+/// Encoding of the data section of a PLY file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlyFormat {
+    /// Whitespace-separated text
+    Ascii,
+    /// Packed little-endian binary
+    BinaryLittleEndian,
+    /// Packed big-endian binary
+    BinaryBigEndian,
+}
+
+/// Scalar property type as declared in a PLY header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl ScalarType {
+    /// Parse a type name (both `uchar` and `uint8` spellings are accepted)
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" | "int8" => Self::I8,
+            "uchar" | "uint8" => Self::U8,
+            "short" | "int16" => Self::I16,
+            "ushort" | "uint16" => Self::U16,
+            "int" | "int32" => Self::I32,
+            "uint" | "uint32" => Self::U32,
+            "float" | "float32" => Self::F32,
+            "double" | "float64" => Self::F64,
+            _ => return None,
+        })
+    }
+
+    /// Size in bytes of a binary value
+    fn size(self) -> usize {
+        match self {
+            Self::I8 | Self::U8 => 1,
+            Self::I16 | Self::U16 => 2,
+            Self::I32 | Self::U32 | Self::F32 => 4,
+            Self::F64 => 8,
+        }
+    }
+
+    /// Value that maps to full intensity when used as a color channel
+    fn color_scale(self) -> f32 {
+        match self {
+            Self::U8 => 255.0,
+            Self::U16 => 65535.0,
+            _ => 1.0,
+        }
+    }
+
+    /// Decode a binary value
+    fn decode(self, bytes: &[u8], format: PlyFormat) -> f64 {
+        macro_rules! decode {
+            ($t:ty) => {{
+                let array = bytes.try_into().expect("scalar size matches type");
+                match format {
+                    PlyFormat::BinaryBigEndian => <$t>::from_be_bytes(array) as f64,
+                    _ => <$t>::from_le_bytes(array) as f64,
+                }
+            }};
+        }
+
+        match self {
+            Self::I8 => decode!(i8),
+            Self::U8 => decode!(u8),
+            Self::I16 => decode!(i16),
+            Self::U16 => decode!(u16),
+            Self::I32 => decode!(i32),
+            Self::U32 => decode!(u32),
+            Self::F32 => decode!(f32),
+            Self::F64 => decode!(f64),
+        }
+    }
+}
+
+/// Property declared on a PLY element
+#[derive(Debug, Clone)]
+enum Property {
+    Scalar { name: String, ty: ScalarType },
+    List { count: ScalarType, item: ScalarType },
+}
+
+/// Element declared in a PLY header
+#[derive(Debug, Clone)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+impl Element {
+    /// Index of a scalar property among the element's scalar properties
+    fn scalar_index(&self, name: &str) -> Option<usize> {
+        self.scalars().position(|(n, _)| n == name)
+    }
+
+    /// Iterate over scalar properties as (name, type)
+    fn scalars(&self) -> impl Iterator<Item = (&str, ScalarType)> {
+        self.properties.iter().filter_map(|p| match p {
+            Property::Scalar { name, ty } => Some((name.as_str(), *ty)),
+            Property::List { .. } => None,
+        })
+    }
+}
+
+/// Parsed PLY header
+#[derive(Debug)]
+struct Header {
+    format: PlyFormat,
+    elements: Vec<Element>,
+    /// World-space origin from an `origin` comment
+    origin: Option<DVec3>,
+}
+
+/// Loader that reads PLY point clouds
+///
+/// Vertex `x`/`y`/`z` become positions, `red`/`green`/`blue`/`alpha` become
+/// colors, `nx`/`ny`/`nz` become normals, `point_size` becomes the point
+/// sizes, and every other scalar vertex property becomes a metadata field.
+/// Other elements (faces, edges, ...) are skipped. A `comment origin x y z`
+/// header line, as written by [`PlyWriter`], sets the cloud's origin.
+///
+/// # Examples
+/// ```
+/// use viz_core::data::Dataset;
+/// use viz_core::io::PlyLoader;
+///
+/// let ply = "ply\nformat ascii 1.0\nelement vertex 2\n\
+///            property float x\nproperty float y\nproperty float z\n\
+///            property float intensity\nend_header\n\
+///            0 0 0 0.5\n1 2 3 0.75\n";
+///
+/// let cloud = PlyLoader::new().load_from_reader(ply.as_bytes()).unwrap();
+/// assert_eq!(cloud.len(), 2);
+/// assert_eq!(cloud.metadata("intensity").unwrap(), &[0.5, 0.75]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PlyLoader;
+
+impl PlyLoader {
+    /// Create a new PLY loader
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Load a point cloud from a PLY file
+    ///
+    /// The dataset is named after the file stem.
+    pub fn load(&self, path: impl AsRef<Path>) -> Result<PointCloud, LoadError> {
+        let path = path.as_ref();
+        let cloud = self.load_from_reader(File::open(path)?)?;
+
+        info!(
+            "Loaded {} points from {}",
+            cloud.positions().len(),
+            path.display()
+        );
+
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+
+        Ok(cloud.with_name(name))
+    }
+
+    /// Load a point cloud from any PLY reader
+    pub fn load_from_reader<R: Read>(&self, reader: R) -> Result<PointCloud, LoadError> {
+        let mut reader = BufReader::new(reader);
+        let header = read_header(&mut reader)?;
+        let mut body = Body::new(reader, header.format);
+
+        for element in &header.elements {
+            if element.name == "vertex" {
+                let cloud = read_vertices(&mut body, element)?;
+                return Ok(match header.origin {
+                    Some(origin) => cloud.with_origin(origin),
+                    None => cloud,
+                });
+            }
+            body.skip_element(element)?;
+        }
+
+        Err(malformed("no vertex element"))
+    }
+}
+
+/// Writer that saves point clouds as PLY
+///
+/// Positions are written as float `x`/`y`/`z`, colors as uchar
+/// `red`/`green`/`blue`/`alpha`, sizes as float `point_size`, normals as
+/// float `nx`/`ny`/`nz`. Then come float properties for the metadata
+/// fields, the categorical fields as their codes (NaN when missing; the
+/// labels are not stored) and the vector fields as `<name>_x`/`_y`/`_z`,
+/// each group in sorted key order. Whitespace in field names is replaced by
+/// underscores, and a name that would clash with another property gets a
+/// `field_` prefix (and a number, if needed).
+/// Positions stay relative to the cloud's origin, which is kept in a
+/// `comment origin x y z` header line when it is not zero.
+#[derive(Debug, Clone)]
+pub struct PlyWriter {
+    format: PlyFormat,
+}
+
+/// Property names the loader reads as positions, colors, sizes or normals
+const RESERVED_PROPERTIES: [&str; 11] = [
+    "x",
+    "y",
+    "z",
+    "red",
+    "green",
+    "blue",
+    "alpha",
+    "point_size",
+    "nx",
+    "ny",
+    "nz",
+];
+
+/// Per-point values written as a float property after the fixed ones
+enum FieldColumn<'a> {
+    Metadata(&'a [f32]),
+    Codes(&'a [u32]),
+    Component(&'a [Vec3], usize),
+}
+
+impl FieldColumn<'_> {
+    fn value(&self, index: usize) -> f32 {
+        match self {
+            Self::Metadata(values) => values[index],
+            Self::Codes(codes) if codes[index] == CategoricalField::MISSING => f32::NAN,
+            Self::Codes(codes) => codes[index] as f32,
+            Self::Component(vectors, axis) => vectors[index][*axis],
+        }
+    }
+}
+
+impl PlyWriter {
+    /// Create a writer using the given encoding
+    pub fn new(format: PlyFormat) -> Self {
+        Self { format }
+    }
+
+    /// Write a point cloud to a PLY file
+    pub fn write(&self, cloud: &PointCloud, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(cloud, &mut writer)?;
+        writer.flush()
+    }
+
+    /// Write a point cloud to any writer
+    pub fn write_to<W: Write>(&self, cloud: &PointCloud, writer: &mut W) -> io::Result<()> {
+        let fields = field_columns(cloud);
+        let colors = cloud.colors();
+        let sizes = cloud.sizes();
+        let normals = cloud.normals();
+
+        let format = match self.format {
+            PlyFormat::Ascii => "ascii",
+            PlyFormat::BinaryLittleEndian => "binary_little_endian",
+            PlyFormat::BinaryBigEndian => "binary_big_endian",
+        };
+
+        writeln!(writer, "ply")?;
+        writeln!(writer, "format {} 1.0", format)?;
+        writeln!(writer, "comment written by scientific-visualizer")?;
+        let origin = cloud.origin();
+        if origin != DVec3::ZERO {
+            writeln!(
+                writer,
+                "comment origin {} {} {}",
+                origin.x, origin.y, origin.z
+            )?;
+        }
+        writeln!(writer, "element vertex {}", cloud.positions().len())?;
+        for axis in ["x", "y", "z"] {
+            writeln!(writer, "property float {}", axis)?;
+        }
+        if colors.is_some() {
+            for channel in ["red", "green", "blue", "alpha"] {
+                writeln!(writer, "property uchar {}", channel)?;
+            }
+        }
+        if sizes.is_some() {
+            writeln!(writer, "property float point_size")?;
+        }
+        if normals.is_some() {
+            for axis in ["nx", "ny", "nz"] {
+                writeln!(writer, "property float {}", axis)?;
+            }
+        }
+        for (name, _) in &fields {
+            writeln!(writer, "property float {}", name)?;
+        }
+        writeln!(writer, "end_header")?;
+
+        for (i, position) in cloud.positions().iter().enumerate() {
+            let color = colors.map(|c| {
+                (c[i].clamp(Vec4::ZERO, Vec4::ONE) * 255.0)
+                    .round()
+                    .to_array()
+                    .map(|v| v as u8)
+            });
+
+            match self.format {
+                PlyFormat::Ascii => {
+                    write!(writer, "{} {} {}", position.x, position.y, position.z)?;
+                    if let Some([r, g, b, a]) = color {
+                        write!(writer, " {} {} {} {}", r, g, b, a)?;
+                    }
+                    if let Some(sizes) = sizes {
+                        write!(writer, " {}", sizes[i])?;
+                    }
+                    if let Some(normals) = normals {
+                        let normal = normals[i];
+                        write!(writer, " {} {} {}", normal.x, normal.y, normal.z)?;
+                    }
+                    for (_, field) in &fields {
+                        write!(writer, " {}", field.value(i))?;
+                    }
+                    writeln!(writer)?;
+                }
+                PlyFormat::BinaryLittleEndian | PlyFormat::BinaryBigEndian => {
+                    let big_endian = self.format == PlyFormat::BinaryBigEndian;
+                    let floats = position.to_array().into_iter();
+                    let size = sizes.map(|sizes| sizes[i]);
+                    let normal = normals.map(|normals| normals[i].to_array());
+                    let fields = fields.iter().map(|(_, field)| field.value(i));
+
+                    let encode = |v: f32| {
+                        if big_endian {
+                            v.to_be_bytes()
+                        } else {
+                            v.to_le_bytes()
+                        }
+                    };
+
+                    for v in floats {
+                        writer.write_all(&encode(v))?;
+                    }
+                    if let Some(rgba) = color {
+                        writer.write_all(&rgba)?;
+                    }
+                    if let Some(size) = size {
+                        writer.write_all(&encode(size))?;
+                    }
+                    for v in normal.into_iter().flatten() {
+                        writer.write_all(&encode(v))?;
+                    }
+                    for v in fields {
+                        writer.write_all(&encode(v))?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for PlyWriter {
+    fn default() -> Self {
+        Self::new(PlyFormat::BinaryLittleEndian)
+    }
+}
+
+/// Metadata, categorical and vector fields of a cloud with unique property
+/// names
+fn field_columns(cloud: &PointCloud) -> Vec<(String, FieldColumn<'_>)> {
+    let mut metadata_keys = cloud.metadata_keys();
+    metadata_keys.sort();
+    let mut categorical_keys = cloud.categorical_keys();
+    categorical_keys.sort();
+    let mut vector_keys = cloud.vector_keys();
+    vector_keys.sort();
+
+    let mut columns = Vec::new();
+    for key in metadata_keys {
+        if let Some(values) = cloud.metadata(key) {
+            columns.push((key.clone(), FieldColumn::Metadata(values)));
+        }
+    }
+    for key in categorical_keys {
+        if let Some(field) = cloud.categorical(key) {
+            columns.push((key.clone(), FieldColumn::Codes(field.codes())));
+        }
+    }
+    for key in vector_keys {
+        if let Some(vectors) = cloud.vectors(key) {
+            for (axis, suffix) in ["x", "y", "z"].into_iter().enumerate() {
+                let name = format!("{}_{}", key, suffix);
+                columns.push((name, FieldColumn::Component(vectors, axis)));
+            }
+        }
+    }
+
+    let mut used: HashSet<String> = RESERVED_PROPERTIES
+        .iter()
+        .map(|&name| name.into())
+        .collect();
+    for (name, _) in &mut columns {
+        let base = name.replace(char::is_whitespace, "_");
+        let mut unique = base.clone();
+        if used.contains(&unique) || unique.is_empty() {
+            unique = format!("field_{}", base);
+        }
+        let mut n = 2;
+        while used.contains(&unique) {
+            unique = format!("field_{}_{}", base, n);
+            n += 1;
+        }
+        used.insert(unique.clone());
+        *name = unique;
+    }
+    columns
+}
+
+/// Parse the PLY header up to and including `end_header`
+fn read_header<R: BufRead>(reader: &mut R) -> Result<Header, LoadError> {
+    let mut line = String::new();
+    let mut next_line = |line: &mut String| -> Result<(), LoadError> {
+        line.clear();
+        if reader.read_line(line)? == 0 {
+            return Err(malformed("unexpected end of header"));
+        }
+        Ok(())
+    };
+
+    next_line(&mut line)?;
+    if line.trim() != "ply" {
+        return Err(malformed("missing 'ply' magic number"));
+    }
+
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    let mut origin = None;
+
+    loop {
+        next_line(&mut line)?;
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        match tokens.as_slice() {
+            ["end_header"] => break,
+            ["comment", "origin", x, y, z] => {
+                let axes = [x, y, z].map(|axis| axis.parse::<f64>());
+                match axes {
+                    [Ok(x), Ok(y), Ok(z)] => origin = Some(DVec3::new(x, y, z)),
+                    _ => return Err(malformed(format!("invalid origin '{}'", line.trim()))),
+                }
+            }
+            ["comment", ..] | ["obj_info", ..] | [] => {}
+            ["format", kind, _version] => {
+                format = Some(match *kind {
+                    "ascii" => PlyFormat::Ascii,
+                    "binary_little_endian" => PlyFormat::BinaryLittleEndian,
+                    "binary_big_endian" => PlyFormat::BinaryBigEndian,
+                    other => return Err(malformed(format!("unknown format '{}'", other))),
+                });
+            }
+            ["element", name, count] => {
+                let count = count
+                    .parse()
+                    .map_err(|_| malformed(format!("invalid element count '{}'", count)))?;
+                elements.push(Element {
+                    name: name.to_string(),
+                    count,
+                    properties: Vec::new(),
+                });
+            }
+            ["property", "list", count, item, _name] => {
+                let property = Property::List {
+                    count: scalar_type(count)?,
+                    item: scalar_type(item)?,
+                };
+                current_element(&mut elements)?.properties.push(property);
+            }
+            ["property", ty, name] => {
+                let property = Property::Scalar {
+                    name: name.to_string(),
+                    ty: scalar_type(ty)?,
+                };
+                current_element(&mut elements)?.properties.push(property);
+            }
+            _ => return Err(malformed(format!("invalid header line '{}'", line.trim()))),
+        }
+    }
+
+    let format = format.ok_or_else(|| malformed("missing format line"))?;
+    Ok(Header {
+        format,
+        elements,
+        origin,
+    })
+}
+
+fn scalar_type(name: &str) -> Result<ScalarType, LoadError> {
+    ScalarType::parse(name).ok_or_else(|| malformed(format!("unknown property type '{}'", name)))
+}
+
+fn current_element(elements: &mut [Element]) -> Result<&mut Element, LoadError> {
+    elements
+        .last_mut()
+        .ok_or_else(|| malformed("property declared before any element"))
+}
+
+/// Reader over the data section of a PLY file
+struct Body<R> {
+    reader: R,
+    format: PlyFormat,
+    /// Tokens of the current ASCII line
+    tokens: Vec<String>,
+    /// Position of the next unread token
+    cursor: usize,
+    /// Scratch buffer for binary values
+    buffer: [u8; 8],
+}
+
+impl<R: BufRead> Body<R> {
+    fn new(reader: R, format: PlyFormat) -> Self {
+        Self {
+            reader,
+            format,
+            tokens: Vec::new(),
+            cursor: 0,
+            buffer: [0; 8],
+        }
+    }
+
+    /// Read the next value, reporting `name` and `row` on parse errors
+    fn read_value(&mut self, ty: ScalarType, name: &str, row: usize) -> Result<f64, LoadError> {
+        match self.format {
+            PlyFormat::Ascii => {
+                while self.cursor >= self.tokens.len() {
+                    let mut line = String::new();
+                    if self.reader.read_line(&mut line)? == 0 {
+                        return Err(malformed("unexpected end of data"));
+                    }
+                    self.tokens = line.split_whitespace().map(str::to_string).collect();
+                    self.cursor = 0;
+                }
+
+                let token = &self.tokens[self.cursor];
+                self.cursor += 1;
+                token.parse().map_err(|_| LoadError::InvalidValue {
+                    row: row as u64 + 1,
+                    column: name.to_string(),
+                    value: token.clone(),
+                })
+            }
+            PlyFormat::BinaryLittleEndian | PlyFormat::BinaryBigEndian => {
+                let bytes = &mut self.buffer[..ty.size()];
+                self.reader.read_exact(bytes).map_err(|e| match e.kind() {
+                    io::ErrorKind::UnexpectedEof => malformed("unexpected end of data"),
+                    _ => LoadError::Io(e),
+                })?;
+                Ok(ty.decode(bytes, self.format))
+            }
+        }
+    }
+
+    /// Read one element row, storing scalar values and discarding lists
+    fn read_row(
+        &mut self,
+        element: &Element,
+        row: usize,
+        values: &mut Vec<f64>,
+    ) -> Result<(), LoadError> {
+        values.clear();
+        for property in &element.properties {
+            match property {
+                Property::Scalar { name, ty } => values.push(self.read_value(*ty, name, row)?),
+                Property::List { count, item } => {
+                    let len = self.read_value(*count, "list length", row)? as usize;
+                    for _ in 0..len {
+                        self.read_value(*item, "list item", row)?;
+                    }
+                }
+            }
+        }
+
+        // ASCII rows end at the line break
+        self.cursor = self.tokens.len();
+        Ok(())
+    }
+
+    fn skip_element(&mut self, element: &Element) -> Result<(), LoadError> {
+        let mut values = Vec::new();
+        for row in 0..element.count {
+            self.read_row(element, row, &mut values)?;
+        }
+        Ok(())
+    }
+}
+
+/// Read the vertex element into a point cloud
+fn read_vertices<R: BufRead>(
+    body: &mut Body<R>,
+    element: &Element,
+) -> Result<PointCloud, LoadError> {
+    let index = |name: &str| {
+        element
+            .scalar_index(name)
+            .ok_or_else(|| LoadError::MissingColumn(name.to_string()))
+    };
+    let (xi, yi, zi) = (index("x")?, index("y")?, index("z")?);
+
+    let scalars: Vec<(&str, ScalarType)> = element.scalars().collect();
+    let color_channels = ["red", "green", "blue", "alpha"].map(|c| element.scalar_index(c));
+    let has_colors = color_channels[..3].iter().all(Option::is_some);
+    let size_index = element.scalar_index("point_size");
+    let normal_axes = ["nx", "ny", "nz"].map(|axis| element.scalar_index(axis));
+    let normal_indices = match normal_axes {
+        [Some(x), Some(y), Some(z)] => Some([x, y, z]),
+        _ => None,
+    };
+
+    let metadata_indices: Vec<usize> = (0..scalars.len())
+        .filter(|&i| ![xi, yi, zi].contains(&i))
+        .filter(|&i| !(has_colors && color_channels.contains(&Some(i))))
+        .filter(|&i| size_index != Some(i))
+        .filter(|&i| !normal_indices.is_some_and(|axes| axes.contains(&i)))
+        .collect();
+
+    // Don't trust a corrupt header to size allocations up front
+    let capacity = element.count.min(MAX_PREALLOCATED_POINTS);
+    let mut positions = Vec::with_capacity(capacity);
+    let mut colors = Vec::with_capacity(if has_colors { capacity } else { 0 });
+    let mut sizes = Vec::with_capacity(if size_index.is_some() { capacity } else { 0 });
+    let mut normals = Vec::with_capacity(if normal_indices.is_some() {
+        capacity
+    } else {
+        0
+    });
+    let mut metadata: Vec<Vec<f32>> = metadata_indices
+        .iter()
+        .map(|_| Vec::with_capacity(capacity))
+        .collect();
+
+    let mut values = Vec::with_capacity(scalars.len());
+    for row in 0..element.count {
+        body.read_row(element, row, &mut values)?;
+
+        positions.push(Vec3::new(
+            values[xi] as f32,
+            values[yi] as f32,
+            values[zi] as f32,
+        ));
+
+        if has_colors {
+            let channel = |c: Option<usize>| match c {
+                Some(i) => values[i] as f32 / scalars[i].1.color_scale(),
+                None => 1.0,
+            };
+            colors.push(Vec4::new(
+                channel(color_channels[0]),
+                channel(color_channels[1]),
+                channel(color_channels[2]),
+                channel(color_channels[3]),
+            ));
+        }
+
+        if let Some(i) = size_index {
+            sizes.push(values[i] as f32);
+        }
+
+        if let Some([x, y, z]) = normal_indices {
+            normals.push(Vec3::new(
+                values[x] as f32,
+                values[y] as f32,
+                values[z] as f32,
+            ));
+        }
+
+        for (field, &i) in metadata.iter_mut().zip(&metadata_indices) {
+            field.push(values[i] as f32);
+        }
+    }
+
+    let mut cloud = PointCloud::new(positions);
+    if has_colors {
+        cloud = cloud.with_colors(colors);
+    }
+    if size_index.is_some() {
+        cloud = cloud.with_sizes(sizes);
+    }
+    if normal_indices.is_some() {
+        cloud = cloud.with_normals(normals);
+    }
+    for (values, &i) in metadata.into_iter().zip(&metadata_indices) {
+        cloud = cloud.with_metadata(scalars[i].0.to_string(), values);
+    }
+
+    Ok(cloud)
+}
+
+fn malformed(message: impl Into<String>) -> LoadError {
+    LoadError::Malformed {
+        format: "PLY",
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Dataset;
+
+    fn sample_cloud() -> PointCloud {
+        PointCloud::new(vec![Vec3::ZERO, Vec3::new(1.0, -2.0, 3.5)])
+            .with_colors(vec![
+                Vec4::new(1.0, 0.0, 0.0, 1.0),
+                Vec4::new(0.0, 0.2, 1.0, 0.6),
+            ])
+            .with_sizes(vec![1.0, 2.5])
+            .with_normals(vec![Vec3::Z, Vec3::new(0.6, 0.0, -0.8)])
+            .with_metadata("intensity".to_string(), vec![0.25, 42.0])
+    }
+
+    fn round_trip(format: PlyFormat) -> PointCloud {
+        let mut bytes = Vec::new();
+        PlyWriter::new(format)
+            .write_to(&sample_cloud(), &mut bytes)
+            .unwrap();
+        PlyLoader::new().load_from_reader(bytes.as_slice()).unwrap()
+    }
+
+    fn assert_matches_sample(cloud: &PointCloud) {
+        let sample = sample_cloud();
+        assert_eq!(cloud.len(), 2);
+        assert_eq!(cloud.positions(), sample.positions());
+        assert_eq!(cloud.sizes(), sample.sizes());
+        assert_eq!(cloud.normals(), sample.normals());
+        assert_eq!(cloud.metadata("intensity"), sample.metadata("intensity"));
+        assert!(cloud.metadata("point_size").is_none());
+
+        for (a, b) in cloud.colors().unwrap().iter().zip(sample.colors().unwrap()) {
+            assert!((*a - *b).abs().max_element() < 1.0 / 255.0);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_ascii() {
+        assert_matches_sample(&round_trip(PlyFormat::Ascii));
+    }
+
+    #[test]
+    fn test_round_trip_binary_little_endian() {
+        assert_matches_sample(&round_trip(PlyFormat::BinaryLittleEndian));
+    }
+
+    #[test]
+    fn test_round_trip_binary_big_endian() {
+        assert_matches_sample(&round_trip(PlyFormat::BinaryBigEndian));
+    }
+
+    #[test]
+    fn test_round_trip_keeps_origin() {
+        let origin = DVec3::new(512_000.125, 4_100_000.5, -12.25);
+        let cloud = sample_cloud().with_origin(origin);
+
+        for format in [PlyFormat::Ascii, PlyFormat::BinaryLittleEndian] {
+            let mut bytes = Vec::new();
+            PlyWriter::new(format).write_to(&cloud, &mut bytes).unwrap();
+            let loaded = PlyLoader::new().load_from_reader(bytes.as_slice()).unwrap();
+
+            assert_eq!(loaded.origin(), origin);
+            assert_eq!(loaded.world_position(1), cloud.world_position(1));
+        }
+
+        // Clouds without an origin write no origin comment
+        let mut bytes = Vec::new();
+        PlyWriter::default()
+            .write_to(&sample_cloud(), &mut bytes)
+            .unwrap();
+        assert!(!String::from_utf8_lossy(&bytes).contains("comment origin"));
+    }
+
+    #[test]
+    fn test_writes_every_field_under_a_unique_name() {
+        let cloud = PointCloud::new(vec![Vec3::ZERO, Vec3::ONE])
+            .with_colors(vec![Vec4::ONE; 2])
+            .with_metadata("red".to_string(), vec![1.0, 2.0])
+            .with_metadata("field_red".to_string(), vec![3.0, 4.0])
+            .with_metadata("wind speed".to_string(), vec![5.0, 6.0])
+            .with_categorical("class", CategoricalField::from_labels([Some("oak"), None]))
+            .with_vectors("flow", vec![Vec3::X, Vec3::new(0.5, 1.5, -2.0)]);
+
+        let mut bytes = Vec::new();
+        PlyWriter::new(PlyFormat::Ascii)
+            .write_to(&cloud, &mut bytes)
+            .unwrap();
+        let loaded = PlyLoader::new().load_from_reader(bytes.as_slice()).unwrap();
+
+        assert_eq!(loaded.colors(), cloud.colors());
+        assert_eq!(loaded.metadata("field_red_2").unwrap(), &[1.0, 2.0]);
+        assert_eq!(loaded.metadata("field_red").unwrap(), &[3.0, 4.0]);
+        assert_eq!(loaded.metadata("wind_speed").unwrap(), &[5.0, 6.0]);
+
+        let class = loaded.metadata("class").unwrap();
+        assert_eq!(class[0], 0.0);
+        assert!(class[1].is_nan());
+        assert_eq!(loaded.metadata("flow_y").unwrap(), &[0.0, 1.5]);
+        assert_eq!(loaded.metadata("flow_z").unwrap(), &[0.0, -2.0]);
+    }
+
+    #[test]
+    fn test_skips_faces_and_reads_double_positions() {
+        let ply = "ply\n\
+                   format ascii 1.0\n\
+                   comment made by hand\n\
+                   element face 1\n\
+                   property list uchar int vertex_indices\n\
+                   element vertex 3\n\
+                   property double x\n\
+                   property double y\n\
+                   property double z\n\
+                   property uchar red\n\
+                   property uchar green\n\
+                   property uchar blue\n\
+                   property float confidence\n\
+                   end_header\n\
+                   3 0 1 2\n\
+                   0 0 0 255 0 0 0.9\n\
+                   1 0 0 0 255 0 0.8\n\
+                   0 1 0 0 0 255 0.7\n";
+
+        let cloud = PlyLoader::new().load_from_reader(ply.as_bytes()).unwrap();
+
+        assert_eq!(cloud.len(), 3);
+        assert_eq!(cloud.positions()[2], Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(cloud.colors().unwrap()[1], Vec4::new(0.0, 1.0, 0.0, 1.0));
+        assert_eq!(cloud.metadata("confidence").unwrap()[0], 0.9);
+        assert!(cloud.metadata("red").is_none());
+    }
+
+    #[test]
+    fn test_missing_position_property() {
+        let ply = "ply\nformat ascii 1.0\nelement vertex 1\n\
+                   property float x\nproperty float y\nend_header\n0 0\n";
+        let err = PlyLoader::new()
+            .load_from_reader(ply.as_bytes())
+            .unwrap_err();
+
+        assert!(matches!(err, LoadError::MissingColumn(ref name) if name == "z"));
+    }
+
+    #[test]
+    fn test_truncated_binary() {
+        let mut bytes = Vec::new();
+        PlyWriter::new(PlyFormat::BinaryLittleEndian)
+            .write_to(&sample_cloud(), &mut bytes)
+            .unwrap();
+        bytes.truncate(bytes.len() - 3);
+
+        let err = PlyLoader::new()
+            .load_from_reader(bytes.as_slice())
+            .unwrap_err();
+        assert!(matches!(err, LoadError::Malformed { format: "PLY", .. }));
+    }
+
+    #[test]
+    fn test_huge_vertex_count_fails_without_allocating() {
+        let ply = "ply\nformat binary_little_endian 1.0\nelement vertex 4000000000\n\
+                   property float x\nproperty float y\nproperty float z\nend_header\n";
+        let err = PlyLoader::new()
+            .load_from_reader(ply.as_bytes())
+            .unwrap_err();
+
+        assert!(matches!(err, LoadError::Malformed { format: "PLY", .. }));
+    }
+
+    #[test]
+    fn test_invalid_ascii_value() {
+        let ply = "ply\nformat ascii 1.0\nelement vertex 2\n\
+                   property float x\nproperty float y\nproperty float z\nend_header\n\
+                   0 0 0\n1 abc 1\n";
+        let err = PlyLoader::new()
+            .load_from_reader(ply.as_bytes())
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            LoadError::InvalidValue { row: 2, ref column, .. } if column == "y"
+        ));
+    }
+}
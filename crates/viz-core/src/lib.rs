@@ -1,13 +1,33 @@
-pub mod renderer;
 pub mod camera;
-pub mod data;
+pub mod cluster;
 pub mod color;
+pub mod data;
+mod error;
+pub mod io;
 pub mod math;
+pub mod renderer;
+pub mod spatial;
 pub mod ui;
 
-pub use renderer::{RenderContext, RenderError, CameraUniforms};
-pub use math::{Bounds3D, Transform};
 pub use camera::OrbitalCamera;
-pub use data::{Dataset, PointCloud};
-pub use color::{Colormap, Viridis, Plasma, Inferno, Turbo, ColorScale, ScaleType};
-pub use ui::{UiContext, PerformanceMetrics, ControlPanel, performance_panel};
+pub use cluster::{Clusters, Dbscan, KMeans};
+pub use color::{
+    ColorRange, ColorScale, Colormap, Inferno, Legend, Palette, Plasma, ScaleType, Tab10, Turbo,
+    Viridis,
+};
+pub use data::{
+    ArrowDataset, Binning, CategoricalField, DataError, Dataset, Expression, FieldInfo, FieldStats,
+    Filter, Histogram, NanPolicy, NormalOrientation, Pca, PointCloud, Selection, TimeSeries,
+};
+pub use error::{Error, Result};
+pub use io::{
+    CacheLoader, CacheWriter, CsvLoader, CsvWriter, FileFormat, LasLoader, LoadError, NpyLoader,
+    ParquetLoader, PlyFormat, PlyLoader, PlyWriter,
+};
+pub use math::{Bounds3D, Ray, Transform};
+pub use renderer::{CameraUniforms, RenderContext, RenderError};
+pub use spatial::KdTree;
+pub use ui::{
+    colorbar_panel, legend_panel, performance_panel, stats_panel, ControlPanel, PerformanceMetrics,
+    Timeline, UiContext,
+};
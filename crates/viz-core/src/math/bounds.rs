@@ -1,4 +1,5 @@
-use glam::Vec3;
+use super::Transform;
+use glam::{DVec3, Vec3};
 
 /// Axis-aligned bounding box (AABB) in 3D space
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -15,6 +16,9 @@ impl Bounds3D {
 
     /// Create a bounding box that encompasses all given points
     ///
+    /// Points with a NaN or infinite component are ignored; if no point is
+    /// finite the result is [`Bounds3D::zero`].
+    ///
     /// # Examples
     /// ```
     /// use glam::Vec3;
@@ -30,14 +34,15 @@ impl Bounds3D {
     /// assert_eq!(bounds.max, Vec3::new(1.0, 2.0, 3.0));
     /// ```
     pub fn from_points(points: &[Vec3]) -> Self {
-        if points.is_empty() {
+        let mut finite = points.iter().copied().filter(|p| p.is_finite());
+        let Some(first) = finite.next() else {
             return Self::zero();
-        }
+        };
 
-        let mut min = points[0];
-        let mut max = points[0];
+        let mut min = first;
+        let mut max = first;
 
-        for &point in points.iter().skip(1) {
+        for point in finite {
             min = min.min(point);
             max = max.max(point);
         }
@@ -112,6 +117,34 @@ impl Bounds3D {
         }
     }
 
+    /// Bounding box of this box after a transform
+    ///
+    /// Encloses the eight transformed corners, so a rotated box grows to
+    /// stay axis-aligned.
+    pub fn transformed(&self, transform: &Transform) -> Bounds3D {
+        Bounds3D::from_points(
+            &self
+                .corners()
+                .map(|corner| transform.transform_point(corner)),
+        )
+    }
+
+    /// Bounding box of a dataset placed in a shared frame
+    ///
+    /// `self` is relative to the dataset's origin `from` and is transformed
+    /// around it; the result is relative to `to`. The offset between the
+    /// origins is taken in double precision.
+    pub fn placed(&self, transform: &Transform, from: DVec3, to: DVec3) -> Bounds3D {
+        let bounds = self.transformed(transform);
+        let shift = (from - to).as_vec3();
+        Bounds3D::new(bounds.min + shift, bounds.max + shift)
+    }
+
+    /// Smallest box enclosing every given box, or `None` if there are none
+    pub fn union_all(bounds: impl IntoIterator<Item = Bounds3D>) -> Option<Bounds3D> {
+        bounds.into_iter().reduce(|a, b| a.union(&b))
+    }
+
     /// Create a padded version of this bounding box
     pub fn padded(&self, padding: f32) -> Bounds3D {
         Bounds3D {
@@ -157,6 +190,63 @@ mod tests {
         assert_eq!(bounds.max, Vec3::new(1.0, 2.0, 3.0));
     }
 
+    #[test]
+    fn test_from_points_ignores_non_finite() {
+        let points = vec![
+            Vec3::new(f32::NAN, 0.0, 0.0),
+            Vec3::new(1.0, 2.0, 3.0),
+            Vec3::new(-1.0, f32::INFINITY, 0.5),
+            Vec3::new(0.0, -2.0, 1.0),
+        ];
+        let bounds = Bounds3D::from_points(&points);
+        assert_eq!(bounds.min, Vec3::new(0.0, -2.0, 1.0));
+        assert_eq!(bounds.max, Vec3::new(1.0, 2.0, 3.0));
+
+        let bounds = Bounds3D::from_points(&[Vec3::NAN]);
+        assert_eq!(bounds, Bounds3D::zero());
+    }
+
+    #[test]
+    fn test_transformed() {
+        let bounds = Bounds3D::new(Vec3::ZERO, Vec3::new(2.0, 1.0, 1.0));
+
+        let moved = bounds.transformed(&Transform::from_translation(Vec3::X * 10.0));
+        assert_eq!(
+            moved,
+            Bounds3D::new(Vec3::new(10.0, 0.0, 0.0), Vec3::new(12.0, 1.0, 1.0))
+        );
+
+        let turned = bounds.transformed(&Transform::from_rotation(glam::Quat::from_rotation_z(
+            std::f32::consts::FRAC_PI_2,
+        )));
+        assert!((turned.min - Vec3::new(-1.0, 0.0, 0.0)).abs().max_element() < 1e-6);
+        assert!((turned.max - Vec3::new(0.0, 2.0, 1.0)).abs().max_element() < 1e-6);
+    }
+
+    #[test]
+    fn test_placed_and_union_all() {
+        let bounds = Bounds3D::new(Vec3::ZERO, Vec3::ONE);
+        let origin = DVec3::new(1.0e6, 0.0, 0.0);
+
+        // Same origin: only the transform applies
+        let scaled = bounds.placed(&Transform::from_uniform_scale(2.0), origin, origin);
+        assert_eq!(scaled, Bounds3D::new(Vec3::ZERO, Vec3::splat(2.0)));
+
+        // A dataset 0.5 past a large shared origin keeps the small offset
+        let other = DVec3::new(1.0e6 + 0.5, 0.0, -1.0);
+        let moved = bounds.placed(&Transform::identity(), other, origin);
+        assert_eq!(
+            moved,
+            Bounds3D::new(Vec3::new(0.5, 0.0, -1.0), Vec3::new(1.5, 1.0, 0.0))
+        );
+
+        assert_eq!(
+            Bounds3D::union_all([scaled, moved]),
+            Some(Bounds3D::new(Vec3::new(0.0, 0.0, -1.0), Vec3::splat(2.0)))
+        );
+        assert_eq!(Bounds3D::union_all([]), None);
+    }
+
     #[test]
     fn test_center() {
         let bounds = Bounds3D::new(Vec3::new(-1.0, -1.0, -1.0), Vec3::new(1.0, 1.0, 1.0));
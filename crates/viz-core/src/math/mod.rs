@@ -1,5 +1,9 @@
 mod bounds;
+mod ray;
+mod rng;
 mod transform;
 
 pub use bounds::Bounds3D;
+pub use ray::Ray;
+pub(crate) use rng::SplitMix64;
 pub use transform::Transform;
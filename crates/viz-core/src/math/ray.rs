@@ -0,0 +1,75 @@
+use super::Bounds3D;
+use glam::Vec3;
+
+/// Half-line starting at `origin` and extending along `direction`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: Vec3,
+    /// Unit direction
+    pub direction: Vec3,
+}
+
+impl Ray {
+    /// Create a ray, normalizing the direction
+    pub fn new(origin: Vec3, direction: Vec3) -> Self {
+        Self {
+            origin,
+            direction: direction.normalize(),
+        }
+    }
+
+    /// Point at distance `t` along the ray
+    pub fn at(&self, t: f32) -> Vec3 {
+        self.origin + self.direction * t
+    }
+
+    /// Distance from a point to the closest point on the ray
+    ///
+    /// Points behind the origin are measured to the origin itself.
+    pub fn distance_to_point(&self, point: Vec3) -> f32 {
+        let t = (point - self.origin).dot(self.direction).max(0.0);
+        self.at(t).distance(point)
+    }
+
+    /// Entry and exit distances where the ray crosses a bounding box
+    ///
+    /// Returns None if the ray misses the box. The entry distance is 0 when
+    /// the origin is inside.
+    pub fn intersect_bounds(&self, bounds: &Bounds3D) -> Option<(f32, f32)> {
+        let inverse = self.direction.recip();
+        let t0 = (bounds.min - self.origin) * inverse;
+        let t1 = (bounds.max - self.origin) * inverse;
+
+        // f32::min/max drop the NaN from 0 × inf (origin on a slab plane)
+        let mut near = 0.0f32;
+        let mut far = f32::INFINITY;
+        for axis in 0..3 {
+            near = near.max(t0[axis].min(t1[axis]));
+            far = far.min(t0[axis].max(t1[axis]));
+        }
+
+        (near <= far).then_some((near, far))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_and_bounds_intersection() {
+        let ray = Ray::new(Vec3::ZERO, Vec3::new(2.0, 0.0, 0.0));
+        assert_eq!(ray.direction, Vec3::X);
+        assert_eq!(ray.distance_to_point(Vec3::new(5.0, 3.0, 4.0)), 5.0);
+        assert_eq!(ray.distance_to_point(Vec3::new(-3.0, 4.0, 0.0)), 5.0);
+
+        let ahead = Bounds3D::new(Vec3::new(2.0, -1.0, -1.0), Vec3::new(4.0, 1.0, 1.0));
+        assert_eq!(ray.intersect_bounds(&ahead), Some((2.0, 4.0)));
+
+        let behind = Bounds3D::new(Vec3::splat(-4.0), Vec3::splat(-2.0));
+        assert_eq!(ray.intersect_bounds(&behind), None);
+
+        let around = Bounds3D::centered(2.0);
+        assert_eq!(ray.intersect_bounds(&around), Some((0.0, 1.0)));
+    }
+}
@@ -1,5 +1,3 @@
-use wgpu::{Buffer, Device};
-
 /// Utility for managing GPU buffers
 pub struct BufferManager {
     // Buffer management implementation (placeholder for now)
@@ -1,8 +1,8 @@
 use anyhow::Result;
 use std::sync::Arc;
 use thiserror::Error;
-use tracing::{info, warn, instrument};
-use wgpu::{Adapter, Device, Queue, Surface, SurfaceConfiguration};
+use tracing::{info, instrument, warn};
+use wgpu::{Device, Queue, Surface, SurfaceConfiguration};
 use winit::window::Window;
 
 #[derive(Debug, Error)]
@@ -148,7 +148,10 @@ impl RenderContext {
             self.surface.configure(&self.device, &self.config);
             info!("Surface resized to {}x{}", new_width, new_height);
         } else {
-            warn!("Attempted to resize to invalid dimensions: {}x{}", new_width, new_height);
+            warn!(
+                "Attempted to resize to invalid dimensions: {}x{}",
+                new_width, new_height
+            );
         }
     }
 
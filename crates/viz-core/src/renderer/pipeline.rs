@@ -1,5 +1,3 @@
-use wgpu::{Device, RenderPipeline};
-
 /// Builder for creating render pipelines with sensible defaults
 pub struct PipelineBuilder {
     // Pipeline builder implementation (placeholder for now)
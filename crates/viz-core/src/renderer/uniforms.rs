@@ -1,5 +1,7 @@
+use crate::camera::OrbitalCamera;
+use crate::math::Transform;
 use bytemuck::{Pod, Zeroable};
-use glam::{Mat4, Vec3};
+use glam::{DVec3, Mat4, Vec3};
 
 /// Camera uniforms for GPU (matches WGSL struct)
 ///
@@ -16,6 +18,16 @@ pub struct CameraUniforms {
 
     /// Padding for alignment (4 bytes)
     pub _padding: f32,
+
+    /// Translation added to vertex positions before `view_proj` (12 bytes)
+    pub offset: [f32; 3],
+
+    /// Padding for alignment (4 bytes)
+    pub _offset_padding: f32,
+
+    /// Rotation and scale applied to vertex positions and normals before
+    /// the offset (64 bytes)
+    pub model: [[f32; 4]; 4],
 }
 
 impl CameraUniforms {
@@ -25,9 +37,59 @@ impl CameraUniforms {
             view_proj: view_proj.to_cols_array_2d(),
             view_pos: view_pos.to_array(),
             _padding: 0.0,
+            offset: [0.0; 3],
+            _offset_padding: 0.0,
+            model: Mat4::IDENTITY.to_cols_array_2d(),
         }
     }
 
+    /// Create camera-relative uniforms for vertices stored relative to
+    /// `origin`
+    ///
+    /// The offset from the camera to `origin` is computed in double
+    /// precision, and the shader adds it to each vertex before a view with
+    /// the camera at zero. Data near the camera then renders without f32
+    /// jitter even at geospatial or astronomical coordinates.
+    ///
+    /// # Examples
+    /// ```
+    /// use glam::{DVec3, Vec3};
+    /// use viz_core::camera::OrbitalCamera;
+    /// use viz_core::renderer::CameraUniforms;
+    ///
+    /// let origin = DVec3::new(500_000.0, 4_000_000.0, 0.0);
+    /// let mut camera = OrbitalCamera::new(Vec3::ZERO, 10.0, 1.5);
+    /// camera.origin = origin;
+    ///
+    /// let uniforms = CameraUniforms::camera_relative(&camera, origin);
+    /// assert_eq!(uniforms.view_pos, [0.0; 3]);
+    /// assert_eq!(uniforms.offset, [0.0, 0.0, -10.0]);
+    /// ```
+    pub fn camera_relative(camera: &OrbitalCamera, origin: DVec3) -> Self {
+        Self {
+            view_proj: camera.relative_view_projection_matrix().to_cols_array_2d(),
+            view_pos: [0.0; 3],
+            _padding: 0.0,
+            offset: (origin - camera.world_position()).as_vec3().to_array(),
+            _offset_padding: 0.0,
+            model: Mat4::IDENTITY.to_cols_array_2d(),
+        }
+    }
+
+    /// Place the vertices with a model transform
+    ///
+    /// The transform acts in the frame of the vertex positions (around the
+    /// `origin` given to [`CameraUniforms::camera_relative`]): rotation and
+    /// scale go into the model matrix and the translation is added to the
+    /// offset.
+    pub fn with_transform(mut self, transform: &Transform) -> Self {
+        let linear =
+            Mat4::from_scale_rotation_translation(transform.scale, transform.rotation, Vec3::ZERO);
+        self.model = linear.to_cols_array_2d();
+        self.offset = (Vec3::from_array(self.offset) + transform.translation).to_array();
+        self
+    }
+
     /// Update from camera
     pub fn update(&mut self, view_proj: Mat4, view_pos: Vec3) {
         self.view_proj = view_proj.to_cols_array_2d();
@@ -41,6 +103,9 @@ impl Default for CameraUniforms {
             view_proj: Mat4::IDENTITY.to_cols_array_2d(),
             view_pos: [0.0, 0.0, 0.0],
             _padding: 0.0,
+            offset: [0.0; 3],
+            _offset_padding: 0.0,
+            model: Mat4::IDENTITY.to_cols_array_2d(),
         }
     }
 }
@@ -51,8 +116,8 @@ mod tests {
 
     #[test]
     fn test_size() {
-        // Must be 80 bytes (64 for mat4x4 + 12 for vec3 + 4 for padding)
-        assert_eq!(std::mem::size_of::<CameraUniforms>(), 80);
+        // Must be 160 bytes (2 × 64 for mat4x4 + 2 × (12 for vec3 + 4 for padding))
+        assert_eq!(std::mem::size_of::<CameraUniforms>(), 160);
     }
 
     #[test]
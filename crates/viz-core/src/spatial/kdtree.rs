@@ -0,0 +1,471 @@
+use crate::data::Selection;
+use crate::math::{Bounds3D, Ray};
+use glam::Vec3;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Maximum number of points stored in a leaf
+const LEAF_SIZE: usize = 16;
+
+/// Subtrees with at least this many points are built on separate threads
+const PARALLEL_THRESHOLD: usize = 8 * 1024;
+
+/// A point returned by a [`KdTree`] query
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Neighbor {
+    /// Index of the point in the positions the tree was built from
+    pub index: usize,
+    /// Distance to the query point (or ray)
+    pub distance: f32,
+}
+
+/// k-d tree over a set of 3D points
+///
+/// Built once from positions (e.g. [`PointCloud::positions`]) and then
+/// queried for nearest neighbours, radius and box ranges, or the point
+/// closest to a ray. All results refer to indices into the original slice;
+/// points with a NaN or infinite component are left out of the tree.
+///
+/// Splits are at the median along the longest axis of each cell, so the
+/// tree is balanced. Large subtrees are built in parallel with rayon.
+///
+/// [`PointCloud::positions`]: crate::data::PointCloud::positions
+///
+/// # Examples
+/// ```
+/// use glam::Vec3;
+/// use viz_core::spatial::KdTree;
+///
+/// let positions = vec![Vec3::ZERO, Vec3::X, Vec3::new(5.0, 0.0, 0.0)];
+/// let tree = KdTree::new(&positions);
+///
+/// let nearest = tree.nearest(Vec3::new(0.9, 0.0, 0.0)).unwrap();
+/// assert_eq!(nearest.index, 1);
+///
+/// let close = tree.within_radius(Vec3::ZERO, 1.5);
+/// assert_eq!(close.iter().map(|n| n.index).collect::<Vec<_>>(), vec![0, 1]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct KdTree {
+    /// Points in tree order
+    points: Vec<Vec3>,
+
+    /// Original index of each point in `points`
+    indices: Vec<usize>,
+
+    /// Root node (None when there are no finite points)
+    root: Option<Node>,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    /// Tight bounds of the points below this node
+    bounds: Bounds3D,
+
+    /// Range of `points` covered by this node
+    start: usize,
+    end: usize,
+
+    /// Children, for interior nodes
+    children: Option<Box<(Node, Node)>>,
+}
+
+/// Max-heap entry ordered by squared distance
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    distance_squared: f32,
+    slot: usize,
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance_squared.total_cmp(&other.distance_squared)
+    }
+}
+
+impl KdTree {
+    /// Build a tree from point positions
+    pub fn new(positions: &[Vec3]) -> Self {
+        let mut items: Vec<(Vec3, usize)> = positions
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.is_finite())
+            .map(|(i, &p)| (p, i))
+            .collect();
+
+        let root = (!items.is_empty()).then(|| {
+            let mut cell = Bounds3D::new(items[0].0, items[0].0);
+            for (point, _) in &items {
+                cell.expand_to_include(*point);
+            }
+            build(&mut items, 0, cell)
+        });
+
+        let (points, indices) = items.into_iter().unzip();
+        Self {
+            points,
+            indices,
+            root,
+        }
+    }
+
+    /// Number of points in the tree
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Check if the tree has no points
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Closest point to `point`
+    pub fn nearest(&self, point: Vec3) -> Option<Neighbor> {
+        self.k_nearest(point, 1).into_iter().next()
+    }
+
+    /// The `k` closest points to `point`, nearest first
+    pub fn k_nearest(&self, point: Vec3, k: usize) -> Vec<Neighbor> {
+        let mut heap = BinaryHeap::with_capacity(k.min(self.len()) + 1);
+        if let Some(root) = &self.root {
+            if k > 0 {
+                self.k_nearest_in(root, point, k, &mut heap);
+            }
+        }
+
+        self.sorted_neighbors(heap.into_vec())
+    }
+
+    /// All points within `radius` of `point`, nearest first
+    pub fn within_radius(&self, point: Vec3, radius: f32) -> Vec<Neighbor> {
+        let radius_squared = radius * radius;
+        let mut found = Vec::new();
+        let mut stack: Vec<&Node> = self.root.iter().collect();
+
+        while let Some(node) = stack.pop() {
+            if distance_squared_to_bounds(point, &node.bounds) > radius_squared {
+                continue;
+            }
+            match &node.children {
+                Some(children) => stack.extend([&children.0, &children.1]),
+                None => {
+                    for slot in node.start..node.end {
+                        let distance_squared = self.points[slot].distance_squared(point);
+                        if distance_squared <= radius_squared {
+                            found.push(Candidate {
+                                distance_squared,
+                                slot,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        self.sorted_neighbors(found)
+    }
+
+    /// All points inside a bounding box (inclusive)
+    pub fn in_bounds(&self, bounds: &Bounds3D) -> Selection {
+        let mut found = Vec::new();
+        let mut stack: Vec<&Node> = self.root.iter().collect();
+
+        while let Some(node) = stack.pop() {
+            if !node.bounds.intersects(bounds) {
+                continue;
+            }
+
+            let contained = bounds.contains(node.bounds.min) && bounds.contains(node.bounds.max);
+            match &node.children {
+                Some(children) if !contained => stack.extend([&children.0, &children.1]),
+                _ => found.extend(
+                    (node.start..node.end)
+                        .filter(|&slot| contained || bounds.contains(self.points[slot]))
+                        .map(|slot| self.indices[slot]),
+                ),
+            }
+        }
+
+        Selection::from_indices(found)
+    }
+
+    /// Point closest to a ray, if any lies within `max_distance` of it
+    ///
+    /// Useful for picking, with `max_distance` as the pick tolerance in world
+    /// units. Points behind the ray origin are measured to the origin.
+    pub fn nearest_to_ray(&self, ray: &Ray, max_distance: f32) -> Option<Neighbor> {
+        let mut best: Option<Neighbor> = None;
+        let mut limit = max_distance;
+        let mut stack: Vec<&Node> = self.root.iter().collect();
+
+        while let Some(node) = stack.pop() {
+            // A ray within `limit` of a box passes through the padded box
+            if ray.intersect_bounds(&node.bounds.padded(limit)).is_none() {
+                continue;
+            }
+            match &node.children {
+                Some(children) => stack.extend([&children.0, &children.1]),
+                None => {
+                    for slot in node.start..node.end {
+                        let distance = ray.distance_to_point(self.points[slot]);
+                        if distance <= limit && best.is_none_or(|b| distance < b.distance) {
+                            limit = distance;
+                            best = Some(Neighbor {
+                                index: self.indices[slot],
+                                distance,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        best
+    }
+
+    fn k_nearest_in(&self, node: &Node, point: Vec3, k: usize, heap: &mut BinaryHeap<Candidate>) {
+        if heap.len() == k
+            && distance_squared_to_bounds(point, &node.bounds)
+                > heap.peek().unwrap().distance_squared
+        {
+            return;
+        }
+
+        match &node.children {
+            Some(children) => {
+                // Descend into the closer child first to tighten the bound early
+                let (a, b) = (&children.0, &children.1);
+                let (first, second) = if distance_squared_to_bounds(point, &a.bounds)
+                    <= distance_squared_to_bounds(point, &b.bounds)
+                {
+                    (a, b)
+                } else {
+                    (b, a)
+                };
+                self.k_nearest_in(first, point, k, heap);
+                self.k_nearest_in(second, point, k, heap);
+            }
+            None => {
+                for slot in node.start..node.end {
+                    let candidate = Candidate {
+                        distance_squared: self.points[slot].distance_squared(point),
+                        slot,
+                    };
+                    if heap.len() < k {
+                        heap.push(candidate);
+                    } else if candidate < *heap.peek().unwrap() {
+                        heap.pop();
+                        heap.push(candidate);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Convert candidates to neighbors sorted by distance, then index
+    fn sorted_neighbors(&self, mut candidates: Vec<Candidate>) -> Vec<Neighbor> {
+        candidates.sort_by(|a, b| {
+            a.cmp(b)
+                .then_with(|| self.indices[a.slot].cmp(&self.indices[b.slot]))
+        });
+        candidates
+            .into_iter()
+            .map(|c| Neighbor {
+                index: self.indices[c.slot],
+                distance: c.distance_squared.sqrt(),
+            })
+            .collect()
+    }
+}
+
+/// Build the subtree over `items`, which start at `offset` in tree order
+fn build(items: &mut [(Vec3, usize)], offset: usize, cell: Bounds3D) -> Node {
+    let end = offset + items.len();
+
+    if items.len() <= LEAF_SIZE {
+        let mut bounds = Bounds3D::new(items[0].0, items[0].0);
+        for (point, _) in items.iter() {
+            bounds.expand_to_include(*point);
+        }
+        return Node {
+            bounds,
+            start: offset,
+            end,
+            children: None,
+        };
+    }
+
+    let size = cell.size();
+    let axis = if size.x >= size.y && size.x >= size.z {
+        0
+    } else if size.y >= size.z {
+        1
+    } else {
+        2
+    };
+    let mid = items.len() / 2;
+    items.select_nth_unstable_by(mid, |a, b| a.0[axis].total_cmp(&b.0[axis]));
+    let split = items[mid].0[axis];
+
+    let mut left_cell = cell;
+    left_cell.max[axis] = split;
+    let mut right_cell = cell;
+    right_cell.min[axis] = split;
+
+    let (left_items, right_items) = items.split_at_mut(mid);
+    let (left, right) = if end - offset >= PARALLEL_THRESHOLD {
+        rayon::join(
+            || build(left_items, offset, left_cell),
+            || build(right_items, offset + mid, right_cell),
+        )
+    } else {
+        (
+            build(left_items, offset, left_cell),
+            build(right_items, offset + mid, right_cell),
+        )
+    };
+
+    Node {
+        bounds: left.bounds.union(&right.bounds),
+        start: offset,
+        end,
+        children: Some(Box::new((left, right))),
+    }
+}
+
+/// Squared distance from a point to the nearest point of a box
+fn distance_squared_to_bounds(point: Vec3, bounds: &Bounds3D) -> f32 {
+    let clamped = point.clamp(bounds.min, bounds.max);
+    clamped.distance_squared(point)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random points in [-10, 10)³, with a few NaNs
+    fn random_points(count: usize, seed: u64) -> Vec<Vec3> {
+        let mut state = seed;
+        let mut next = move || {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((state >> 40) as f32 / (1u64 << 24) as f32) * 20.0 - 10.0
+        };
+
+        (0..count)
+            .map(|i| {
+                if i % 97 == 13 {
+                    Vec3::NAN
+                } else {
+                    Vec3::new(next(), next(), next())
+                }
+            })
+            .collect()
+    }
+
+    fn brute_force_sorted(points: &[Vec3], distance: impl Fn(Vec3) -> f32) -> Vec<Neighbor> {
+        let mut all: Vec<Neighbor> = points
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.is_finite())
+            .map(|(index, &p)| Neighbor {
+                index,
+                distance: distance(p),
+            })
+            .collect();
+        all.sort_by(|a, b| {
+            a.distance
+                .total_cmp(&b.distance)
+                .then(a.index.cmp(&b.index))
+        });
+        all
+    }
+
+    #[test]
+    fn test_k_nearest_and_radius_match_brute_force() {
+        // Large enough to exercise the parallel build
+        let points = random_points(20_000, 7);
+        let tree = KdTree::new(&points);
+        assert_eq!(tree.len(), points.iter().filter(|p| p.is_finite()).count());
+
+        for query in random_points(20, 99).into_iter().filter(|p| p.is_finite()) {
+            let expected = brute_force_sorted(&points, |p| p.distance(query));
+
+            let knn = tree.k_nearest(query, 10);
+            let knn_indices: Vec<usize> = knn.iter().map(|n| n.index).collect();
+            let expected_indices: Vec<usize> = expected[..10].iter().map(|n| n.index).collect();
+            assert_eq!(knn_indices, expected_indices);
+            assert_eq!(tree.nearest(query).unwrap().index, expected[0].index);
+
+            let within = tree.within_radius(query, 2.0);
+            let expected_within: Vec<usize> = expected
+                .iter()
+                .filter(|n| n.distance * n.distance <= 4.0)
+                .map(|n| n.index)
+                .collect();
+            assert_eq!(
+                within.iter().map(|n| n.index).collect::<Vec<_>>(),
+                expected_within
+            );
+        }
+    }
+
+    #[test]
+    fn test_in_bounds_matches_brute_force() {
+        let points = random_points(5_000, 3);
+        let tree = KdTree::new(&points);
+
+        let bounds = Bounds3D::new(Vec3::new(-2.0, -10.0, 0.0), Vec3::new(3.0, 1.0, 20.0));
+        let expected: Vec<usize> = (0..points.len())
+            .filter(|&i| points[i].is_finite() && bounds.contains(points[i]))
+            .collect();
+        assert_eq!(tree.in_bounds(&bounds).indices(), expected.as_slice());
+
+        let everything = Bounds3D::centered(100.0);
+        assert_eq!(tree.in_bounds(&everything).len(), tree.len());
+    }
+
+    #[test]
+    fn test_nearest_to_ray_matches_brute_force() {
+        let points = random_points(5_000, 11);
+        let tree = KdTree::new(&points);
+
+        let rays = [
+            Ray::new(Vec3::new(-20.0, 0.3, 0.1), Vec3::X),
+            Ray::new(Vec3::new(15.0, 15.0, 15.0), Vec3::new(-1.0, -0.8, -1.2)),
+            Ray::new(Vec3::ZERO, Vec3::Z),
+        ];
+        for ray in rays {
+            let expected = brute_force_sorted(&points, |p| ray.distance_to_point(p));
+            let hit = tree.nearest_to_ray(&ray, 1.0).unwrap();
+            assert_eq!(hit.index, expected[0].index);
+            assert!((hit.distance - expected[0].distance).abs() < 1e-6);
+        }
+
+        let far_away = Ray::new(Vec3::new(100.0, 100.0, 100.0), Vec3::X);
+        assert!(tree.nearest_to_ray(&far_away, 1.0).is_none());
+    }
+
+    #[test]
+    fn test_empty_and_degenerate() {
+        let tree = KdTree::new(&[Vec3::NAN]);
+        assert!(tree.is_empty());
+        assert!(tree.nearest(Vec3::ZERO).is_none());
+        assert!(tree.in_bounds(&Bounds3D::centered(1.0)).is_empty());
+
+        // Many duplicates still split into leaves
+        let tree = KdTree::new(&vec![Vec3::ONE; 100]);
+        assert_eq!(tree.k_nearest(Vec3::ZERO, 5).len(), 5);
+        assert_eq!(tree.k_nearest(Vec3::ZERO, usize::MAX).len(), 100);
+        assert_eq!(tree.within_radius(Vec3::ONE, 0.0).len(), 100);
+    }
+}
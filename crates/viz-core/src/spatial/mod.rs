@@ -0,0 +1,3 @@
+mod kdtree;
+
+pub use kdtree::{KdTree, Neighbor};
@@ -4,4 +4,8 @@ mod panels;
 
 pub use context::UiContext;
 pub use metrics::PerformanceMetrics;
-pub use panels::{performance_panel, ControlPanel};
+#[cfg(not(target_arch = "wasm32"))]
+pub use panels::loading_panel;
+pub use panels::{
+    colorbar_panel, legend_panel, performance_panel, stats_panel, ControlPanel, Timeline,
+};
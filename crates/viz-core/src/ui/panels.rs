@@ -1,4 +1,8 @@
 use super::PerformanceMetrics;
+use crate::color::{Colormap, Legend, ScaleType};
+use crate::data::{Dataset, FieldStats, Histogram, PointCloud, TimeSeries};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::io::{LoadHandle, ProgressUnit};
 
 /// Draw performance metrics panel
 pub fn performance_panel(ctx: &egui::Context, metrics: &PerformanceMetrics) {
@@ -66,11 +70,7 @@ pub fn performance_panel(ctx: &egui::Context, metrics: &PerformanceMetrics) {
                 ui.separator();
                 ui.label("Frame Times:");
 
-                let points: Vec<f64> = metrics
-                    .frame_times()
-                    .iter()
-                    .map(|&t| t as f64)
-                    .collect();
+                let points: Vec<f64> = metrics.frame_times().iter().map(|&t| t as f64).collect();
 
                 use egui_plot::{Line, Plot, PlotPoints};
                 let line = Line::new(PlotPoints::from_ys_f64(&points));
@@ -89,6 +89,190 @@ pub fn performance_panel(ctx: &egui::Context, metrics: &PerformanceMetrics) {
         });
 }
 
+/// Draw a progress bar for a background load
+///
+/// Returns true when the Cancel button was clicked.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn loading_panel(ctx: &egui::Context, handle: &LoadHandle) -> bool {
+    let mut cancel = false;
+    let progress = handle.progress();
+
+    egui::Window::new("⏳ Loading")
+        .id(egui::Id::new(("loading_panel", handle.path())))
+        .default_pos([270.0, 10.0])
+        .default_width(250.0)
+        .resizable(false)
+        .show(ctx, |ui| {
+            let file_name = handle
+                .path()
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            ui.label(file_name);
+
+            let text = match progress.unit {
+                ProgressUnit::Bytes => format!(
+                    "{:.1} / {:.1} MB",
+                    progress.processed as f64 / 1e6,
+                    progress.total as f64 / 1e6
+                ),
+                ProgressUnit::Rows => {
+                    format!("{} / {} rows", progress.processed, progress.total)
+                }
+            };
+            ui.add(egui::ProgressBar::new(progress.fraction()).text(text));
+
+            ui.add_enabled_ui(!handle.is_cancelled(), |ui| {
+                if ui.button("Cancel").clicked() {
+                    cancel = true;
+                }
+            });
+        });
+
+    // Keep repainting so the bar advances without input events
+    ctx.request_repaint();
+
+    cancel
+}
+
+/// Draw a legend mapping category labels to colors
+pub fn legend_panel(ctx: &egui::Context, legend: &Legend) {
+    egui::Window::new(format!("🏷 {}", legend.title))
+        .id(egui::Id::new("legend_panel"))
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-10.0, -10.0])
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    for entry in &legend.entries {
+                        ui.horizontal(|ui| {
+                            let (rect, _) = ui
+                                .allocate_exact_size(egui::vec2(14.0, 14.0), egui::Sense::hover());
+                            ui.painter().rect_filled(
+                                rect,
+                                2.0,
+                                egui::Color32::from_rgb(
+                                    (entry.color.x * 255.0) as u8,
+                                    (entry.color.y * 255.0) as u8,
+                                    (entry.color.z * 255.0) as u8,
+                                ),
+                            );
+                            ui.label(&entry.label);
+                        });
+                    }
+                });
+        });
+}
+
+/// Draw a colorbar for a colormapped field
+///
+/// `label` titles the bar, e.g. a field label with its unit from
+/// [`PointCloud::field_label`], and the ticks show the values at both ends
+/// and the middle of the bar.
+pub fn colorbar_panel(
+    ctx: &egui::Context,
+    label: &str,
+    colormap: &dyn Colormap,
+    min: f32,
+    max: f32,
+    scale_type: ScaleType,
+) {
+    egui::Window::new(format!("🌈 {}", label))
+        .id(egui::Id::new("colorbar_panel"))
+        .anchor(egui::Align2::RIGHT_BOTTOM, [-10.0, -10.0])
+        .default_width(220.0)
+        .resizable(false)
+        .show(ctx, |ui| {
+            let width = ui.available_width();
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(width, 16.0), egui::Sense::hover());
+            let steps = 64;
+            for i in 0..steps {
+                let color = colormap.sample(i as f32 / (steps - 1) as f32);
+                let x0 = rect.min.x + width * i as f32 / steps as f32;
+                let x1 = rect.min.x + width * (i + 1) as f32 / steps as f32;
+                ui.painter().rect_filled(
+                    egui::Rect::from_min_max(
+                        egui::pos2(x0, rect.min.y),
+                        egui::pos2(x1, rect.max.y),
+                    ),
+                    0.0,
+                    egui::Color32::from_rgb(
+                        (color.x * 255.0) as u8,
+                        (color.y * 255.0) as u8,
+                        (color.z * 255.0) as u8,
+                    ),
+                );
+            }
+            ui.painter()
+                .rect_stroke(rect, 0.0, (1.0, egui::Color32::from_gray(100)));
+
+            // Value at the middle of the bar, which is the geometric mean on
+            // a log scale
+            let mid = match scale_type {
+                ScaleType::Log if min > 0.0 => (min * max).sqrt(),
+                _ => (min + max) / 2.0,
+            };
+            ui.columns(3, |columns| {
+                columns[0].monospace(format!("{:.4}", min));
+                columns[1].vertical_centered(|ui| ui.monospace(format!("{:.4}", mid)));
+                columns[2].with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
+                    ui.monospace(format!("{:.4}", max));
+                });
+            });
+        });
+}
+
+/// Draw the statistics and histogram of a metadata field
+pub fn stats_panel(ctx: &egui::Context, field: &str, stats: &FieldStats, histogram: &Histogram) {
+    egui::Window::new(format!("📈 {}", field))
+        .id(egui::Id::new("stats_panel"))
+        .anchor(egui::Align2::RIGHT_TOP, [-10.0, 10.0])
+        .default_width(220.0)
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("stats_grid").num_columns(2).show(ui, |ui| {
+                let rows = [
+                    ("Count", stats.count().to_string()),
+                    ("NaN", stats.nan_count().to_string()),
+                    ("Min", format!("{:.4}", stats.min())),
+                    ("Max", format!("{:.4}", stats.max())),
+                    ("Mean", format!("{:.4}", stats.mean())),
+                    ("Std Dev", format!("{:.4}", stats.std_dev())),
+                    ("2%", format!("{:.4}", stats.percentile(2.0))),
+                    ("Median", format!("{:.4}", stats.median())),
+                    ("98%", format!("{:.4}", stats.percentile(98.0))),
+                ];
+                for (name, value) in rows {
+                    ui.label(name);
+                    ui.label(value);
+                    ui.end_row();
+                }
+            });
+
+            ui.separator();
+
+            let width = ui.available_width();
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(width, 60.0), egui::Sense::hover());
+            let peak = histogram.counts.iter().copied().max().unwrap_or(0).max(1);
+            let bar_width = width / histogram.counts.len() as f32;
+            for (i, &count) in histogram.counts.iter().enumerate() {
+                let x = rect.min.x + bar_width * i as f32;
+                let height = rect.height() * count as f32 / peak as f32;
+                ui.painter().rect_filled(
+                    egui::Rect::from_min_max(
+                        egui::pos2(x, rect.max.y - height),
+                        egui::pos2(x + bar_width, rect.max.y),
+                    ),
+                    0.0,
+                    egui::Color32::from_rgb(90, 140, 200),
+                );
+            }
+            ui.painter()
+                .rect_stroke(rect, 0.0, (1.0, egui::Color32::from_gray(100)));
+        });
+}
+
 /// Control panel for visualization settings
 pub struct ControlPanel {
     pub point_size: f32,
@@ -96,8 +280,13 @@ pub struct ControlPanel {
     pub dataset_index: usize,
     pub background_color: [f32; 3],
     pub colormap_index: usize,
+    /// Metadata field chosen in the "Color By" selector
     pub metadata_field: String,
     pub use_log_scale: bool,
+    /// Map the 2nd-98th percentile instead of min/max onto the colormap
+    pub robust_range: bool,
+    /// Light points using their normals
+    pub lit_shading: bool,
 }
 
 impl Default for ControlPanel {
@@ -110,13 +299,24 @@ impl Default for ControlPanel {
             colormap_index: 0,
             metadata_field: String::new(),
             use_log_scale: false,
+            robust_range: false,
+            lit_shading: false,
         }
     }
 }
 
 impl ControlPanel {
     /// Draw the control panel UI
-    pub fn show(&mut self, ctx: &egui::Context, dataset_names: &[&str]) -> bool {
+    ///
+    /// The "Color By" selector lists the metadata fields of `cloud` by
+    /// their [`FieldInfo`](crate::data::FieldInfo) labels, with the other
+    /// attributes as tooltips.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        dataset_names: &[&str],
+        cloud: Option<&PointCloud>,
+    ) -> bool {
         let mut changed = false;
 
         egui::Window::new("🎛️ Controls")
@@ -144,7 +344,9 @@ impl ControlPanel {
                     ui.label("Dataset:");
                     let old_index = self.dataset_index;
                     egui::ComboBox::from_label("")
-                        .selected_text(dataset_names[self.dataset_index.min(dataset_names.len() - 1)])
+                        .selected_text(
+                            dataset_names[self.dataset_index.min(dataset_names.len() - 1)],
+                        )
                         .show_ui(ui, |ui| {
                             for (i, &name) in dataset_names.iter().enumerate() {
                                 ui.selectable_value(&mut self.dataset_index, i, name);
@@ -158,6 +360,41 @@ impl ControlPanel {
 
                 ui.separator();
 
+                // Field selector
+                if let Some(cloud) = cloud {
+                    let mut keys = cloud.metadata_keys();
+                    keys.sort();
+                    if !keys.is_empty() {
+                        ui.label("Color By:");
+                        let selected = if cloud.metadata(&self.metadata_field).is_some() {
+                            cloud.field_label(&self.metadata_field)
+                        } else {
+                            "Choose a field".to_string()
+                        };
+                        let old_field = self.metadata_field.clone();
+                        egui::ComboBox::from_id_source("field_select")
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                for key in keys {
+                                    let response = ui.selectable_value(
+                                        &mut self.metadata_field,
+                                        key.clone(),
+                                        cloud.field_label(key),
+                                    );
+                                    if let Some(info) = cloud.field_info(key) {
+                                        response.on_hover_text(info.tooltip(key));
+                                    }
+                                }
+                            });
+
+                        if self.metadata_field != old_field {
+                            changed = true;
+                        }
+
+                        ui.separator();
+                    }
+                }
+
                 // Colormap selector
                 ui.label("Colormap:");
                 let colormap_names = ["Viridis", "Plasma", "Inferno", "Turbo"];
@@ -184,6 +421,21 @@ impl ControlPanel {
                     changed = true;
                 }
 
+                // Robust range toggle
+                if ui
+                    .checkbox(&mut self.robust_range, "Robust Range (2-98%)")
+                    .changed()
+                {
+                    changed = true;
+                }
+
+                ui.separator();
+
+                // Shading toggle
+                if ui.checkbox(&mut self.lit_shading, "Lit Shading").changed() {
+                    changed = true;
+                }
+
                 ui.separator();
 
                 // Grid toggle
@@ -216,7 +468,7 @@ impl ControlPanel {
 
     /// Draw a colormap preview strip
     fn draw_colormap_preview(&self, ui: &mut egui::Ui, colormap_index: usize) {
-        use crate::color::{Colormap, Viridis, Plasma, Inferno, Turbo};
+        use crate::color::{Colormap, Inferno, Plasma, Turbo, Viridis};
 
         let colormap: &dyn Colormap = match colormap_index {
             0 => &Viridis,
@@ -228,10 +480,7 @@ impl ControlPanel {
 
         let height = 20.0;
         let width = ui.available_width();
-        let (rect, _) = ui.allocate_exact_size(
-            egui::vec2(width, height),
-            egui::Sense::hover(),
-        );
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(width, height), egui::Sense::hover());
 
         if ui.is_rect_visible(rect) {
             let painter = ui.painter();
@@ -275,3 +524,128 @@ impl ControlPanel {
         }
     }
 }
+
+/// Playback state and controls for a [`TimeSeries`]
+pub struct Timeline {
+    /// Advance the time every frame
+    pub playing: bool,
+    /// Playback speed in series frames per second
+    pub speed: f32,
+    /// Start over after the last frame instead of stopping
+    pub looping: bool,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Self {
+            playing: false,
+            speed: 10.0,
+            looping: true,
+        }
+    }
+}
+
+impl Timeline {
+    /// Move a series forward by `dt` seconds of wall time while playing
+    ///
+    /// Time advances by the average frame spacing `speed` times per second.
+    /// Returns true if the series time changed.
+    pub fn advance(&mut self, series: &mut TimeSeries, dt: f32) -> bool {
+        let Some((start, end)) = series.time_range() else {
+            return false;
+        };
+        if !self.playing || end <= start {
+            return false;
+        }
+
+        let spacing = (end - start) / (series.frame_count() - 1) as f64;
+        let mut time = series.time() + (dt * self.speed) as f64 * spacing;
+        if time > end {
+            if self.looping {
+                time = start + (time - end) % (end - start);
+            } else {
+                time = end;
+                self.playing = false;
+            }
+        }
+        series.set_time(time);
+        true
+    }
+
+    /// Draw play/pause, step, speed and scrub controls
+    ///
+    /// Returns true if the series time or interpolation changed.
+    pub fn show(&mut self, ctx: &egui::Context, series: &mut TimeSeries) -> bool {
+        let Some((start, end)) = series.time_range() else {
+            return false;
+        };
+        let old_time = series.time();
+        let old_interpolation = series.interpolation();
+
+        egui::Window::new(format!("⏱ {}", series.name()))
+            .id(egui::Id::new("timeline_panel"))
+            .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -10.0])
+            .default_width(420.0)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let index = series.current_index();
+                let last = series.frame_count() - 1;
+
+                ui.horizontal(|ui| {
+                    if ui.button("⏮").on_hover_text("First frame").clicked() {
+                        series.set_frame(0);
+                    }
+                    if ui.button("⏪").on_hover_text("Previous frame").clicked() {
+                        // Between frames, step back to the current one first
+                        if series.time() > series.frame_times()[index] {
+                            series.set_frame(index);
+                        } else {
+                            series.set_frame(index.saturating_sub(1));
+                        }
+                    }
+                    let label = if self.playing { "⏸" } else { "▶" };
+                    if ui.button(label).on_hover_text("Play/pause").clicked() {
+                        self.playing = !self.playing;
+                        if self.playing && series.time() >= end {
+                            series.set_frame(0);
+                        }
+                    }
+                    if ui.button("⏩").on_hover_text("Next frame").clicked() {
+                        series.set_frame((index + 1).min(last));
+                    }
+                    if ui.button("⏭").on_hover_text("Last frame").clicked() {
+                        series.set_frame(last);
+                    }
+                    ui.monospace(format!("Frame {} / {}", index + 1, last + 1));
+                });
+
+                let mut time = series.time();
+                if ui
+                    .add(egui::Slider::new(&mut time, start..=end).text("Time"))
+                    .changed()
+                {
+                    series.set_time(time);
+                }
+
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::Slider::new(&mut self.speed, 0.5..=120.0)
+                            .logarithmic(true)
+                            .suffix(" fps"),
+                    );
+                    ui.checkbox(&mut self.looping, "Loop");
+                    let mut interpolation = series.interpolation();
+                    if ui.checkbox(&mut interpolation, "Interpolate").changed() {
+                        series.set_interpolation(interpolation);
+                    }
+                });
+            });
+
+        // Keep repainting while playing so time advances without input events
+        if self.playing {
+            ctx.request_repaint();
+        }
+
+        series.time() != old_time || series.interpolation() != old_interpolation
+    }
+}
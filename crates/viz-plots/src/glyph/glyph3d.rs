@@ -0,0 +1,630 @@
+use super::{GlyphShape, GlyphStyle};
+use anyhow::Result;
+use bytemuck::{Pod, Zeroable};
+use glam::{DVec3, Vec3};
+use std::f32::consts::TAU;
+use viz_core::{
+    CameraUniforms, ColorScale, DataError, OrbitalCamera, PointCloud, RenderContext, Transform,
+};
+use wgpu::util::DeviceExt;
+
+/// Segments around the circumference of a glyph mesh
+const SEGMENTS: usize = 12;
+
+/// Vertex format for the glyph mesh, which points along +z with unit length
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct MeshVertex {
+    position: [f32; 3],
+    normal: [f32; 3],
+}
+
+impl MeshVertex {
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<MeshVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                // Position
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                // Normal
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+            ],
+        }
+    }
+}
+
+/// Per-glyph instance data
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct Instance {
+    origin: [f32; 3],
+    direction: [f32; 3],
+    color: [f32; 4],
+}
+
+impl Instance {
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Instance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[
+                // Origin
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                // Direction
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                // Color
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 6]>() as wgpu::BufferAddress,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+            ],
+        }
+    }
+}
+
+/// Vector field renderer drawing an instanced arrow or cone per point
+///
+/// Glyphs start at their point and follow a vector field of the cloud
+/// (see [`PointCloud::with_vectors`]). Length and color come from the
+/// vector magnitude as set by a [`GlyphStyle`], which can also thin dense
+/// fields to one glyph per cell. Points with a non-finite position or
+/// vector, and zero vectors, get no glyph.
+pub struct Glyph3D {
+    /// GPU pipeline for rendering
+    pipeline: wgpu::RenderPipeline,
+
+    /// Vertex buffer holding the glyph mesh
+    mesh_buffer: wgpu::Buffer,
+
+    /// Number of vertices in the mesh
+    mesh_len: u32,
+
+    /// Instance buffer, one instance per glyph
+    instance_buffer: wgpu::Buffer,
+
+    /// Number of instances the buffer can hold
+    capacity: usize,
+
+    /// Number of instances in use
+    instance_count: u32,
+
+    /// Uniform buffer for camera data
+    uniform_buffer: wgpu::Buffer,
+
+    /// Bind group for uniforms
+    bind_group: wgpu::BindGroup,
+
+    /// Vector field drawn
+    field: String,
+
+    /// Sizing, coloring and thinning settings
+    style: GlyphStyle,
+
+    /// Magnitudes at the ends of the colormap
+    magnitude_range: (f32, f32),
+
+    /// World-space origin the glyph positions are relative to
+    origin: DVec3,
+
+    /// Placement of the glyphs around their origin
+    transform: Transform,
+}
+
+impl Glyph3D {
+    /// Create a glyph plot of the vector field `field`
+    ///
+    /// # Errors
+    /// [`DataError::MissingField`] if the cloud has no such vector field,
+    /// and [`DataError::InvalidPercentile`] if the style's color range has
+    /// percentiles outside [0, 100] or with `low > high`
+    pub fn new(
+        context: &RenderContext,
+        point_cloud: &PointCloud,
+        field: &str,
+        style: GlyphStyle,
+    ) -> Result<Self> {
+        if point_cloud.vectors(field).is_none() {
+            return Err(DataError::MissingField(field.to_string()).into());
+        }
+
+        let mesh = glyph_mesh(style.shape());
+        let mesh_buffer = create_mesh_buffer(context, &mesh);
+        let capacity = point_cloud.positions().len().max(1);
+        let instance_buffer = create_instance_buffer(context, capacity);
+
+        // Create uniform buffer for camera
+        let uniform_buffer = context.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Glyph Camera Uniform Buffer"),
+            size: std::mem::size_of::<CameraUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // Create bind group layout
+        let bind_group_layout =
+            context
+                .device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("Glyph Bind Group Layout"),
+                    entries: &[wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    }],
+                });
+
+        // Create bind group
+        let bind_group = context
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Glyph Bind Group"),
+                layout: &bind_group_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                }],
+            });
+
+        // Load shader
+        let shader = context
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("Glyph Shader"),
+                source: wgpu::ShaderSource::Wgsl(
+                    include_str!("../../../../shaders/glyph.wgsl").into(),
+                ),
+            });
+
+        // Create pipeline layout
+        let pipeline_layout =
+            context
+                .device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some("Glyph Pipeline Layout"),
+                    bind_group_layouts: &[&bind_group_layout],
+                    push_constant_ranges: &[],
+                });
+
+        // Create render pipeline
+        let pipeline = context
+            .device
+            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("Glyph Pipeline"),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs_main",
+                    buffers: &[MeshVertex::desc(), Instance::desc()],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: context.config.format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: Some(wgpu::Face::Back),
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    unclipped_depth: false,
+                    conservative: false,
+                },
+                depth_stencil: None, // TODO: Add depth buffer in future
+                multisample: wgpu::MultisampleState {
+                    count: 1,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+            });
+
+        let mut glyphs = Self {
+            pipeline,
+            mesh_buffer,
+            mesh_len: mesh.len() as u32,
+            instance_buffer,
+            capacity,
+            instance_count: 0,
+            uniform_buffer,
+            bind_group,
+            field: field.to_string(),
+            style,
+            magnitude_range: (0.0, 0.0),
+            origin: DVec3::ZERO,
+            transform: Transform::identity(),
+        };
+        glyphs.upload(context, point_cloud)?;
+        Ok(glyphs)
+    }
+
+    /// Rebuild the glyphs from a cloud with the same vector field
+    ///
+    /// Reuses the instance buffer when the glyphs fit.
+    ///
+    /// # Errors
+    /// Same as [`Glyph3D::new`]; the previous glyphs are kept
+    pub fn upload(&mut self, context: &RenderContext, point_cloud: &PointCloud) -> Result<()> {
+        let (instances, range) = build_instances(point_cloud, &self.field, &self.style)?;
+
+        if instances.len() > self.capacity {
+            self.capacity = instances.len().max(self.capacity * 2);
+            self.instance_buffer = create_instance_buffer(context, self.capacity);
+        }
+        if !instances.is_empty() {
+            context
+                .queue
+                .write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&instances));
+        }
+
+        self.instance_count = instances.len() as u32;
+        self.magnitude_range = range;
+        self.origin = point_cloud.origin();
+        Ok(())
+    }
+
+    /// Change the style, rebuilding the glyphs from `point_cloud`
+    ///
+    /// # Errors
+    /// Same as [`Glyph3D::new`]; the previous style and glyphs are kept
+    pub fn set_style(
+        &mut self,
+        context: &RenderContext,
+        point_cloud: &PointCloud,
+        style: GlyphStyle,
+    ) -> Result<()> {
+        let previous = std::mem::replace(&mut self.style, style);
+        if let Err(err) = self.upload(context, point_cloud) {
+            self.style = previous;
+            return Err(err);
+        }
+
+        if self.style.shape() != previous.shape() {
+            let mesh = glyph_mesh(self.style.shape());
+            self.mesh_buffer = create_mesh_buffer(context, &mesh);
+            self.mesh_len = mesh.len() as u32;
+        }
+        Ok(())
+    }
+
+    /// Get the style
+    pub fn style(&self) -> &GlyphStyle {
+        &self.style
+    }
+
+    /// Get the name of the vector field drawn
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// Magnitudes at the ends of the colormap, e.g. for a colorbar
+    pub fn magnitude_range(&self) -> (f32, f32) {
+        self.magnitude_range
+    }
+
+    /// Update camera uniforms
+    ///
+    /// Glyphs are drawn relative to the camera, like [`Scatter3D`] points.
+    ///
+    /// [`Scatter3D`]: crate::Scatter3D
+    pub fn update_camera(&self, context: &RenderContext, camera: &OrbitalCamera) {
+        let uniforms =
+            CameraUniforms::camera_relative(camera, self.origin).with_transform(&self.transform);
+
+        context
+            .queue
+            .write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+    }
+
+    /// Set the transform that places the glyphs, applied around their
+    /// origin; takes effect at the next [`Glyph3D::update_camera`]
+    pub fn set_transform(&mut self, transform: Transform) {
+        self.transform = transform;
+    }
+
+    /// Get the transform that places the glyphs
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    /// Get the world-space origin of the uploaded glyphs
+    pub fn origin(&self) -> DVec3 {
+        self.origin
+    }
+
+    /// Render the glyphs
+    pub fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if self.instance_count == 0 {
+            return;
+        }
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.mesh_buffer.slice(..));
+        render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+        render_pass.draw(0..self.mesh_len, 0..self.instance_count);
+    }
+
+    /// Get number of glyphs drawn, after thinning
+    pub fn glyph_count(&self) -> u32 {
+        self.instance_count
+    }
+}
+
+/// Glyph instances for a vector field, and the resolved magnitude range
+fn build_instances(
+    point_cloud: &PointCloud,
+    field: &str,
+    style: &GlyphStyle,
+) -> Result<(Vec<Instance>, (f32, f32)), DataError> {
+    let vectors = point_cloud
+        .vectors(field)
+        .ok_or_else(|| DataError::MissingField(field.to_string()))?;
+
+    // Thin on a copy holding just the positions and the field
+    let thinned;
+    let (positions, vectors) = match style.spacing() {
+        Some(spacing) => {
+            thinned = PointCloud::new(point_cloud.positions().to_vec())
+                .with_vectors(field, vectors.to_vec())
+                .voxel_downsample(spacing);
+            (
+                thinned.positions(),
+                thinned.vectors(field).unwrap_or_default(),
+            )
+        }
+        None => (point_cloud.positions(), vectors),
+    };
+
+    let magnitudes: Vec<f32> = vectors
+        .iter()
+        .map(|v| if v.is_finite() { v.length() } else { f32::NAN })
+        .collect();
+    let (min, max) = style.color_range().resolve(&magnitudes, None)?;
+
+    let instances = positions
+        .iter()
+        .zip(vectors)
+        .zip(&magnitudes)
+        .filter(|((position, _), &magnitude)| position.is_finite() && magnitude > 0.0)
+        .map(|((position, vector), &magnitude)| {
+            let t = ColorScale::map(magnitude, min, max, style.scale_type());
+            let length = if style.scale_by_magnitude() && max > 0.0 {
+                style.length() * (magnitude / max).min(1.0)
+            } else {
+                style.length()
+            };
+            Instance {
+                origin: position.to_array(),
+                direction: (*vector / magnitude * length).to_array(),
+                color: style.colormap().sample(t).to_array(),
+            }
+        })
+        .collect();
+
+    Ok((instances, (min, max)))
+}
+
+/// Triangle list for a glyph pointing along +z with unit length
+fn glyph_mesh(shape: GlyphShape) -> Vec<MeshVertex> {
+    let mut mesh = Vec::new();
+    match shape {
+        GlyphShape::Arrow => {
+            disk(&mut mesh, 0.0, 0.04);
+            frustum(&mut mesh, (0.04, 0.0), (0.04, 0.7));
+            disk(&mut mesh, 0.7, 0.12);
+            frustum(&mut mesh, (0.12, 0.7), (0.0, 1.0));
+        }
+        GlyphShape::Cone => {
+            disk(&mut mesh, 0.0, 0.2);
+            frustum(&mut mesh, (0.2, 0.0), (0.0, 1.0));
+        }
+    }
+    mesh
+}
+
+/// Point on a ring of `radius` at height `z`, for segment `i`
+fn ring(radius: f32, z: f32, i: usize) -> Vec3 {
+    let angle = TAU * i as f32 / SEGMENTS as f32;
+    Vec3::new(radius * angle.cos(), radius * angle.sin(), z)
+}
+
+/// Disk facing -z at height `z`
+fn disk(mesh: &mut Vec<MeshVertex>, z: f32, radius: f32) {
+    let normal = Vec3::NEG_Z.to_array();
+    for i in 0..SEGMENTS {
+        for position in [
+            Vec3::new(0.0, 0.0, z),
+            ring(radius, z, i + 1),
+            ring(radius, z, i),
+        ] {
+            mesh.push(MeshVertex {
+                position: position.to_array(),
+                normal,
+            });
+        }
+    }
+}
+
+/// Side of a truncated cone between two `(radius, z)` rings
+fn frustum(mesh: &mut Vec<MeshVertex>, (r0, z0): (f32, f32), (r1, z1): (f32, f32)) {
+    // Normals are perpendicular to the slanted side
+    let normal = |i: usize| {
+        let radial = ring(1.0, 0.0, i);
+        (radial * (z1 - z0) + Vec3::Z * (r0 - r1))
+            .normalize()
+            .to_array()
+    };
+    for i in 0..SEGMENTS {
+        let quad = [
+            (ring(r0, z0, i), i),
+            (ring(r0, z0, i + 1), i + 1),
+            (ring(r1, z1, i + 1), i + 1),
+            (ring(r1, z1, i), i),
+        ];
+        for j in [0, 1, 2, 0, 2, 3] {
+            let (position, segment) = quad[j];
+            mesh.push(MeshVertex {
+                position: position.to_array(),
+                normal: normal(segment),
+            });
+        }
+    }
+}
+
+/// Vertex buffer holding a glyph mesh
+fn create_mesh_buffer(context: &RenderContext, mesh: &[MeshVertex]) -> wgpu::Buffer {
+    context
+        .device
+        .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Glyph Mesh Buffer"),
+            contents: bytemuck::cast_slice(mesh),
+            usage: wgpu::BufferUsages::VERTEX,
+        })
+}
+
+/// Instance buffer with room for `capacity` glyphs
+fn create_instance_buffer(context: &RenderContext, capacity: usize) -> wgpu::Buffer {
+    context.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Glyph Instance Buffer"),
+        size: (capacity * std::mem::size_of::<Instance>()) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use viz_core::{ColorRange, Colormap, Viridis};
+
+    fn cloud(positions: Vec<Vec3>, vectors: Vec<Vec3>) -> PointCloud {
+        PointCloud::new(positions).with_vectors("v", vectors)
+    }
+
+    #[test]
+    fn test_mesh_faces_outward() {
+        // Back-face culling needs counter-clockwise triangles seen from
+        // outside, i.e. winding that agrees with the stored normals
+        for shape in [GlyphShape::Arrow, GlyphShape::Cone] {
+            let mesh = glyph_mesh(shape);
+            assert_eq!(mesh.len() % 3, 0);
+            for triangle in mesh.chunks(3) {
+                let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(triangle[i].position));
+                let winding = (b - a).cross(c - a);
+                if winding.length() < 1e-6 {
+                    continue;
+                }
+                let normal: Vec3 = triangle.iter().map(|v| Vec3::from(v.normal)).sum();
+                assert!(winding.dot(normal) > 0.0, "{shape:?} triangle {triangle:?}");
+                assert!([a, b, c].iter().all(|p| (0.0..=1.0).contains(&p.z)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_magnitude_sets_length_and_color() {
+        let cloud = cloud(
+            vec![Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::Z, Vec3::NAN],
+            vec![
+                Vec3::new(2.0, 0.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+                Vec3::ZERO,
+                Vec3::new(f32::NAN, 0.0, 0.0),
+                Vec3::Z,
+            ],
+        );
+
+        // Zero and NaN vectors and unplaceable points get no glyph
+        let (instances, range) = build_instances(&cloud, "v", &GlyphStyle::new()).unwrap();
+        assert_eq!(range, (0.0, 2.0));
+        assert_eq!(instances.len(), 2);
+        assert_eq!(instances[0].origin, [0.0, 0.0, 0.0]);
+        assert_eq!(instances[0].direction, [1.0, 0.0, 0.0]);
+        assert_eq!(instances[0].color, Viridis.sample(1.0).to_array());
+        assert_eq!(instances[1].direction, [0.0, 0.5, 0.0]);
+        assert_eq!(instances[1].color, Viridis.sample(0.5).to_array());
+
+        // Magnitudes past a fixed range are clamped to the full length
+        let style = GlyphStyle::new()
+            .with_length(2.0)
+            .with_color_range(ColorRange::Fixed { min: 0.0, max: 1.0 });
+        let (instances, _) = build_instances(&cloud, "v", &style).unwrap();
+        assert_eq!(instances[0].direction, [2.0, 0.0, 0.0]);
+        assert_eq!(instances[1].direction, [0.0, 2.0, 0.0]);
+
+        let style = GlyphStyle::new().with_scale_by_magnitude(false);
+        let (instances, _) = build_instances(&cloud, "v", &style).unwrap();
+        assert_eq!(instances[1].direction, [0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_spacing_thins_glyphs() {
+        let cloud = cloud(
+            vec![
+                Vec3::new(0.1, 0.1, 0.1),
+                Vec3::new(0.3, 0.3, 0.3),
+                Vec3::new(5.2, 5.2, 5.2),
+            ],
+            vec![Vec3::X, Vec3::Y, Vec3::new(0.0, 0.0, 2.0)],
+        );
+
+        let style = GlyphStyle::new().with_spacing(1.0);
+        let (mut instances, range) = build_instances(&cloud, "v", &style).unwrap();
+        assert_eq!(instances.len(), 2);
+        assert_eq!(range.1, 2.0);
+
+        // The first cell holds the mean position and vector of its points
+        instances.sort_by(|a, b| a.origin[0].total_cmp(&b.origin[0]));
+        let origin = Vec3::from(instances[0].origin);
+        assert!((origin - Vec3::splat(0.2)).length() < 1e-6);
+        let direction = Vec3::from(instances[0].direction);
+        let mean = Vec3::new(0.5, 0.5, 0.0);
+        assert!((direction - mean / 2.0).length() < 1e-6);
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let cloud = cloud(vec![Vec3::ZERO], vec![Vec3::X]);
+
+        assert!(matches!(
+            build_instances(&cloud, "missing", &GlyphStyle::new()),
+            Err(DataError::MissingField(_))
+        ));
+
+        let style = GlyphStyle::new().with_color_range(ColorRange::Percentile {
+            low: 50.0,
+            high: 120.0,
+        });
+        assert!(matches!(
+            build_instances(&cloud, "v", &style),
+            Err(DataError::InvalidPercentile { .. })
+        ));
+    }
+}
@@ -0,0 +1,5 @@
+mod glyph3d;
+mod style;
+
+pub use glyph3d::Glyph3D;
+pub use style::{GlyphShape, GlyphStyle};
@@ -0,0 +1,137 @@
+use std::sync::Arc;
+use viz_core::{ColorRange, Colormap, ScaleType, Viridis};
+
+/// Mesh drawn for each vector by [`Glyph3D`](super::Glyph3D)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GlyphShape {
+    /// Thin shaft with a cone for a head
+    #[default]
+    Arrow,
+    /// Single cone, wide at the point and narrow at the tip
+    Cone,
+}
+
+/// How [`Glyph3D`](super::Glyph3D) sizes, colors and thins its glyphs
+///
+/// Glyph length and color both follow the vector magnitude: the top of the
+/// color range gets the full [length](GlyphStyle::with_length) and the top
+/// of the colormap, and magnitudes above it are clamped.
+#[derive(Clone)]
+pub struct GlyphStyle {
+    shape: GlyphShape,
+    length: f32,
+    scale_by_magnitude: bool,
+    colormap: Arc<dyn Colormap>,
+    scale_type: ScaleType,
+    range: ColorRange,
+    spacing: Option<f32>,
+}
+
+impl Default for GlyphStyle {
+    fn default() -> Self {
+        Self {
+            shape: GlyphShape::default(),
+            length: 1.0,
+            scale_by_magnitude: true,
+            colormap: Arc::new(Viridis),
+            scale_type: ScaleType::Linear,
+            range: ColorRange::MinMax,
+            spacing: None,
+        }
+    }
+}
+
+impl GlyphStyle {
+    /// Arrows up to one unit long, colored with viridis over the full
+    /// magnitude range, without thinning
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the glyph mesh
+    pub fn with_shape(mut self, shape: GlyphShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Set the length of the longest glyph, in data units
+    ///
+    /// # Panics
+    /// Panics if `length` is not positive
+    pub fn with_length(mut self, length: f32) -> Self {
+        assert!(length > 0.0, "Glyph length must be positive");
+        self.length = length;
+        self
+    }
+
+    /// Scale glyphs by magnitude (the default), or draw them all at full
+    /// length so only their color shows magnitude
+    pub fn with_scale_by_magnitude(mut self, scale_by_magnitude: bool) -> Self {
+        self.scale_by_magnitude = scale_by_magnitude;
+        self
+    }
+
+    /// Set the colormap for magnitudes
+    pub fn with_colormap(mut self, colormap: impl Colormap + 'static) -> Self {
+        self.colormap = Arc::new(colormap);
+        self
+    }
+
+    /// Set how magnitudes are scaled onto the colormap
+    pub fn with_scale_type(mut self, scale_type: ScaleType) -> Self {
+        self.scale_type = scale_type;
+        self
+    }
+
+    /// Set the magnitude range mapped onto the colormap and glyph length
+    pub fn with_color_range(mut self, range: ColorRange) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Draw at most one glyph per cube of side `spacing`, with the mean
+    /// position and vector of the points inside
+    ///
+    /// # Panics
+    /// Panics if `spacing` is not positive
+    pub fn with_spacing(mut self, spacing: f32) -> Self {
+        assert!(spacing > 0.0, "Glyph spacing must be positive");
+        self.spacing = Some(spacing);
+        self
+    }
+
+    /// Get the glyph mesh
+    pub fn shape(&self) -> GlyphShape {
+        self.shape
+    }
+
+    /// Get the length of the longest glyph
+    pub fn length(&self) -> f32 {
+        self.length
+    }
+
+    /// Check if glyphs are scaled by magnitude
+    pub fn scale_by_magnitude(&self) -> bool {
+        self.scale_by_magnitude
+    }
+
+    /// Get the colormap
+    pub fn colormap(&self) -> &dyn Colormap {
+        self.colormap.as_ref()
+    }
+
+    /// Get the color scale type
+    pub fn scale_type(&self) -> ScaleType {
+        self.scale_type
+    }
+
+    /// Get the magnitude range
+    pub fn color_range(&self) -> ColorRange {
+        self.range
+    }
+
+    /// Get the thinning cell size, if any
+    pub fn spacing(&self) -> Option<f32> {
+        self.spacing
+    }
+}
@@ -1,8 +1,12 @@
-pub mod scatter;
-pub mod line;
+pub mod glyph;
+pub mod graph;
 pub mod heatmap;
+pub mod line;
+pub mod scatter;
+pub mod scene;
 pub mod surface;
 pub mod volume;
-pub mod graph;
 
-pub use scatter::Scatter3D;
+pub use glyph::{Glyph3D, GlyphShape, GlyphStyle};
+pub use scatter::{Scatter3D, Shading};
+pub use scene::{Scene, SceneItem};
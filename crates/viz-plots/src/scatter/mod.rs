@@ -1,3 +1,3 @@
 mod scatter3d;
 
-pub use scatter3d::Scatter3D;
+pub use scatter3d::{Scatter3D, Shading};
@@ -1,7 +1,10 @@
 use anyhow::Result;
 use bytemuck::{Pod, Zeroable};
-use glam::{Vec3, Vec4};
-use viz_core::{CameraUniforms, Dataset, OrbitalCamera, PointCloud, RenderContext};
+use glam::{DVec3, Vec3, Vec4};
+use std::ops::Range;
+use viz_core::{
+    CameraUniforms, Dataset, OrbitalCamera, PointCloud, RenderContext, Selection, Transform,
+};
 use wgpu::util::DeviceExt;
 
 /// Vertex format for scatter plot points
@@ -10,9 +13,36 @@ use wgpu::util::DeviceExt;
 struct Vertex {
     position: [f32; 3],
     color: [f32; 4],
+    normal: [f32; 3],
 }
 
 impl Vertex {
+    /// Vertex for point `index`; points without a finite position get a
+    /// transparent vertex, which the fragment shader discards
+    fn from_point(cloud: &PointCloud, index: usize) -> Self {
+        let position = cloud.positions()[index];
+        if !position.is_finite() {
+            return Self::zeroed();
+        }
+
+        let color = match cloud.colors().map(|colors| colors[index]) {
+            Some(color) if color.is_finite() => color,
+            Some(_) => cloud.bad_value_color(),
+            None => Vec4::ONE,
+        };
+
+        let normal = match cloud.normals().map(|normals| normals[index]) {
+            Some(normal) if normal.is_finite() => normal,
+            _ => Vec3::ZERO,
+        };
+
+        Self {
+            position: position.to_array(),
+            color: color.to_array(),
+            normal: normal.to_array(),
+        }
+    }
+
     fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
         wgpu::VertexBufferLayout {
             array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
@@ -30,59 +60,101 @@ impl Vertex {
                     shader_location: 1,
                     format: wgpu::VertexFormat::Float32x4,
                 },
+                // Normal
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 7]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
             ],
         }
     }
 }
 
+/// How [`Scatter3D`] shades points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Shading {
+    /// Point colors as given
+    #[default]
+    Flat,
+    /// Diffuse lighting from a headlight at the camera, using the point
+    /// normals; both sides of a surface are lit, and points without a normal
+    /// stay flat
+    Lit,
+}
+
+/// Shading parameters for the fragment shader (matches WGSL)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct ShadingUniforms {
+    /// 1 for [`Shading::Lit`]
+    lit: u32,
+    /// Fraction of the color kept on surfaces facing away from the light
+    ambient: f32,
+    _padding: [f32; 2],
+}
+
+impl ShadingUniforms {
+    fn new(shading: Shading) -> Self {
+        Self {
+            lit: (shading == Shading::Lit) as u32,
+            ambient: 0.25,
+            _padding: [0.0; 2],
+        }
+    }
+}
+
 /// 3D scatter plot renderer
 pub struct Scatter3D {
     /// GPU pipeline for rendering
     pipeline: wgpu::RenderPipeline,
 
-    /// Vertex buffer containing point data
+    /// Vertex buffer containing point data, one vertex per point
     vertex_buffer: wgpu::Buffer,
 
+    /// Number of vertices the buffer can hold
+    capacity: usize,
+
     /// Uniform buffer for camera data
     uniform_buffer: wgpu::Buffer,
 
+    /// Uniform buffer for shading parameters
+    shading_buffer: wgpu::Buffer,
+
     /// Bind group for uniforms
     bind_group: wgpu::BindGroup,
 
-    /// Number of points to render
-    point_count: u32,
+    /// Number of vertices in use
+    vertex_count: u32,
+
+    /// World-space origin the vertex positions are relative to
+    origin: DVec3,
+
+    /// Placement of the points around their origin
+    transform: Transform,
+
+    /// Point indices with a non-finite position (transparent), ascending
+    hidden: Vec<usize>,
+
+    /// Vertex indices to draw instead of every point, and their count
+    selection: Option<(wgpu::Buffer, u32)>,
 
     /// Point size in pixels
     point_size: f32,
+
+    /// Current shading mode
+    shading: Shading,
+
+    /// Whether the point cloud had normals
+    has_normals: bool,
 }
 
 impl Scatter3D {
     /// Create a new 3D scatter plot from a point cloud
     pub fn new(context: &RenderContext, point_cloud: &PointCloud) -> Result<Self> {
-        // Convert point cloud to vertices
-        let mut vertices = Vec::with_capacity(point_cloud.len());
-
-        for i in 0..point_cloud.len() {
-            let position = point_cloud.positions()[i];
-            let color = point_cloud
-                .colors()
-                .map(|colors| colors[i])
-                .unwrap_or(Vec4::ONE);
-
-            vertices.push(Vertex {
-                position: position.to_array(),
-                color: color.to_array(),
-            });
-        }
-
         // Create vertex buffer
-        let vertex_buffer = context
-            .device
-            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("Scatter Vertex Buffer"),
-                contents: bytemuck::cast_slice(&vertices),
-                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-            });
+        let capacity = point_cloud.len().max(1);
+        let vertex_buffer = create_vertex_buffer(context, capacity);
 
         // Create uniform buffer for camera
         let uniform_buffer = context.device.create_buffer(&wgpu::BufferDescriptor {
@@ -92,33 +164,52 @@ impl Scatter3D {
             mapped_at_creation: false,
         });
 
+        // Create uniform buffer for shading
+        let shading = Shading::default();
+        let shading_buffer = context
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Shading Uniform Buffer"),
+                contents: bytemuck::bytes_of(&ShadingUniforms::new(shading)),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            });
+
         // Create bind group layout
+        let uniform_entry = |binding: u32| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
         let bind_group_layout =
             context
                 .device
                 .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                     label: Some("Camera Bind Group Layout"),
-                    entries: &[wgpu::BindGroupLayoutEntry {
-                        binding: 0,
-                        visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
-                        ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Uniform,
-                            has_dynamic_offset: false,
-                            min_binding_size: None,
-                        },
-                        count: None,
-                    }],
+                    entries: &[uniform_entry(0), uniform_entry(1)],
                 });
 
         // Create bind group
-        let bind_group = context.device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Camera Bind Group"),
-            layout: &bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: uniform_buffer.as_entire_binding(),
-            }],
-        });
+        let bind_group = context
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Camera Bind Group"),
+                layout: &bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: uniform_buffer.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: shading_buffer.as_entire_binding(),
+                    },
+                ],
+            });
 
         // Load shader
         let shader = context
@@ -178,39 +269,189 @@ impl Scatter3D {
                 multiview: None,
             });
 
-        Ok(Self {
+        let mut scatter = Self {
             pipeline,
             vertex_buffer,
+            capacity,
             uniform_buffer,
+            shading_buffer,
             bind_group,
-            point_count: vertices.len() as u32,
+            vertex_count: 0,
+            origin: DVec3::ZERO,
+            transform: Transform::identity(),
+            hidden: Vec::new(),
+            selection: None,
             point_size: 5.0,
-        })
+            shading,
+            has_normals: false,
+        };
+        scatter.upload(context, point_cloud);
+        Ok(scatter)
+    }
+
+    /// Replace the points with those of another cloud
+    ///
+    /// Reuses the vertex buffer when the cloud fits, so switching datasets
+    /// does not rebuild the pipeline. Clears any selection.
+    pub fn upload(&mut self, context: &RenderContext, point_cloud: &PointCloud) {
+        self.selection = None;
+        self.update(context, point_cloud, 0..point_cloud.len());
+    }
+
+    /// Upload the points in `range` after they changed
+    ///
+    /// For clouds that grow or are overwritten in place, such as a live
+    /// feed from [`PointCloud::extend`]; pass the range from
+    /// [`PointCloud::take_changed`]. Only those vertices are written, unless
+    /// the cloud outgrew the buffer, which is then reallocated with room to
+    /// spare and filled completely. A selection keeps its indices.
+    pub fn update(
+        &mut self,
+        context: &RenderContext,
+        point_cloud: &PointCloud,
+        range: Range<usize>,
+    ) {
+        let len = point_cloud.len();
+        let range = if len > self.capacity {
+            self.capacity = len.max(self.capacity * 2);
+            self.vertex_buffer = create_vertex_buffer(context, self.capacity);
+            0..len
+        } else {
+            range.start.min(len)..range.end.min(len)
+        };
+
+        let vertices: Vec<Vertex> = range
+            .clone()
+            .map(|i| Vertex::from_point(point_cloud, i))
+            .collect();
+        if !vertices.is_empty() {
+            let offset = (range.start * std::mem::size_of::<Vertex>()) as wgpu::BufferAddress;
+            context.queue.write_buffer(
+                &self.vertex_buffer,
+                offset,
+                bytemuck::cast_slice(&vertices),
+            );
+        }
+
+        // Points that cannot be placed are kept transparent and not counted
+        self.hidden.retain(|&i| i < len && !range.contains(&i));
+        self.hidden.extend(
+            range
+                .clone()
+                .filter(|&i| !point_cloud.positions()[i].is_finite()),
+        );
+        self.hidden.sort_unstable();
+
+        self.vertex_count = len as u32;
+        self.origin = point_cloud.origin();
+        self.has_normals = point_cloud.normals().is_some();
     }
 
     /// Update camera uniforms
+    ///
+    /// Points are drawn relative to the camera, with the offset between
+    /// the cloud's origin and the camera's world position computed in
+    /// double precision, so large coordinates do not jitter.
     pub fn update_camera(&self, context: &RenderContext, camera: &OrbitalCamera) {
-        let uniforms = CameraUniforms::new(
-            camera.view_projection_matrix(),
-            camera.position(),
-        );
+        let uniforms =
+            CameraUniforms::camera_relative(camera, self.origin).with_transform(&self.transform);
 
         context
             .queue
             .write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
     }
 
+    /// Set the transform that places the points, applied around their
+    /// origin; takes effect at the next [`Scatter3D::update_camera`]
+    pub fn set_transform(&mut self, transform: Transform) {
+        self.transform = transform;
+    }
+
+    /// Get the transform that places the points
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    /// Get the world-space origin of the uploaded points
+    pub fn origin(&self) -> DVec3 {
+        self.origin
+    }
+
+    /// Set the shading mode
+    ///
+    /// [`Shading::Lit`] only changes points that have a normal; see
+    /// [`Scatter3D::has_normals`].
+    pub fn set_shading(&mut self, context: &RenderContext, shading: Shading) {
+        self.shading = shading;
+        context.queue.write_buffer(
+            &self.shading_buffer,
+            0,
+            bytemuck::bytes_of(&ShadingUniforms::new(shading)),
+        );
+    }
+
+    /// Get the shading mode
+    pub fn shading(&self) -> Shading {
+        self.shading
+    }
+
+    /// Check if the points carry normals for lit shading
+    pub fn has_normals(&self) -> bool {
+        self.has_normals
+    }
+
+    /// Draw only the selected points, or every point with `None`
+    ///
+    /// Indices refer to the point cloud the plot was created from. The
+    /// vertex data stays on the GPU; only an index list is uploaded.
+    pub fn set_selection(&mut self, context: &RenderContext, selection: Option<&Selection>) {
+        self.selection = selection.map(|selection| {
+            let indices: Vec<u32> = selection
+                .indices()
+                .iter()
+                .filter(|&&i| i < self.vertex_count as usize)
+                .filter(|i| self.hidden.binary_search(i).is_err())
+                .map(|&i| i as u32)
+                .collect();
+
+            let buffer = context
+                .device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Scatter Selection Buffer"),
+                    contents: bytemuck::cast_slice(&indices),
+                    usage: wgpu::BufferUsages::INDEX,
+                });
+            (buffer, indices.len() as u32)
+        });
+    }
+
     /// Render the scatter plot
     pub fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
         render_pass.set_pipeline(&self.pipeline);
         render_pass.set_bind_group(0, &self.bind_group, &[]);
         render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-        render_pass.draw(0..self.point_count, 0..1);
+
+        match &self.selection {
+            Some((_, 0)) => {}
+            Some((indices, count)) => {
+                render_pass.set_index_buffer(indices.slice(..), wgpu::IndexFormat::Uint32);
+                render_pass.draw_indexed(0..*count, 0, 0..1);
+            }
+            None => render_pass.draw(0..self.vertex_count, 0..1),
+        }
     }
 
-    /// Get number of points
+    /// Get number of points with a finite position
     pub fn point_count(&self) -> u32 {
-        self.point_count
+        self.vertex_count - self.hidden.len() as u32
+    }
+
+    /// Get number of points drawn, after any selection
+    pub fn visible_count(&self) -> u32 {
+        match &self.selection {
+            Some((_, count)) => *count,
+            None => self.point_count(),
+        }
     }
 
     /// Get/set point size
@@ -222,3 +463,13 @@ impl Scatter3D {
         self.point_size = size;
     }
 }
+
+/// Vertex buffer with room for `capacity` points
+fn create_vertex_buffer(context: &RenderContext, capacity: usize) -> wgpu::Buffer {
+    context.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Scatter Vertex Buffer"),
+        size: (capacity * std::mem::size_of::<Vertex>()) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
@@ -0,0 +1,122 @@
+use super::SceneItem;
+use crate::scatter::Scatter3D;
+use anyhow::Result;
+use glam::DVec3;
+use viz_core::{Bounds3D, Dataset, OrbitalCamera, PointCloud, RenderContext};
+
+/// Several datasets drawn together
+///
+/// Each dataset gets its own [`Scatter3D`], a [`Transform`] placing it
+/// around its origin, a visibility toggle and a display name, e.g. to show
+/// a before/after pair of scans side by side. Bounds and camera framing
+/// cover every visible dataset.
+///
+/// [`Transform`]: viz_core::Transform
+#[derive(Default)]
+pub struct Scene {
+    items: Vec<SceneItem>,
+}
+
+impl Scene {
+    /// Create an empty scene
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a dataset, named after the cloud; returns its index
+    pub fn add(&mut self, context: &RenderContext, cloud: PointCloud) -> Result<usize> {
+        let plot = Scatter3D::new(context, &cloud)?;
+        self.items
+            .push(SceneItem::new(cloud.name().to_string(), cloud, plot));
+        Ok(self.items.len() - 1)
+    }
+
+    /// Remove a dataset and return it
+    ///
+    /// # Panics
+    /// Panics if `index` is out of range
+    pub fn remove(&mut self, index: usize) -> SceneItem {
+        self.items.remove(index)
+    }
+
+    /// Get all datasets in drawing order
+    pub fn items(&self) -> &[SceneItem] {
+        &self.items
+    }
+
+    /// Get all datasets mutably, e.g. to change every plot's point size
+    pub fn items_mut(&mut self) -> &mut [SceneItem] {
+        &mut self.items
+    }
+
+    /// Get a dataset by index
+    pub fn item(&self, index: usize) -> Option<&SceneItem> {
+        self.items.get(index)
+    }
+
+    /// Get a dataset by index mutably
+    pub fn item_mut(&mut self, index: usize) -> Option<&mut SceneItem> {
+        self.items.get_mut(index)
+    }
+
+    /// Get the number of datasets
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Check if the scene has no datasets
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// World-space origin that [`Scene::bounds`] are relative to (that of
+    /// the first dataset)
+    pub fn origin(&self) -> DVec3 {
+        self.items
+            .first()
+            .map_or(DVec3::ZERO, |item| item.cloud().origin())
+    }
+
+    /// Union of the bounds of the visible, non-empty datasets, relative to
+    /// [`Scene::origin`]
+    pub fn bounds(&self) -> Option<Bounds3D> {
+        let origin = self.origin();
+        Bounds3D::union_all(
+            self.items
+                .iter()
+                .filter(|item| item.is_visible() && !item.cloud().is_empty())
+                .map(|item| item.bounds_from(origin)),
+        )
+    }
+
+    /// Point the camera at every visible dataset
+    pub fn frame(&self, camera: &mut OrbitalCamera, padding: f32) {
+        if let Some(bounds) = self.bounds() {
+            camera.origin = self.origin();
+            camera.frame_bounds(bounds.min, bounds.max, padding);
+        }
+    }
+
+    /// Number of points drawn by the visible datasets
+    pub fn visible_count(&self) -> u32 {
+        self.items
+            .iter()
+            .filter(|item| item.is_visible())
+            .map(|item| item.plot().visible_count())
+            .sum()
+    }
+
+    /// Update the camera uniforms of every dataset
+    pub fn update_camera(&self, context: &RenderContext, camera: &OrbitalCamera) {
+        for item in &self.items {
+            item.plot().update_camera(context, camera);
+        }
+    }
+
+    /// Render the visible datasets
+    pub fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        for item in self.items.iter().filter(|item| item.is_visible()) {
+            item.plot().render(render_pass);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use crate::scatter::Scatter3D;
+use glam::DVec3;
+use viz_core::{Bounds3D, Dataset, PointCloud, RenderContext, Transform};
+
+/// A dataset in a [`Scene`](super::Scene) with its plot, transform and
+/// visibility
+pub struct SceneItem {
+    name: String,
+    cloud: PointCloud,
+    plot: Scatter3D,
+    visible: bool,
+}
+
+impl SceneItem {
+    pub(super) fn new(name: String, cloud: PointCloud, plot: Scatter3D) -> Self {
+        Self {
+            name,
+            cloud,
+            plot,
+            visible: true,
+        }
+    }
+
+    /// Get the display name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Set the display name
+    pub fn set_name(&mut self, name: impl Into<String>) {
+        self.name = name.into();
+    }
+
+    /// Get the point cloud
+    pub fn cloud(&self) -> &PointCloud {
+        &self.cloud
+    }
+
+    /// Replace the point cloud, re-uploading it to the existing plot
+    pub fn set_cloud(&mut self, context: &RenderContext, cloud: PointCloud) {
+        self.plot.upload(context, &cloud);
+        self.cloud = cloud;
+    }
+
+    /// Get the plot
+    pub fn plot(&self) -> &Scatter3D {
+        &self.plot
+    }
+
+    /// Get the plot mutably, e.g. to change point size or shading
+    pub fn plot_mut(&mut self) -> &mut Scatter3D {
+        &mut self.plot
+    }
+
+    /// Get the transform that places the dataset
+    pub fn transform(&self) -> Transform {
+        self.plot.transform()
+    }
+
+    /// Set the transform that places the dataset, applied around the
+    /// cloud's origin
+    pub fn set_transform(&mut self, transform: Transform) {
+        self.plot.set_transform(transform);
+    }
+
+    /// Check if the dataset is drawn
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Show or hide the dataset
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    /// Bounds of the transformed points, relative to `origin`
+    pub fn bounds_from(&self, origin: DVec3) -> Bounds3D {
+        self.cloud
+            .bounds()
+            .placed(&self.transform(), self.cloud.origin(), origin)
+    }
+}
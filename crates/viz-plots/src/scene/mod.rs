@@ -0,0 +1,5 @@
+mod collection;
+mod item;
+
+pub use collection::Scene;
+pub use item::SceneItem;
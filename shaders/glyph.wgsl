@@ -0,0 +1,79 @@
+// Glyph shaders for instanced vector arrows and cones
+
+// Camera uniforms passed from CPU
+struct CameraUniforms {
+    view_proj: mat4x4<f32>,  // Combined view-projection matrix
+    view_pos: vec3<f32>,      // Camera position in world space
+    _padding: f32,            // Alignment padding
+    offset: vec3<f32>,        // Added to positions (camera-relative rendering)
+    _offset_padding: f32,     // Alignment padding
+    model: mat4x4<f32>,       // Rotation and scale of the dataset
+}
+
+@group(0) @binding(0)
+var<uniform> camera: CameraUniforms;
+
+// Light kept on surfaces facing away from the light
+const AMBIENT: f32 = 0.3;
+
+// Glyph mesh vertex, pointing along +z with unit length
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+}
+
+// Per-glyph data
+struct InstanceInput {
+    @location(2) origin: vec3<f32>,    // Base of the glyph
+    @location(3) direction: vec3<f32>, // Glyph axis, scaled to its length
+    @location(4) color: vec4<f32>,
+}
+
+// Output from vertex shader to fragment shader
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) world_pos: vec3<f32>,
+    @location(2) normal: vec3<f32>,
+}
+
+// Vertex shader
+@vertex
+fn vs_main(vertex: VertexInput, instance: InstanceInput) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Orthonormal basis around the glyph axis
+    let size = length(instance.direction);
+    let axis = instance.direction / size;
+    var helper = vec3<f32>(0.0, 0.0, 1.0);
+    if (abs(axis.z) > 0.999) {
+        helper = vec3<f32>(1.0, 0.0, 0.0);
+    }
+    let u = normalize(cross(helper, axis));
+    let v = cross(axis, u);
+    let basis = mat3x3<f32>(u, v, axis);
+
+    // Orient and scale the mesh, place it with the dataset's transform, then
+    // shift it into the frame of view_proj
+    let local = instance.origin + basis * vertex.position * size;
+    let position = (camera.model * vec4<f32>(local, 1.0)).xyz + camera.offset;
+    out.clip_position = camera.view_proj * vec4<f32>(position, 1.0);
+
+    out.color = instance.color;
+    out.world_pos = position;
+    out.normal = (camera.model * vec4<f32>(basis * vertex.normal, 0.0)).xyz;
+
+    return out;
+}
+
+// Fragment shader
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Diffuse lighting from a headlight at the camera; back faces are
+    // culled, so only outward normals reach here
+    let light = normalize(camera.view_pos - in.world_pos);
+    let diffuse = max(dot(normalize(in.normal), light), 0.0);
+    let intensity = AMBIENT + (1.0 - AMBIENT) * diffuse;
+
+    return vec4<f32>(in.color.rgb * intensity, in.color.a);
+}
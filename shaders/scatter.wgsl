@@ -5,15 +5,29 @@ struct CameraUniforms {
     view_proj: mat4x4<f32>,  // Combined view-projection matrix
     view_pos: vec3<f32>,      // Camera position in world space
     _padding: f32,            // Alignment padding
+    offset: vec3<f32>,        // Added to positions (camera-relative rendering)
+    _offset_padding: f32,     // Alignment padding
+    model: mat4x4<f32>,       // Rotation and scale of the dataset
 }
 
 @group(0) @binding(0)
 var<uniform> camera: CameraUniforms;
 
+// Shading parameters
+struct ShadingUniforms {
+    lit: u32,                 // 1 for normal-based lighting
+    ambient: f32,             // Light kept on surfaces facing away
+    _padding: vec2<f32>,      // Alignment padding
+}
+
+@group(0) @binding(1)
+var<uniform> shading: ShadingUniforms;
+
 // Vertex input from vertex buffer
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) color: vec4<f32>,
+    @location(2) normal: vec3<f32>,
 }
 
 // Output from vertex shader to fragment shader
@@ -22,6 +36,7 @@ struct VertexOutput {
     @location(0) color: vec4<f32>,
     @location(1) world_pos: vec3<f32>,
     @location(2) distance: f32,
+    @location(3) normal: vec3<f32>,
 }
 
 // Vertex shader
@@ -29,17 +44,23 @@ struct VertexOutput {
 fn vs_main(in: VertexInput) -> VertexOutput {
     var out: VertexOutput;
 
-    // Transform position to clip space
-    out.clip_position = camera.view_proj * vec4<f32>(in.position, 1.0);
+    // Place the point with the dataset's transform, shift it into the frame
+    // of view_proj (relative to the camera when the offset is set), then
+    // transform to clip space
+    let position = (camera.model * vec4<f32>(in.position, 1.0)).xyz + camera.offset;
+    out.clip_position = camera.view_proj * vec4<f32>(position, 1.0);
 
     // Pass through color
     out.color = in.color;
 
     // Store world position for fragment shader
-    out.world_pos = in.position;
+    out.world_pos = position;
 
     // Calculate distance from camera for depth-based effects
-    out.distance = length(in.position - camera.view_pos);
+    out.distance = length(position - camera.view_pos);
+
+    // Rotate the normal with the dataset (zero when unknown)
+    out.normal = (camera.model * vec4<f32>(in.normal, 0.0)).xyz;
 
     return out;
 }
@@ -56,6 +77,14 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     var color = in.color;
     color.a *= fade;
 
+    // Two-sided diffuse lighting from a headlight at the camera
+    if (shading.lit == 1u && dot(in.normal, in.normal) > 0.0) {
+        let light = normalize(camera.view_pos - in.world_pos);
+        let diffuse = abs(dot(normalize(in.normal), light));
+        let intensity = shading.ambient + (1.0 - shading.ambient) * diffuse;
+        color = vec4<f32>(color.rgb * intensity, color.a);
+    }
+
     // Discard fully transparent fragments
     if (color.a < 0.01) {
         discard;